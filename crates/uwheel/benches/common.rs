@@ -11,6 +11,7 @@ pub struct SumAggregator;
 
 impl Aggregator for SumAggregator {
     const IDENTITY: Self::PartialAggregate = 0;
+    const TAG: &'static str = "SumAggregator";
 
     type Input = u32;
     type PartialAggregate = u32;
@@ -92,6 +92,7 @@ pub struct AllAggregator;
 
 impl Aggregator for AllAggregator {
     const IDENTITY: Self::PartialAggregate = AggState::identity();
+    const TAG: &'static str = "AllAggregator";
     type Input = f64;
     type Aggregate = AggState;
     type PartialAggregate = AggState;
@@ -119,6 +120,14 @@ impl Aggregator for AllAggregator {
     fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
         a
     }
+    #[inline]
+    fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+        if a.count == 0 {
+            None
+        } else {
+            Some(a.avg())
+        }
+    }
 }
 
 #[cfg(test)]
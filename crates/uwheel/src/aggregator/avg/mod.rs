@@ -8,6 +8,7 @@ macro_rules! avg_impl {
 
         impl Aggregator for $struct {
             const IDENTITY: Self::PartialAggregate = (0 as $type, 0 as $type);
+            const TAG: &'static str = stringify!($struct);
             type Input = $type;
             type MutablePartialAggregate = $pa;
             type Aggregate = $type;
@@ -41,6 +42,21 @@ macro_rules! avg_impl {
             fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
                 a.0 / a.1
             }
+
+            #[inline]
+            fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+                if a.1 == 0 as $type {
+                    None
+                } else {
+                    Some(a.0 as f64 / a.1 as f64)
+                }
+            }
+
+            #[inline]
+            fn exact_inverse() -> bool {
+                // floats lose precision when removing partial aggregates
+                <$type>::MIN == 0 as $type
+            }
             #[inline]
             fn combine_inverse() -> Option<fn(Self::PartialAggregate, Self::PartialAggregate) -> Self::PartialAggregate> {
                 Some(|a, b| {
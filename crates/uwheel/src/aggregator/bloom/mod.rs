@@ -0,0 +1,349 @@
+use crate::aggregator::{
+    hash::{HashKey, HasherSignature, SketchError, SketchHasher, XxHash64},
+    Aggregator,
+    InputBounds,
+    PartialAggregateType,
+};
+use core::marker::PhantomData;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+#[cfg(feature = "serde")]
+use serde_big_array::BigArray;
+
+/// A fixed-size Bloom filter used as the partial aggregate of [BloomAggregator]
+///
+/// The filter consists of `WORDS * 64` bits and sets `K` bits per key.
+/// The hash function used to build the filter is stored alongside the bits so that
+/// filters built with different hashers are never merged through [BloomFilter::try_merge].
+/// Deserializing a filter that was built with another hasher than `H` fails with [SketchError::IncompatibleHasher].
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound = "", try_from = "SerializedBloomFilter<WORDS>")
+)]
+#[derive(Debug, Clone, Copy)]
+pub struct BloomFilter<const WORDS: usize, const K: usize, H: SketchHasher = XxHash64> {
+    #[cfg_attr(feature = "serde", serde(with = "BigArray"))]
+    bits: [u64; WORDS],
+    hasher: HasherSignature,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    _marker: PhantomData<H>,
+}
+
+/// Serialized form of a [BloomFilter] whose hasher is validated before it is accepted
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct SerializedBloomFilter<const WORDS: usize> {
+    #[serde(with = "BigArray")]
+    bits: [u64; WORDS],
+    hasher: HasherSignature,
+}
+
+#[cfg(feature = "serde")]
+impl<const WORDS: usize, const K: usize, H: SketchHasher> TryFrom<SerializedBloomFilter<WORDS>>
+    for BloomFilter<WORDS, K, H>
+{
+    type Error = SketchError;
+
+    fn try_from(filter: SerializedBloomFilter<WORDS>) -> Result<Self, Self::Error> {
+        H::SIGNATURE.ensure_compatible(&filter.hasher)?;
+        Ok(Self {
+            bits: filter.bits,
+            hasher: filter.hasher,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<const WORDS: usize, const K: usize, H: SketchHasher> Default for BloomFilter<WORDS, K, H> {
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<const WORDS: usize, const K: usize, H: SketchHasher> BloomFilter<WORDS, K, H> {
+    /// Creates an empty Bloom filter
+    pub const fn empty() -> Self {
+        assert!(WORDS > 0, "BloomFilter requires at least one word");
+        assert!(K > 0, "BloomFilter requires at least one hash function");
+        Self {
+            bits: [0; WORDS],
+            hasher: H::SIGNATURE,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Returns the number of bits in the filter
+    pub const fn num_bits() -> usize {
+        WORDS * 64
+    }
+
+    /// Returns the number of bits set per inserted key
+    pub const fn num_hashes() -> usize {
+        K
+    }
+
+    /// Returns the size of the filter in bytes
+    pub const fn size_bytes() -> usize {
+        core::mem::size_of::<Self>()
+    }
+
+    /// Returns the signature of the hasher that was used to build the filter
+    pub fn hasher(&self) -> HasherSignature {
+        self.hasher
+    }
+
+    /// Inserts a key into the filter
+    #[inline]
+    pub fn insert<Key: HashKey + ?Sized>(&mut self, key: &Key) {
+        for bit in Self::bit_indexes(key) {
+            self.bits[bit / 64] |= 1 << (bit % 64);
+        }
+    }
+
+    /// Returns `true` if the key may have been inserted and `false` if it was definitely not
+    #[inline]
+    pub fn contains<Key: HashKey + ?Sized>(&self, key: &Key) -> bool {
+        Self::bit_indexes(key).all(|bit| self.bits[bit / 64] & (1 << (bit % 64)) != 0)
+    }
+
+    /// Returns the number of bits that are set
+    pub fn count_ones(&self) -> usize {
+        self.bits.iter().map(|w| w.count_ones() as usize).sum()
+    }
+
+    /// Returns `true` if no key has been inserted into the filter
+    pub fn is_empty(&self) -> bool {
+        self.bits.iter().all(|w| *w == 0)
+    }
+
+    /// Estimates the false-positive rate of the filter based on its current fill ratio
+    pub fn false_positive_rate(&self) -> f64 {
+        let fill = self.count_ones() as f64 / Self::num_bits() as f64;
+        (0..K).fold(1.0, |acc, _| acc * fill)
+    }
+
+    /// Merges another filter into this one
+    ///
+    /// Returns an error if the filters were built using different hashers.
+    pub fn try_merge(&mut self, other: &Self) -> Result<(), SketchError> {
+        self.hasher.ensure_compatible(&other.hasher)?;
+        self.union(other);
+        Ok(())
+    }
+
+    #[inline]
+    fn union(&mut self, other: &Self) {
+        for (a, b) in self.bits.iter_mut().zip(other.bits.iter()) {
+            *a |= *b;
+        }
+    }
+
+    // Derives K bit positions from a single 64-bit hash using double hashing (Kirsch-Mitzenmacher)
+    #[inline]
+    fn bit_indexes<Key: HashKey + ?Sized>(key: &Key) -> impl Iterator<Item = usize> {
+        let hash = H::hash(key);
+        let h1 = hash as u32 as u64;
+        let h2 = (hash >> 32) | 1;
+        (0..K as u64)
+            .map(move |i| (h1.wrapping_add(i.wrapping_mul(h2)) % Self::num_bits() as u64) as usize)
+    }
+}
+
+impl<const WORDS: usize, const K: usize, H: SketchHasher> PartialAggregateType
+    for BloomFilter<WORDS, K, H>
+{
+}
+
+/// Bloom filter aggregator for approximate membership queries over time ranges
+///
+/// Each slot holds a [BloomFilter] of `WORDS * 64` bits with `K` hash functions.
+/// Filters are combined through a bitwise OR which makes range queries exact with respect
+/// to the filters but not invertible. Filters built with a different hasher than `H` are rejected
+/// when deserialized, so all filters of a wheel share the same hasher.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::bloom::BloomAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<BloomAggregator<u64, 16, 4>> = RwWheel::new(0);
+/// wheel.insert(Entry::new(42, 1000));
+/// wheel.advance(2.seconds());
+///
+/// let filter = wheel.read().interval(2.seconds()).unwrap();
+/// assert!(filter.contains(&42u64));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BloomAggregator<Key, const WORDS: usize = 16, const K: usize = 4, H = XxHash64>(
+    PhantomData<(Key, H)>,
+);
+
+impl<Key, const WORDS: usize, const K: usize, H> Default for BloomAggregator<Key, WORDS, K, H> {
+    // have to implement manually as Key may not implement Default
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<Key, const WORDS: usize, const K: usize, H> Aggregator for BloomAggregator<Key, WORDS, K, H>
+where
+    Key: HashKey + InputBounds + 'static,
+    H: SketchHasher,
+{
+    const IDENTITY: Self::PartialAggregate = BloomFilter::empty();
+    const TAG: &'static str = "BloomAggregator";
+
+    type Input = Key;
+    type MutablePartialAggregate = BloomFilter<WORDS, K, H>;
+    type PartialAggregate = BloomFilter<WORDS, K, H>;
+    type Aggregate = BloomFilter<WORDS, K, H>;
+
+    fn qualified_tag() -> String {
+        format!(
+            "{}<{}, {}, {}, {}>",
+            Self::TAG,
+            core::mem::size_of::<Key>(),
+            WORDS,
+            K,
+            H::SIGNATURE
+        )
+    }
+
+    #[inline]
+    fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+        let mut filter = BloomFilter::empty();
+        filter.insert(&input);
+        filter
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        a.insert(&input);
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(mut a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        a.union(&b);
+        a
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a
+    }
+
+    fn format_partial(partial: &Self::PartialAggregate) -> String {
+        format!(
+            "BloomFilter({}/{} bits set)",
+            partial.count_ones(),
+            BloomFilter::<WORDS, K, H>::num_bits()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregator::hash::SipHash24, duration::NumericalDuration, Entry, RwWheel};
+
+    type Bloom = BloomAggregator<u64, 256, 7>;
+
+    #[test]
+    fn bloom_size_reflects_parameters() {
+        assert_eq!(BloomFilter::<256, 7>::num_bits(), 16384);
+        assert_eq!(BloomFilter::<256, 7>::num_hashes(), 7);
+        assert!(BloomFilter::<256, 7>::size_bytes() >= 256 * 8);
+        assert!(BloomFilter::<16, 4>::size_bytes() < BloomFilter::<256, 7>::size_bytes());
+    }
+
+    #[test]
+    fn bloom_range_membership() {
+        let mut wheel: RwWheel<Bloom> = RwWheel::new(0);
+        let keys = 1000u64;
+
+        // spread keys over 10 seconds
+        for key in 0..keys {
+            wheel.insert(Entry::new(key, (key % 10) * 1000));
+        }
+        wheel.advance(10.seconds());
+
+        let filter = wheel.read().interval(10.seconds()).unwrap();
+
+        // no false negatives
+        for key in 0..keys {
+            assert!(filter.contains(&key));
+        }
+
+        // false positives within bound for absent keys
+        let absent = 10_000u64;
+        let false_positives = (keys..keys + absent)
+            .filter(|key| filter.contains(key))
+            .count();
+        let observed = false_positives as f64 / absent as f64;
+
+        // expected rate for n=1000, m=16384, k=7 is about 0.0006
+        assert!(observed < 0.01, "false-positive rate {observed} too high");
+        assert!(filter.false_positive_rate() < 0.01);
+
+        // a sub-range only contains the keys inserted within it
+        let filter = wheel.read().interval(1.seconds()).unwrap();
+        assert!(filter.contains(&9u64));
+        assert!(filter.contains(&999u64));
+    }
+
+    #[test]
+    fn bloom_combine_is_idempotent() {
+        let a = Bloom::lift(1);
+        assert_eq!(Bloom::combine(a, a).count_ones(), a.count_ones());
+        assert!(Bloom::IDENTITY.is_empty());
+        assert!(!Bloom::invertible());
+    }
+
+    #[test]
+    fn bloom_merge_requires_matching_hasher() {
+        let mut a = BloomAggregator::<u64, 16, 4, XxHash64<1>>::lift(1);
+        let b = BloomAggregator::<u64, 16, 4, XxHash64<1>>::lift(2);
+        assert!(a.try_merge(&b).is_ok());
+        assert!(a.contains(&1u64) && a.contains(&2u64));
+
+        // simulate a filter deserialized from a sketch built with another hasher
+        let mut foreign = b;
+        foreign.hasher = SipHash24::<1>::SIGNATURE;
+        assert_eq!(
+            a.try_merge(&foreign),
+            Err(SketchError::IncompatibleHasher {
+                expected: XxHash64::<1>::SIGNATURE,
+                found: SipHash24::<1>::SIGNATURE,
+            })
+        );
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn bloom_deserialize_rejects_mismatched_hasher() {
+        let filter = BloomAggregator::<u64, 16, 4, XxHash64<1>>::lift(1);
+        let serialized = bincode::serialize(&filter).unwrap();
+
+        let err = bincode::deserialize::<BloomFilter<16, 4, XxHash64<2>>>(&serialized)
+            .err()
+            .expect("deserialization with a different hasher should fail");
+        assert_eq!(
+            err.to_string(),
+            SketchError::IncompatibleHasher {
+                expected: XxHash64::<2>::SIGNATURE,
+                found: XxHash64::<1>::SIGNATURE,
+            }
+            .to_string()
+        );
+
+        let filter: BloomFilter<16, 4, XxHash64<1>> = bincode::deserialize(&serialized).unwrap();
+        assert!(filter.contains(&1u64));
+    }
+}
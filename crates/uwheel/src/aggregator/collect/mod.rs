@@ -0,0 +1,230 @@
+use crate::aggregator::{Aggregator, InputBounds, PartialAggregateType};
+use core::marker::PhantomData;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+#[cfg(feature = "serde")]
+use serde_big_array::BigArray;
+
+/// A bounded list of values used as the partial aggregate of [CollectAggregator]
+///
+/// Holds at most `CAP` values in the order they were collected. Values that do not fit
+/// are dropped and the list is marked as truncated (see [Collected::is_truncated]).
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>"
+    ))
+)]
+#[derive(Debug, Clone, Copy)]
+pub struct Collected<T: Copy, const CAP: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "BigArray"))]
+    values: [Option<T>; CAP],
+    len: usize,
+    truncated: bool,
+}
+
+impl<T: Copy, const CAP: usize> Default for Collected<T, CAP> {
+    // have to implement manually as arrays only implement Default up to 32 elements
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: Copy, const CAP: usize> Collected<T, CAP> {
+    /// Creates an empty list
+    pub const fn empty() -> Self {
+        Self {
+            values: [None; CAP],
+            len: 0,
+            truncated: false,
+        }
+    }
+
+    /// Returns the number of collected values
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if no value has been collected
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if values were dropped since the list reached its capacity of `CAP` values
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
+
+    /// Returns an iterator over the collected values from oldest to most recent
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.values[..self.len].iter().flatten().copied()
+    }
+
+    /// Returns the collected values from oldest to most recent
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+
+    // Appends a value or marks the list as truncated if it is full
+    #[inline]
+    fn push(&mut self, value: T) {
+        if self.len < CAP {
+            self.values[self.len] = Some(value);
+            self.len += 1;
+        } else {
+            self.truncated = true;
+        }
+    }
+
+    // Appends all values of other after the values of self
+    #[inline]
+    fn extend(&mut self, other: &Self) {
+        for value in other.iter() {
+            self.push(value);
+        }
+        self.truncated |= other.truncated;
+    }
+}
+
+impl<T: InputBounds, const CAP: usize> PartialAggregateType for Collected<T, CAP> {}
+
+/// Aggregator that collects the raw values of a range instead of aggregating them
+///
+/// Useful for audit trails where the actual values within a small range are of interest.
+/// Each partial aggregate holds up to `CAP` values and combining partials concatenates them
+/// from oldest to most recent. Values beyond `CAP` are dropped and reported through [Collected::is_truncated].
+///
+/// Note that every slot of the wheel stores `CAP` values, so this aggregator should only be
+/// used with a small `CAP` and for queries over short ranges.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::collect::CollectAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<CollectAggregator<u32, 8>> = RwWheel::new(0);
+/// wheel.insert(Entry::new(1, 1000));
+/// wheel.insert(Entry::new(2, 1500));
+/// wheel.insert(Entry::new(3, 2000));
+/// wheel.advance(3.seconds());
+///
+/// let collected = wheel.read().interval_and_lower(2.seconds()).unwrap();
+/// assert_eq!(collected, vec![1, 2, 3]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct CollectAggregator<T, const CAP: usize>(PhantomData<T>);
+
+impl<T, const CAP: usize> Default for CollectAggregator<T, CAP> {
+    // have to implement manually as T may not implement Default
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, const CAP: usize> Aggregator for CollectAggregator<T, CAP>
+where
+    T: InputBounds + 'static,
+{
+    const IDENTITY: Self::PartialAggregate = Collected::empty();
+    const TAG: &'static str = "CollectAggregator";
+
+    type Input = T;
+    type MutablePartialAggregate = Collected<T, CAP>;
+    type PartialAggregate = Collected<T, CAP>;
+    type Aggregate = Vec<T>;
+
+    fn qualified_tag() -> String {
+        format!("{}<{}, {}>", Self::TAG, core::mem::size_of::<T>(), CAP)
+    }
+
+    #[inline]
+    fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+        let mut collected = Collected::empty();
+        collected.push(input);
+        collected
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        a.push(input);
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(mut a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        a.extend(&b);
+        a
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a.to_vec()
+    }
+
+    fn commutative() -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{duration::NumericalDuration, Entry, RwWheel, WheelRange};
+
+    #[test]
+    fn collect_preserves_time_order() {
+        let mut wheel: RwWheel<CollectAggregator<u64, 16>> = RwWheel::new(0);
+        // insert out of order across seconds, in order within a second
+        for second in [3u64, 0, 2, 1, 4] {
+            wheel.insert(Entry::new(second * 10, second * 1000));
+            wheel.insert(Entry::new(second * 10 + 1, second * 1000 + 500));
+        }
+        wheel.advance(5.seconds());
+
+        let collected = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(1000, 4000))
+            .unwrap();
+        assert_eq!(collected.to_vec(), vec![10, 11, 20, 21, 30, 31]);
+        assert!(!collected.is_truncated());
+
+        assert_eq!(
+            wheel.read().interval_and_lower(5.seconds()),
+            Some(vec![0, 1, 10, 11, 20, 21, 30, 31, 40, 41])
+        );
+    }
+
+    #[test]
+    fn collect_drops_beyond_capacity() {
+        let mut wheel: RwWheel<CollectAggregator<u64, 4>> = RwWheel::new(0);
+        for second in 0..3u64 {
+            for i in 0..3 {
+                wheel.insert(Entry::new(second * 10 + i, second * 1000));
+            }
+        }
+        wheel.advance(3.seconds());
+
+        // keeps the oldest values
+        let collected = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(0, 3000))
+            .unwrap();
+        assert_eq!(collected.to_vec(), vec![0, 1, 2, 10]);
+        assert_eq!(collected.len(), 4);
+        assert!(collected.is_truncated());
+
+        let collected = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(2000, 3000))
+            .unwrap();
+        assert_eq!(collected.to_vec(), vec![20, 21, 22]);
+        assert!(!collected.is_truncated());
+    }
+}
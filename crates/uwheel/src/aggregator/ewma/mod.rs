@@ -0,0 +1,214 @@
+use crate::aggregator::{Aggregator, PartialAggregateType};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
+
+/// Partial aggregate of the [EwmaAggregator]
+///
+/// Holds the decayed sum of values and weights relative to the timestamp of the most recent value.
+#[derive(Default, Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+pub struct EwmaState {
+    weighted_sum: f64,
+    weight_total: f64,
+    anchor_ms: u64,
+}
+
+impl EwmaState {
+    /// Returns the decayed sum of values
+    pub fn weighted_sum(&self) -> f64 {
+        self.weighted_sum
+    }
+    /// Returns the decayed sum of weights
+    pub fn weight_total(&self) -> f64 {
+        self.weight_total
+    }
+    /// Returns the timestamp in milliseconds that the weights are relative to
+    pub fn anchor_ms(&self) -> u64 {
+        self.anchor_ms
+    }
+    /// Returns the exponentially-weighted moving average or `None` if no value has been aggregated
+    pub fn average(&self) -> Option<f64> {
+        if self.weight_total == 0.0 {
+            None
+        } else {
+            Some(self.weighted_sum / self.weight_total)
+        }
+    }
+
+    // Decays the state so that its weights become relative to the given anchor
+    #[inline]
+    fn decay_to(self, anchor_ms: u64, half_life_ms: f64) -> Self {
+        let distance = anchor_ms.saturating_sub(self.anchor_ms) as f64;
+        let factor = 0.5f64.powf(distance / half_life_ms);
+        Self {
+            weighted_sum: self.weighted_sum * factor,
+            weight_total: self.weight_total * factor,
+            anchor_ms,
+        }
+    }
+}
+
+impl PartialAggregateType for EwmaState {}
+
+/// Exponentially-weighted moving average aggregator
+///
+/// The weight of a value halves every `HALF_LIFE_SECS` seconds. Since the aggregation framework
+/// does not tell the order of two partial aggregates, each partial keeps track of the timestamp
+/// of its most recent value. When two partials are combined, the older one is decayed by the time
+/// distance between them, which makes the combination both associative and commutative.
+///
+/// Values are inserted together with their timestamp in milliseconds, i.e., `(value, timestamp)`.
+///
+/// Note that the decay is applied when partials are combined and is relative to the most recent value
+/// of a range rather than the end of the range. Since the average normalizes the weighted sum by
+/// the total weight, the result only differs from a reference EWMA through floating-point rounding.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::ewma::EwmaAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<EwmaAggregator<1>> = RwWheel::new(0);
+/// wheel.insert(Entry::new((10.0, 0), 0));
+/// wheel.insert(Entry::new((40.0, 1000), 1000));
+/// wheel.advance(2.seconds());
+///
+/// // the most recent value has twice the weight of the value one half-life earlier
+/// let ewma = wheel.read().interval_and_lower(2.seconds()).unwrap();
+/// assert_eq!(ewma, 30.0);
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct EwmaAggregator<const HALF_LIFE_SECS: u64>;
+
+impl<const HALF_LIFE_SECS: u64> EwmaAggregator<HALF_LIFE_SECS> {
+    const HALF_LIFE_MS: f64 = {
+        assert!(HALF_LIFE_SECS > 0, "EwmaAggregator requires a half-life");
+        (HALF_LIFE_SECS * 1000) as f64
+    };
+}
+
+impl<const HALF_LIFE_SECS: u64> Aggregator for EwmaAggregator<HALF_LIFE_SECS> {
+    const IDENTITY: Self::PartialAggregate = EwmaState {
+        weighted_sum: 0.0,
+        weight_total: 0.0,
+        anchor_ms: 0,
+    };
+    const TAG: &'static str = "EwmaAggregator";
+
+    type Input = (f64, u64);
+    type MutablePartialAggregate = EwmaState;
+    type PartialAggregate = EwmaState;
+    type Aggregate = f64;
+
+    fn qualified_tag() -> String {
+        format!("{}<{}>", Self::TAG, HALF_LIFE_SECS)
+    }
+
+    #[inline]
+    fn lift((value, timestamp): Self::Input) -> Self::MutablePartialAggregate {
+        EwmaState {
+            weighted_sum: value,
+            weight_total: 1.0,
+            anchor_ms: timestamp,
+        }
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        *a = Self::combine(*a, Self::lift(input));
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        let anchor_ms = a.anchor_ms.max(b.anchor_ms);
+        let a = a.decay_to(anchor_ms, Self::HALF_LIFE_MS);
+        let b = b.decay_to(anchor_ms, Self::HALF_LIFE_MS);
+        EwmaState {
+            weighted_sum: a.weighted_sum + b.weighted_sum,
+            weight_total: a.weight_total + b.weight_total,
+            anchor_ms,
+        }
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a.weighted_sum / a.weight_total
+    }
+
+    #[inline]
+    fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+        a.average()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Entry, RwWheel, WheelRange};
+
+    const HALF_LIFE: u64 = 30;
+
+    #[test]
+    fn ewma_matches_reference_test() {
+        let mut wheel: RwWheel<EwmaAggregator<HALF_LIFE>> = RwWheel::new(0);
+        let seconds = 300u64;
+        let values: Vec<f64> = (0..seconds).map(|i| ((i * 37) % 101) as f64).collect();
+
+        for (i, value) in values.iter().enumerate() {
+            let ts = i as u64 * 1000;
+            wheel.insert(Entry::new((*value, ts), ts));
+        }
+        wheel.advance_to(seconds * 1000);
+
+        // reference per-second EWMA with bias correction: s_t = alpha * x_t + (1 - alpha) * s_{t-1}
+        let reference = |values: &[f64]| {
+            let keep = 0.5f64.powf(1.0 / HALF_LIFE as f64);
+            let (mut smoothed, mut weight) = (0.0, 0.0);
+            for value in values {
+                smoothed = keep * smoothed + (1.0 - keep) * value;
+                weight = keep * weight + (1.0 - keep);
+            }
+            smoothed / weight
+        };
+
+        let assert_close = |range: WheelRange, expected: f64| {
+            let ewma = wheel.read().combine_range_and_lower(range).unwrap();
+            assert!(
+                (ewma - expected).abs() < 1e-9,
+                "ewma {ewma} differs from reference {expected}"
+            );
+        };
+
+        // spans the minutes wheel, the seconds wheel and a partial minutes range
+        assert_close(
+            WheelRange::new_unchecked(0, seconds * 1000),
+            reference(&values),
+        );
+        assert_close(
+            WheelRange::new_unchecked(250000, 300000),
+            reference(&values[250..]),
+        );
+        assert_close(
+            WheelRange::new_unchecked(60000, 240000),
+            reference(&values[60..240]),
+        );
+    }
+
+    #[test]
+    fn ewma_combine_is_order_independent() {
+        type A = EwmaAggregator<HALF_LIFE>;
+        let a = A::lift((10.0, 0));
+        let b = A::lift((20.0, 15000));
+        let c = A::lift((30.0, 45000));
+
+        let left = A::combine(A::combine(a, b), c);
+        let right = A::combine(c, A::combine(b, a));
+        assert!((A::lower(left) - A::lower(right)).abs() < 1e-12);
+        assert_eq!(A::lower_f64(A::IDENTITY), None);
+    }
+}
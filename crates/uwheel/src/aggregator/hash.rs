@@ -0,0 +1,376 @@
+use core::fmt::{self, Debug, Display};
+
+const XXH_PRIME_1: u64 = 0x9E37_79B1_85EB_CA87;
+const XXH_PRIME_2: u64 = 0xC2B2_AE3D_27D4_EB4F;
+const XXH_PRIME_3: u64 = 0x1656_67B1_9E37_79F9;
+const XXH_PRIME_4: u64 = 0x85EB_CA77_C2B2_AE63;
+const XXH_PRIME_5: u64 = 0x27D4_EB2F_1656_67C5;
+
+/// Identifies the hash function (and its seed) that was used to build a sketch
+///
+/// The signature is stored as part of a sketch so that two sketches are only merged
+/// if they were built using the same hash function.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum HasherSignature {
+    /// XXH64 with a 64-bit seed
+    XxHash64 {
+        /// Hash seed
+        seed: u64,
+    },
+    /// SipHash-2-4 with a 128-bit key split into two 64-bit halves
+    SipHash24 {
+        /// First half of the key
+        k0: u64,
+        /// Second half of the key
+        k1: u64,
+    },
+}
+
+impl HasherSignature {
+    /// Returns `Ok(())` if `other` was produced by the same hash function and seed as `self`
+    pub fn ensure_compatible(&self, other: &Self) -> Result<(), SketchError> {
+        if self == other {
+            Ok(())
+        } else {
+            Err(SketchError::IncompatibleHasher {
+                expected: *self,
+                found: *other,
+            })
+        }
+    }
+}
+
+impl Display for HasherSignature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HasherSignature::XxHash64 { seed } => write!(f, "xxh64(seed={seed})"),
+            HasherSignature::SipHash24 { k0, k1 } => write!(f, "siphash24(k0={k0}, k1={k1})"),
+        }
+    }
+}
+
+/// Errors that may occur when combining sketches
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SketchError {
+    /// The sketches were built using different hash functions or seeds
+    IncompatibleHasher {
+        /// Hasher of the sketch being merged into
+        expected: HasherSignature,
+        /// Hasher of the incoming sketch
+        found: HasherSignature,
+    },
+}
+
+impl Display for SketchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SketchError::IncompatibleHasher { expected, found } => {
+                write!(
+                    f,
+                    "cannot merge sketch built with {found} into sketch built with {expected}"
+                )
+            }
+        }
+    }
+}
+
+/// A hash function used by sketch-based aggregators
+///
+/// The hash function is chosen at the type level so that aggregators stay stateless.
+/// Use [XxHash64] or [SipHash24] and pick a seed through their const generics.
+pub trait SketchHasher: Debug + Clone + Copy + Default + Send + 'static {
+    /// Signature identifying this hash function and its seed
+    const SIGNATURE: HasherSignature;
+
+    /// Hashes a byte slice into a 64-bit value
+    fn hash_bytes(bytes: &[u8]) -> u64;
+
+    /// Hashes a key through its canonical byte representation
+    #[inline]
+    fn hash<K: HashKey + ?Sized>(key: &K) -> u64 {
+        key.with_bytes(Self::hash_bytes)
+    }
+}
+
+/// XXH64 hash function with a configurable seed
+#[derive(Debug, Default, Clone, Copy)]
+pub struct XxHash64<const SEED: u64 = 0>;
+
+impl<const SEED: u64> SketchHasher for XxHash64<SEED> {
+    const SIGNATURE: HasherSignature = HasherSignature::XxHash64 { seed: SEED };
+
+    #[inline]
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        xxh64(bytes, SEED)
+    }
+}
+
+/// SipHash-2-4 hash function with a configurable 128-bit key
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SipHash24<const K0: u64 = 0, const K1: u64 = 0>;
+
+impl<const K0: u64, const K1: u64> SketchHasher for SipHash24<K0, K1> {
+    const SIGNATURE: HasherSignature = HasherSignature::SipHash24 { k0: K0, k1: K1 };
+
+    #[inline]
+    fn hash_bytes(bytes: &[u8]) -> u64 {
+        siphash24(bytes, K0, K1)
+    }
+}
+
+/// Keys that can be hashed by a [SketchHasher]
+///
+/// Integers are hashed using their little-endian representation so that
+/// sketches built on different platforms or in other languages are interoperable.
+pub trait HashKey {
+    /// Calls `f` with the canonical byte representation of the key
+    fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R;
+}
+
+macro_rules! int_hash_key {
+    ($type:ty) => {
+        impl HashKey for $type {
+            #[inline]
+            fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+                f(&self.to_le_bytes())
+            }
+        }
+    };
+}
+
+int_hash_key!(u8);
+int_hash_key!(u16);
+int_hash_key!(u32);
+int_hash_key!(u64);
+int_hash_key!(u128);
+int_hash_key!(i8);
+int_hash_key!(i16);
+int_hash_key!(i32);
+int_hash_key!(i64);
+int_hash_key!(i128);
+
+impl HashKey for str {
+    #[inline]
+    fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        f(self.as_bytes())
+    }
+}
+
+impl HashKey for [u8] {
+    #[inline]
+    fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        f(self)
+    }
+}
+
+impl<const N: usize> HashKey for [u8; N] {
+    #[inline]
+    fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        f(self)
+    }
+}
+
+impl<K: HashKey + ?Sized> HashKey for &K {
+    #[inline]
+    fn with_bytes<R>(&self, f: impl FnOnce(&[u8]) -> R) -> R {
+        (**self).with_bytes(f)
+    }
+}
+
+#[inline]
+fn read_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0u8; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_le_bytes(buf)
+}
+
+#[inline]
+fn read_u32(bytes: &[u8]) -> u32 {
+    let mut buf = [0u8; 4];
+    buf.copy_from_slice(&bytes[..4]);
+    u32::from_le_bytes(buf)
+}
+
+#[inline]
+fn xxh64_round(acc: u64, input: u64) -> u64 {
+    acc.wrapping_add(input.wrapping_mul(XXH_PRIME_2))
+        .rotate_left(31)
+        .wrapping_mul(XXH_PRIME_1)
+}
+
+#[inline]
+fn xxh64_merge_round(acc: u64, val: u64) -> u64 {
+    (acc ^ xxh64_round(0, val))
+        .wrapping_mul(XXH_PRIME_1)
+        .wrapping_add(XXH_PRIME_4)
+}
+
+/// One-shot XXH64
+pub(crate) fn xxh64(input: &[u8], seed: u64) -> u64 {
+    let len = input.len();
+    let mut rest = input;
+
+    let mut h = if len >= 32 {
+        let mut v1 = seed.wrapping_add(XXH_PRIME_1).wrapping_add(XXH_PRIME_2);
+        let mut v2 = seed.wrapping_add(XXH_PRIME_2);
+        let mut v3 = seed;
+        let mut v4 = seed.wrapping_sub(XXH_PRIME_1);
+
+        while rest.len() >= 32 {
+            v1 = xxh64_round(v1, read_u64(rest));
+            v2 = xxh64_round(v2, read_u64(&rest[8..]));
+            v3 = xxh64_round(v3, read_u64(&rest[16..]));
+            v4 = xxh64_round(v4, read_u64(&rest[24..]));
+            rest = &rest[32..];
+        }
+
+        let mut h = v1
+            .rotate_left(1)
+            .wrapping_add(v2.rotate_left(7))
+            .wrapping_add(v3.rotate_left(12))
+            .wrapping_add(v4.rotate_left(18));
+        h = xxh64_merge_round(h, v1);
+        h = xxh64_merge_round(h, v2);
+        h = xxh64_merge_round(h, v3);
+        xxh64_merge_round(h, v4)
+    } else {
+        seed.wrapping_add(XXH_PRIME_5)
+    };
+
+    h = h.wrapping_add(len as u64);
+
+    while rest.len() >= 8 {
+        h ^= xxh64_round(0, read_u64(rest));
+        h = h
+            .rotate_left(27)
+            .wrapping_mul(XXH_PRIME_1)
+            .wrapping_add(XXH_PRIME_4);
+        rest = &rest[8..];
+    }
+
+    if rest.len() >= 4 {
+        h ^= (read_u32(rest) as u64).wrapping_mul(XXH_PRIME_1);
+        h = h
+            .rotate_left(23)
+            .wrapping_mul(XXH_PRIME_2)
+            .wrapping_add(XXH_PRIME_3);
+        rest = &rest[4..];
+    }
+
+    for &byte in rest {
+        h ^= (byte as u64).wrapping_mul(XXH_PRIME_5);
+        h = h.rotate_left(11).wrapping_mul(XXH_PRIME_1);
+    }
+
+    h ^= h >> 33;
+    h = h.wrapping_mul(XXH_PRIME_2);
+    h ^= h >> 29;
+    h = h.wrapping_mul(XXH_PRIME_3);
+    h ^ (h >> 32)
+}
+
+#[inline]
+fn sip_round(v: &mut [u64; 4]) {
+    v[0] = v[0].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(13);
+    v[1] ^= v[0];
+    v[0] = v[0].rotate_left(32);
+    v[2] = v[2].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(16);
+    v[3] ^= v[2];
+    v[0] = v[0].wrapping_add(v[3]);
+    v[3] = v[3].rotate_left(21);
+    v[3] ^= v[0];
+    v[2] = v[2].wrapping_add(v[1]);
+    v[1] = v[1].rotate_left(17);
+    v[1] ^= v[2];
+    v[2] = v[2].rotate_left(32);
+}
+
+/// One-shot SipHash-2-4
+pub(crate) fn siphash24(input: &[u8], k0: u64, k1: u64) -> u64 {
+    let mut v = [
+        k0 ^ 0x736f_6d65_7073_6575,
+        k1 ^ 0x646f_7261_6e64_6f6d,
+        k0 ^ 0x6c79_6765_6e65_7261,
+        k1 ^ 0x7465_6462_7974_6573,
+    ];
+
+    let (chunks, remainder) = input.as_chunks::<8>();
+    for chunk in chunks {
+        let m = u64::from_le_bytes(*chunk);
+        v[3] ^= m;
+        sip_round(&mut v);
+        sip_round(&mut v);
+        v[0] ^= m;
+    }
+
+    let mut b = (input.len() as u64) << 56;
+    for (i, &byte) in remainder.iter().enumerate() {
+        b |= (byte as u64) << (8 * i);
+    }
+
+    v[3] ^= b;
+    sip_round(&mut v);
+    sip_round(&mut v);
+    v[0] ^= b;
+
+    v[2] ^= 0xff;
+    for _ in 0..4 {
+        sip_round(&mut v);
+    }
+
+    v[0] ^ v[1] ^ v[2] ^ v[3]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn xxh64_reference_values() {
+        assert_eq!(xxh64(b"", 0), 0xEF46_DB37_51D8_E999);
+        assert_eq!(xxh64(b"a", 0), 0xD24E_C4F1_A98C_6E5B);
+        assert_eq!(xxh64(b"abc", 0), 0x44BC_2CF5_AD77_0999);
+        assert_eq!(
+            xxh64(b"Nobody inspects the spammish repetition", 0),
+            0xFBCE_A83C_8A37_8BF1
+        );
+    }
+
+    #[test]
+    fn siphash24_reference_values() {
+        let k0 = 0x0706_0504_0302_0100;
+        let k1 = 0x0f0e_0d0c_0b0a_0908;
+        assert_eq!(siphash24(&[], k0, k1), 0x726f_db47_dd0e_0e31);
+        let input: [u8; 15] = core::array::from_fn(|i| i as u8);
+        assert_eq!(siphash24(&input, k0, k1), 0xa129_ca61_49be_45e5);
+    }
+
+    #[test]
+    fn integer_keys_hash_little_endian() {
+        assert_eq!(
+            XxHash64::<7>::hash(&0x0102_0304u32),
+            XxHash64::<7>::hash(&[4u8, 3, 2, 1])
+        );
+        assert_eq!(
+            SipHash24::<1, 2>::hash("uwheel"),
+            siphash24(b"uwheel", 1, 2)
+        );
+    }
+
+    #[test]
+    fn hasher_signature_compatibility() {
+        let a = XxHash64::<42>::SIGNATURE;
+        assert!(a.ensure_compatible(&XxHash64::<42>::SIGNATURE).is_ok());
+        assert_eq!(
+            a.ensure_compatible(&XxHash64::<7>::SIGNATURE),
+            Err(SketchError::IncompatibleHasher {
+                expected: a,
+                found: XxHash64::<7>::SIGNATURE,
+            })
+        );
+        assert!(a.ensure_compatible(&SipHash24::<42>::SIGNATURE).is_err());
+    }
+}
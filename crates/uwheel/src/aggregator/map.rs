@@ -0,0 +1,254 @@
+use super::{Aggregator, CombineSimdFn, Compression, InputBounds, InverseFn};
+use crate::wheels::read::hierarchical::Granularity;
+use core::{fmt::Debug, marker::PhantomData};
+
+#[cfg(not(feature = "std"))]
+use alloc::{string::String, vec::Vec};
+
+/// A function applied to inputs by [MapInput] before they are lifted by the inner aggregator
+///
+/// Implemented on a zero-sized type so that the function is part of the aggregator type.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::aggregator::map::InputMap;
+///
+/// #[derive(Default, Debug, Clone, Copy)]
+/// struct Square;
+///
+/// impl InputMap for Square {
+///     type Input = u64;
+///     type Output = u64;
+///
+///     fn map(input: u64) -> u64 {
+///         input * input
+///     }
+/// }
+/// ```
+pub trait InputMap: Default + Debug + Clone + Copy + 'static {
+    /// Input type accepted by the [MapInput] aggregator
+    type Input: InputBounds;
+    /// Input type of the inner aggregator
+    type Output;
+
+    /// Maps an input to the input of the inner aggregator
+    fn map(input: Self::Input) -> Self::Output;
+}
+
+/// Aggregator combinator that maps inputs with `F` before delegating to the aggregator `A`
+///
+/// Reuses existing aggregators on derived quantities (e.g., a sum of squares through [U64SumAggregator](crate::aggregator::sum::U64SumAggregator))
+/// without writing a new aggregator. Only inputs are mapped, partial aggregates are combined and lowered by `A`.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::{map::{InputMap, MapInput}, sum::U64SumAggregator}, Entry, RwWheel, NumericalDuration};
+///
+/// #[derive(Default, Debug, Clone, Copy)]
+/// struct Square;
+///
+/// impl InputMap for Square {
+///     type Input = u64;
+///     type Output = u64;
+///
+///     fn map(input: u64) -> u64 {
+///         input * input
+///     }
+/// }
+///
+/// let mut wheel: RwWheel<MapInput<Square, U64SumAggregator>> = RwWheel::new(0);
+/// wheel.insert(Entry::new(2, 0));
+/// wheel.insert(Entry::new(3, 1000));
+/// wheel.advance(2.seconds());
+///
+/// assert_eq!(wheel.read().interval(2.seconds()), Some(13));
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MapInput<F: InputMap, A: Aggregator>(PhantomData<(F, A)>);
+
+impl<F: InputMap, A: Aggregator> Default for MapInput<F, A> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<F, A> Aggregator for MapInput<F, A>
+where
+    F: InputMap<Output = A::Input>,
+    A: Aggregator,
+{
+    const IDENTITY: Self::PartialAggregate = A::IDENTITY;
+    const TAG: &'static str = A::TAG;
+
+    type Input = F::Input;
+    type MutablePartialAggregate = A::MutablePartialAggregate;
+    type PartialAggregate = A::PartialAggregate;
+    type Aggregate = A::Aggregate;
+
+    fn qualified_tag() -> String {
+        A::qualified_tag()
+    }
+
+    #[inline]
+    fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+        A::lift(F::map(input))
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        A::combine_mutable(a, F::map(input));
+    }
+
+    #[inline]
+    fn combine_mutable_bulk(
+        a: &mut Self::MutablePartialAggregate,
+        inputs: impl Iterator<Item = Self::Input>,
+    ) {
+        A::combine_mutable_bulk(a, inputs.map(F::map));
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        A::freeze(a)
+    }
+
+    #[inline]
+    fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        A::combine(a, b)
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        A::lower(a)
+    }
+
+    #[inline]
+    fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+        A::lower_f64(a)
+    }
+
+    #[inline]
+    fn lower_bounded(a: Self::PartialAggregate) -> Option<(f64, f64, f64)> {
+        A::lower_bounded(a)
+    }
+
+    fn format_partial(partial: &Self::PartialAggregate) -> String {
+        A::format_partial(partial)
+    }
+
+    #[inline]
+    fn project_on_rollup(
+        a: Self::PartialAggregate,
+        granularity: Granularity,
+    ) -> Self::PartialAggregate {
+        A::project_on_rollup(a, granularity)
+    }
+
+    #[inline]
+    fn combine_slice(slice: &[Self::PartialAggregate]) -> Option<Self::PartialAggregate> {
+        A::combine_slice(slice)
+    }
+
+    #[inline]
+    fn merge(s1: &mut [Self::PartialAggregate], s2: &[Self::PartialAggregate]) {
+        A::merge(s1, s2)
+    }
+
+    #[inline]
+    fn build_prefix(slice: &[Self::PartialAggregate]) -> Vec<Self::PartialAggregate> {
+        A::build_prefix(slice)
+    }
+
+    #[inline]
+    fn prefix_query(
+        slice: &[Self::PartialAggregate],
+        start: usize,
+        end: usize,
+    ) -> Option<Self::PartialAggregate> {
+        A::prefix_query(slice, start, end)
+    }
+
+    fn combine_inverse() -> Option<InverseFn<Self::PartialAggregate>> {
+        A::combine_inverse()
+    }
+
+    fn combine_simd() -> Option<CombineSimdFn<Self::PartialAggregate>> {
+        A::combine_simd()
+    }
+
+    fn compression() -> Option<Compression<Self::PartialAggregate>> {
+        A::compression()
+    }
+
+    fn commutative() -> bool {
+        A::commutative()
+    }
+
+    fn identity_is_noop() -> bool {
+        A::identity_is_noop()
+    }
+
+    fn monotonic() -> bool {
+        A::monotonic()
+    }
+
+    fn exact_inverse() -> bool {
+        A::exact_inverse()
+    }
+}
+
+#[cfg(all(test, feature = "sum"))]
+mod tests {
+    use super::*;
+    use crate::{aggregator::sum::U64SumAggregator, Entry, RwWheel, WheelRange};
+
+    #[derive(Default, Debug, Clone, Copy)]
+    struct Square;
+
+    impl InputMap for Square {
+        type Input = u64;
+        type Output = u64;
+
+        fn map(input: u64) -> u64 {
+            input * input
+        }
+    }
+
+    type SumOfSquares = MapInput<Square, U64SumAggregator>;
+
+    #[test]
+    fn sum_of_squares_test() {
+        let mut squares: RwWheel<SumOfSquares> = RwWheel::new(0);
+        let mut sums: RwWheel<U64SumAggregator> = RwWheel::new(0);
+        let seconds = 180u64;
+        for i in 0..seconds {
+            squares.insert(Entry::new(i % 13, i * 1000));
+            sums.insert(Entry::new((i % 13) * (i % 13), i * 1000));
+        }
+        squares.advance_to(seconds * 1000);
+        sums.advance_to(seconds * 1000);
+
+        let expected = |range: core::ops::Range<u64>| range.map(|i| (i % 13) * (i % 13)).sum();
+
+        // spans the minutes wheel only, minutes and seconds, and a partial seconds range
+        for (start, end) in [(0, 180), (60, 180), (150, 180)] {
+            let range = WheelRange::new_unchecked(start * 1000, end * 1000);
+            assert_eq!(
+                squares.read().combine_range(range),
+                Some(expected(start..end))
+            );
+            // same as squaring the inputs before inserting them
+            assert_eq!(
+                squares.read().combine_range(range),
+                sums.read().combine_range(range)
+            );
+        }
+
+        // bulk inserts are mapped as well
+        let mut bulk = SumOfSquares::lift(1);
+        SumOfSquares::combine_mutable_bulk(&mut bulk, [2, 3].into_iter());
+        assert_eq!(SumOfSquares::freeze(bulk), 14);
+        assert!(SumOfSquares::invertible());
+    }
+}
@@ -19,6 +19,7 @@ macro_rules! max_impl {
 
         impl Aggregator for $struct {
             const IDENTITY: Self::PartialAggregate = <$type>::MIN;
+            const TAG: &'static str = stringify!($struct);
             type Input = $type;
             type MutablePartialAggregate = $pa;
             type Aggregate = $type;
@@ -50,6 +51,21 @@ macro_rules! max_impl {
                 a
             }
 
+            #[inline]
+            fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+                // the identity is a sentinel for ranges without data
+                if a == Self::IDENTITY {
+                    None
+                } else {
+                    Some(a as f64)
+                }
+            }
+
+            #[inline]
+            fn monotonic() -> bool {
+                true
+            }
+
             #[cfg(feature = "simd")]
             #[inline]
             fn combine_simd() -> Option<fn(&[Self::PartialAggregate]) -> Self::PartialAggregate> {
@@ -19,6 +19,7 @@ macro_rules! min_impl {
 
         impl Aggregator for $struct {
             const IDENTITY: Self::PartialAggregate = <$type>::MAX;
+            const TAG: &'static str = stringify!($struct);
 
             type Input = $type;
             type MutablePartialAggregate = $pa;
@@ -50,6 +51,16 @@ macro_rules! min_impl {
                 a
             }
 
+            #[inline]
+            fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+                // the identity is a sentinel for ranges without data
+                if a == Self::IDENTITY {
+                    None
+                } else {
+                    Some(a as f64)
+                }
+            }
+
             #[cfg(feature = "simd")]
             #[inline]
             fn combine_simd() -> Option<fn(&[Self::PartialAggregate]) -> Self::PartialAggregate> {
@@ -69,6 +69,7 @@ macro_rules! min_max_impl {
                 min: <$type>::MAX,
                 max: <$type>::MIN,
             };
+            const TAG: &'static str = stringify!($struct);
 
             type Input = $type;
             type MutablePartialAggregate = Self::PartialAggregate;
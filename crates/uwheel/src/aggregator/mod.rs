@@ -1,7 +1,8 @@
+use crate::wheels::read::hierarchical::Granularity;
 use core::fmt::Debug;
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{format, string::String, vec::Vec};
 
 /// An All Aggregator enabling the following functions (MAX, MIN, SUM, COUNT, AVG).
 #[cfg(feature = "all")]
@@ -9,6 +10,19 @@ pub mod all;
 /// Incremental AVG aggregation
 #[cfg(feature = "avg")]
 pub mod avg;
+/// Bloom filter aggregation for approximate membership queries
+#[cfg(feature = "bloom")]
+pub mod bloom;
+/// Collection of raw values for audit trails over short ranges
+#[cfg(feature = "collect")]
+pub mod collect;
+/// Exponentially-weighted moving average aggregation
+#[cfg(feature = "ewma")]
+pub mod ewma;
+/// Hash functions for sketch-based aggregators
+pub mod hash;
+/// Combinators that map inputs before delegating to another aggregator
+pub mod map;
 /// Incremental MAX aggregation
 #[cfg(feature = "max")]
 pub mod max;
@@ -18,9 +32,19 @@ pub mod min;
 /// Incremental MinMax aggregation for temporal pruning
 #[cfg(feature = "min_max")]
 pub mod min_max;
+/// Exact set of distinct values for low-cardinality dimensions
+#[cfg(feature = "small_set")]
+pub mod small_set;
 /// Incremental SUM aggregation
 #[cfg(feature = "sum")]
 pub mod sum;
+/// Weighted mean aggregation of values carrying a per-entry weight
+#[cfg(feature = "weighted")]
+pub mod weighted;
+
+/// Unit aggregation for using µWheel without aggregating data
+#[cfg(feature = "unit")]
+pub mod unit;
 
 #[cfg(feature = "top_n")]
 /// Top-N Aggregation using a nested Aggregator which has a PartialAggregate that implements `Ord`
@@ -51,6 +75,7 @@ pub type InverseFn<P> = fn(P, P) -> P;
 ///
 /// impl Aggregator for MySumAggregator {
 ///     const IDENTITY: Self::PartialAggregate = 0u32;
+///     const TAG: &'static str = "MySumAggregator";
 ///     type Input = u32;
 ///     type MutablePartialAggregate = u32;
 ///     type Aggregate = u32;
@@ -83,6 +108,26 @@ pub trait Aggregator: Default + Debug + Clone + 'static {
     /// For example, for SUM types the identity value should be set to 0.
     const IDENTITY: Self::PartialAggregate;
 
+    /// Tag identifying the aggregator in serialized wheels
+    ///
+    /// The tag is embedded when a wheel is serialized and validated on deserialization so that
+    /// bytes produced by a different aggregator are rejected instead of silently misinterpreted.
+    /// It must stay the same across releases and toolchains for snapshots to remain loadable, so it is
+    /// spelled out per aggregator rather than derived from the type name. Change it when the layout of
+    /// [Self::PartialAggregate] changes to reject snapshots written with the old layout.
+    const TAG: &'static str;
+
+    /// Returns the tag identifying the aggregator and its type parameters in serialized wheels
+    ///
+    /// Generic aggregators append their parameters to [Self::TAG] so that a wheel serialized with one
+    /// parameterization is rejected when deserialized as another. Type parameters without a tag of their
+    /// own, such as key types, are identified by their size in bytes.
+    ///
+    /// Is set to [Self::TAG] by default
+    fn qualified_tag() -> String {
+        String::from(Self::TAG)
+    }
+
     /// Aggregator Input type that can be converted or applied to a [Self::MutablePartialAggregate].
     type Input: InputBounds;
 
@@ -105,6 +150,20 @@ pub trait Aggregator: Default + Debug + Clone + 'static {
     /// Combines [Self::Input] to an existing `&mut Self::MutablePartialAggregate`.
     fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input);
 
+    /// Combines a batch of [Self::Input] into an existing `&mut Self::MutablePartialAggregate`.
+    ///
+    /// A default implementation is provided that calls [Self::combine_mutable] for each input.
+    /// Aggregators that can combine many inputs at once (e.g., SUM) may override it to speed up bulk ingestion.
+    #[inline]
+    fn combine_mutable_bulk(
+        a: &mut Self::MutablePartialAggregate,
+        inputs: impl Iterator<Item = Self::Input>,
+    ) {
+        for input in inputs {
+            Self::combine_mutable(a, input);
+        }
+    }
+
     /// Freezes a [Self::MutablePartialAggregate] into a [Self::PartialAggregate].
     fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate;
 
@@ -114,6 +173,49 @@ pub trait Aggregator: Default + Debug + Clone + 'static {
     /// Lowers a [Self::PartialAggregate] into a final [Self::Aggregate].
     fn lower(a: Self::PartialAggregate) -> Self::Aggregate;
 
+    /// Lowers a [Self::PartialAggregate] into a `f64` value
+    ///
+    /// Useful for consumers such as charting libraries that require a uniform numeric type
+    /// regardless of the aggregator. Composite aggregates are projected to a single value (e.g., AVG to its mean).
+    ///
+    /// Is set to `None` by default
+    fn lower_f64(_a: Self::PartialAggregate) -> Option<f64> {
+        None
+    }
+
+    /// Lowers a [Self::PartialAggregate] into an `(estimate, lower, upper)` interval of `f64` values
+    ///
+    /// Approximate aggregators may override this function to return an error-based interval around the estimate
+    /// so that consumers can present the uncertainty of a result.
+    ///
+    /// Is set to exact bounds derived from [Self::lower_f64] by default
+    fn lower_bounded(a: Self::PartialAggregate) -> Option<(f64, f64, f64)> {
+        Self::lower_f64(a).map(|estimate| (estimate, estimate, estimate))
+    }
+
+    /// Formats a [Self::PartialAggregate] for debugging purposes such as [Haw::dump_combine_range](crate::Haw::dump_combine_range)
+    ///
+    /// Aggregators with large partial aggregates (e.g., sketches) may override this function to print a compact summary.
+    ///
+    /// Is set to the `Debug` representation by default
+    fn format_partial(partial: &Self::PartialAggregate) -> String {
+        format!("{partial:?}")
+    }
+
+    /// Projects a partial aggregate as it is rolled up into the wheel of the given granularity
+    ///
+    /// Enables heterogeneous storage across granularities, for example keeping a reduced form of the
+    /// partial aggregate at coarse granularities such as years.
+    ///
+    /// Is set to the identity projection by default
+    #[inline]
+    fn project_on_rollup(
+        a: Self::PartialAggregate,
+        _granularity: Granularity,
+    ) -> Self::PartialAggregate {
+        a
+    }
+
     /// Combines a slice of partial aggregates into a new partial
     ///
     /// A default implementation is provided that iterates over the aggregates and combines them
@@ -195,6 +297,39 @@ pub trait Aggregator: Default + Debug + Clone + 'static {
         None
     }
 
+    /// Returns ``true`` if [Self::combine] is commutative
+    ///
+    /// Order-sensitive aggregators (e.g., First, Last, or concatenation) must return ``false``
+    /// so that partial aggregates are always combined from the oldest to the most recent one.
+    /// Note that [Self::combine] is always assumed to be associative.
+    fn commutative() -> bool {
+        true
+    }
+
+    /// Returns ``true`` if combining with [Self::IDENTITY] never changes a partial aggregate
+    ///
+    /// Allows a [Haw](crate::Haw) configured with identity fill suppression to skip work for
+    /// seconds without data. Defaults to ``false``.
+    fn identity_is_noop() -> bool {
+        false
+    }
+
+    /// Returns ``true`` if [Self::combine] never yields a partial aggregate smaller than either of its inputs
+    ///
+    /// Allows threshold queries such as [Haw::combine_range_exceeds](crate::Haw::combine_range_exceeds)
+    /// to stop scanning once the threshold has been crossed. Defaults to ``false``.
+    fn monotonic() -> bool {
+        false
+    }
+
+    /// Returns ``true`` if [Self::combine_inverse] exactly undoes [Self::combine]
+    ///
+    /// Allows a [Haw](crate::Haw) to answer range queries by removing partial aggregates from larger ones,
+    /// such as through inverse landmark plans or adaptive prefix layouts. Defaults to ``false``.
+    fn exact_inverse() -> bool {
+        false
+    }
+
     /// Returns ``true`` if the Aggregator supports invertibility
     #[doc(hidden)]
     fn invertible() -> bool {
@@ -326,3 +461,86 @@ tuple_partial!(A B C);
 tuple_partial!(A B C D);
 tuple_partial!(A B C D E);
 tuple_partial!(A B C D E F);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        aggregator::{
+            avg::U64AvgAggregator,
+            max::{I64MaxAggregator, U64MaxAggregator},
+            min::U64MinAggregator,
+            sum::{KahanSumAggregator, U64SumAggregator},
+        },
+        Entry,
+        RwWheel,
+        WheelRange,
+    };
+
+    fn lower_range_f64<A: Aggregator<Input = u64>>() -> Option<f64> {
+        let mut wheel: RwWheel<A> = RwWheel::new(0);
+        for (value, ts) in [(4, 0), (10, 1000), (1, 2000), (7, 3000)] {
+            wheel.insert(Entry::new(value, ts));
+        }
+        wheel.advance_to(5000);
+        wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(1000, 4000))
+            .and_then(A::lower_f64)
+    }
+
+    #[test]
+    fn lower_f64_test() {
+        assert_eq!(lower_range_f64::<U64SumAggregator>(), Some(18.0));
+        assert_eq!(lower_range_f64::<U64AvgAggregator>(), Some(6.0));
+        assert_eq!(lower_range_f64::<U64MinAggregator>(), Some(1.0));
+
+        // an empty AVG partial has no mean
+        assert_eq!(
+            U64AvgAggregator::lower_f64(U64AvgAggregator::IDENTITY),
+            None
+        );
+        // nor do MIN and MAX partials holding their identity sentinel
+        assert_eq!(
+            U64MinAggregator::lower_f64(U64MinAggregator::IDENTITY),
+            None
+        );
+        assert_eq!(
+            I64MaxAggregator::lower_f64(I64MaxAggregator::IDENTITY),
+            None
+        );
+        let mut wheel: RwWheel<U64MinAggregator> = RwWheel::new(0);
+        wheel.advance_to(5000);
+        let empty = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(0, 5000));
+        assert_eq!(empty, Some(u64::MAX));
+        assert_eq!(empty.and_then(U64MinAggregator::lower_f64), None);
+    }
+
+    #[test]
+    fn lower_bounded_test() {
+        // exact aggregators return exact bounds
+        assert_eq!(
+            U64SumAggregator::lower_bounded(18),
+            Some((18.0, 18.0, 18.0))
+        );
+        assert_eq!(
+            U64AvgAggregator::lower_bounded((18, 3)),
+            Some((6.0, 6.0, 6.0))
+        );
+        assert_eq!(
+            U64AvgAggregator::lower_bounded(U64AvgAggregator::IDENTITY),
+            None
+        );
+        assert_eq!(U64MinAggregator::lower_bounded(1), Some((1.0, 1.0, 1.0)));
+        assert_eq!(
+            U64MaxAggregator::lower_bounded(12),
+            Some((12.0, 12.0, 12.0))
+        );
+        assert_eq!(
+            KahanSumAggregator::lower_bounded((1e16, 1.0)),
+            Some((1e16 + 1.0, 1e16 + 1.0, 1e16 + 1.0))
+        );
+    }
+}
@@ -0,0 +1,267 @@
+use crate::aggregator::{Aggregator, InputBounds, PartialAggregateType};
+use core::marker::PhantomData;
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
+
+#[cfg(feature = "serde")]
+use serde_big_array::BigArray;
+
+/// A bounded set of distinct values used as the partial aggregate of [SmallSetAggregator]
+///
+/// Holds at most `N` distinct values in ascending order. If the set would exceed `N` values,
+/// only the `N` smallest are kept and the set is marked as overflowed (see [SmallSet::is_exact]).
+/// Keeping the smallest values makes the result independent of the order in which sets are combined.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "T: serde::Serialize",
+        deserialize = "T: serde::Deserialize<'de>"
+    ))
+)]
+#[derive(Debug, Clone, Copy)]
+pub struct SmallSet<T: Copy, const N: usize> {
+    #[cfg_attr(feature = "serde", serde(with = "BigArray"))]
+    values: [Option<T>; N],
+    len: usize,
+    overflowed: bool,
+}
+
+impl<T: Copy, const N: usize> Default for SmallSet<T, N> {
+    // have to implement manually as arrays only implement Default up to 32 elements
+    fn default() -> Self {
+        Self::empty()
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> PartialEq for SmallSet<T, N> {
+    fn eq(&self, other: &Self) -> bool {
+        self.overflowed == other.overflowed && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Copy, const N: usize> SmallSet<T, N> {
+    /// Creates an empty set
+    pub const fn empty() -> Self {
+        Self {
+            values: [None; N],
+            len: 0,
+            overflowed: false,
+        }
+    }
+
+    /// Returns the number of distinct values in the set
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns `true` if the set holds no values
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns `true` if the set holds every distinct value that was inserted
+    ///
+    /// Returns `false` if more than `N` distinct values were inserted, in which case only the `N` smallest are kept.
+    pub fn is_exact(&self) -> bool {
+        !self.overflowed
+    }
+
+    /// Returns an iterator over the values of the set in ascending order
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.values[..self.len].iter().flatten().copied()
+    }
+
+    /// Returns the values of the set in ascending order
+    pub fn to_vec(&self) -> Vec<T> {
+        self.iter().collect()
+    }
+}
+
+impl<T: Copy + Ord, const N: usize> SmallSet<T, N> {
+    /// Returns `true` if the set contains the value
+    pub fn contains(&self, value: &T) -> bool {
+        self.values[..self.len].binary_search(&Some(*value)).is_ok()
+    }
+
+    // Inserts a value while keeping the set sorted and bounded by N
+    #[inline]
+    fn insert(&mut self, value: T) {
+        let idx = match self.values[..self.len].binary_search(&Some(value)) {
+            Ok(_) => return,
+            Err(idx) => idx,
+        };
+        if idx == N {
+            // larger than every kept value of a full set
+            self.overflowed = true;
+            return;
+        }
+        if self.len == N {
+            // evict the largest value to make room
+            self.overflowed = true;
+            self.len -= 1;
+        }
+        self.values.copy_within(idx..self.len, idx + 1);
+        self.values[idx] = Some(value);
+        self.len += 1;
+    }
+
+    // Merges the values of other into self
+    #[inline]
+    fn union(&mut self, other: &Self) {
+        for value in other.iter() {
+            self.insert(value);
+        }
+        self.overflowed |= other.overflowed;
+    }
+}
+
+impl<T: InputBounds + Ord, const N: usize> PartialAggregateType for SmallSet<T, N> {}
+
+/// Aggregator that tracks the exact set of distinct values of low-cardinality dimensions
+///
+/// Suited for dimensions such as HTTP status codes or enum states where the distinct values seen
+/// within a range are of interest rather than an approximation (see [bloom](crate::aggregator::bloom)).
+/// Each partial aggregate is a [SmallSet] holding up to `N` distinct values and combining partials
+/// computes their union.
+///
+/// If a range contains more than `N` distinct values, the result degrades to the `N` smallest
+/// values and [SmallSet::is_exact] returns `false`. Every slot of the wheel stores `N` values, so `N` should be kept small.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::small_set::SmallSetAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<SmallSetAggregator<u16, 8>> = RwWheel::new(0);
+/// wheel.insert(Entry::new(500, 1000));
+/// wheel.insert(Entry::new(200, 1500));
+/// wheel.insert(Entry::new(200, 2000));
+/// wheel.advance(3.seconds());
+///
+/// let codes = wheel.read().interval_and_lower(2.seconds()).unwrap();
+/// assert_eq!(codes, vec![200, 500]);
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct SmallSetAggregator<T, const N: usize>(PhantomData<T>);
+
+impl<T, const N: usize> Default for SmallSetAggregator<T, N> {
+    // have to implement manually as T may not implement Default
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, const N: usize> Aggregator for SmallSetAggregator<T, N>
+where
+    T: InputBounds + Ord + 'static,
+{
+    const IDENTITY: Self::PartialAggregate = SmallSet::empty();
+    const TAG: &'static str = "SmallSetAggregator";
+
+    type Input = T;
+    type MutablePartialAggregate = SmallSet<T, N>;
+    type PartialAggregate = SmallSet<T, N>;
+    type Aggregate = Vec<T>;
+
+    fn qualified_tag() -> String {
+        format!("{}<{}, {}>", Self::TAG, core::mem::size_of::<T>(), N)
+    }
+
+    #[inline]
+    fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+        let mut set = SmallSet::empty();
+        set.insert(input);
+        set
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        a.insert(input);
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(mut a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        a.union(&b);
+        a
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a.to_vec()
+    }
+
+    #[inline]
+    fn identity_is_noop() -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{duration::NumericalDuration, Entry, RwWheel, WheelRange};
+
+    #[test]
+    fn small_set_union_is_exact() {
+        let mut wheel: RwWheel<SmallSetAggregator<u16, 8>> = RwWheel::new(0);
+        let codes = [200, 404, 200, 500, 301, 200, 404, 503];
+        for (second, code) in codes.into_iter().enumerate() {
+            wheel.insert(Entry::new(code, second as u64 * 1000));
+        }
+        wheel.advance(8.seconds());
+
+        let set = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(0, 8000))
+            .unwrap();
+        assert!(set.is_exact());
+        assert_eq!(set.to_vec(), vec![200, 301, 404, 500, 503]);
+        assert!(set.contains(&404) && !set.contains(&302));
+
+        // a sub-range only contains the codes seen within it
+        let set = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(1000, 4000))
+            .unwrap();
+        assert_eq!(set.to_vec(), vec![200, 404, 500]);
+    }
+
+    #[test]
+    fn small_set_degrades_beyond_capacity() {
+        let mut wheel: RwWheel<SmallSetAggregator<u16, 3>> = RwWheel::new(0);
+        for (second, code) in [503u16, 200, 404, 200, 500].into_iter().enumerate() {
+            wheel.insert(Entry::new(code, second as u64 * 1000));
+        }
+        wheel.advance(5.seconds());
+
+        // keeps the smallest values and reports that the set is no longer exact
+        let set = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(0, 5000))
+            .unwrap();
+        assert!(!set.is_exact());
+        assert_eq!(set.to_vec(), vec![200, 404, 500]);
+
+        let set = wheel
+            .read()
+            .combine_range(WheelRange::new_unchecked(1000, 4000))
+            .unwrap();
+        assert!(set.is_exact());
+        assert_eq!(set.to_vec(), vec![200, 404]);
+
+        // the result does not depend on the combine order
+        type Set = SmallSetAggregator<u16, 3>;
+        let (a, b) = (Set::lift(503), Set::combine(Set::lift(1), Set::lift(2)));
+        let c = Set::combine(Set::lift(3), Set::lift(4));
+        assert_eq!(
+            Set::combine(Set::combine(a, b), c),
+            Set::combine(a, Set::combine(c, b))
+        );
+    }
+}
@@ -3,7 +3,7 @@ use super::super::Aggregator;
 #[cfg(feature = "simd")]
 use core::simd::prelude::{SimdFloat, SimdInt, SimdUint};
 #[cfg(feature = "simd")]
-use core::simd::{f32x32, f64x32, i16x64, i32x32, i64x32, u16x64, u32x32, u64x32};
+use core::simd::{f32x32, f64x32, i16x64, i64x32, u16x64, u32x32, u64x32};
 
 #[cfg(feature = "simd")]
 use multiversion::multiversion;
@@ -13,12 +13,16 @@ macro_rules! sum_impl {
         sum_impl!($struct, $type, $pa, ());
     };
     ($struct:tt, $type:ty, $pa:tt, $simd: ty) => {
+        sum_impl!($struct, $type, $pa, $simd, core::convert::identity);
+    };
+    ($struct:tt, $type:ty, $pa:tt, $simd: ty, $lower: path) => {
         #[derive(Default, Debug, Clone, Copy)]
         #[allow(missing_docs)]
         pub struct $struct;
 
         impl Aggregator for $struct {
             const IDENTITY: Self::PartialAggregate = 0 as $pa;
+            const TAG: &'static str = stringify!($struct);
 
             type Input = $type;
             type MutablePartialAggregate = $pa;
@@ -30,7 +34,14 @@ macro_rules! sum_impl {
             }
             #[inline]
             fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
-                *a += input;
+                *a += <$pa>::from(input);
+            }
+            #[inline]
+            fn combine_mutable_bulk(
+                a: &mut Self::MutablePartialAggregate,
+                inputs: impl Iterator<Item = Self::Input>,
+            ) {
+                *a += inputs.map(<$pa>::from).sum::<$pa>();
             }
             fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
                 a.into()
@@ -69,7 +80,29 @@ macro_rules! sum_impl {
 
             #[inline]
             fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
-                a.into()
+                $lower(a)
+            }
+
+            #[inline]
+            fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+                Some(a as f64)
+            }
+
+            #[inline]
+            fn identity_is_noop() -> bool {
+                true
+            }
+
+            #[inline]
+            fn monotonic() -> bool {
+                // sums only grow if no input can be negative
+                <$type>::MIN == 0 as $type
+            }
+
+            #[inline]
+            fn exact_inverse() -> bool {
+                // the inverse clamps at zero and floats lose precision
+                <$type>::MIN == 0 as $type
             }
 
             #[inline]
@@ -132,10 +165,17 @@ sum_impl!(I16SumAggregator, i16, i16);
 #[cfg(feature = "simd")]
 sum_impl!(I16SumAggregator, i16, i16, i16x64);
 
+// accumulates into i64 so that sums over long ranges do not overflow
 #[cfg(not(feature = "simd"))]
-sum_impl!(I32SumAggregator, i32, i32);
+sum_impl!(I32SumAggregator, i32, i64, (), saturating_i32);
 #[cfg(feature = "simd")]
-sum_impl!(I32SumAggregator, i32, i32, i32x32);
+sum_impl!(I32SumAggregator, i32, i64, i64x32, saturating_i32);
+
+// Lowers a widened i64 sum to i32 by saturating at the bounds of i32
+#[inline]
+fn saturating_i32(sum: i64) -> i32 {
+    sum.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+}
 
 #[cfg(not(feature = "simd"))]
 sum_impl!(I64SumAggregator, i64, i64);
@@ -152,6 +192,85 @@ sum_impl!(F64SumAggregator, f64, f64);
 #[cfg(feature = "simd")]
 sum_impl!(F64SumAggregator, f64, f64, f64x32);
 
+/// Compensated SUM aggregation of f64 values
+///
+/// The partial aggregate is a `(sum, compensation)` pair where the compensation tracks the low-order
+/// bits that were lost when adding to the running sum. Both inserts and [Aggregator::combine] apply
+/// Neumaier's variant of Kahan summation, so sums across many slots stay accurate where
+/// [F64SumAggregator] would drift. Lowering returns the compensated total `sum + compensation`.
+///
+/// Combining is not exactly associative in floating point, which means different query plans
+/// (e.g., a landmark versus a range over the same slots) may differ in the last bits. The error
+/// stays bounded independent of the number of combined partials. No inverse combine is provided
+/// since subtracting partials would reintroduce the cancellation error that compensation avoids.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::sum::KahanSumAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<KahanSumAggregator> = RwWheel::new(0);
+/// wheel.insert(Entry::new(1e16, 0));
+/// wheel.insert(Entry::new(1.0, 1000));
+/// wheel.insert(Entry::new(-1e16, 2000));
+/// wheel.advance(3.seconds());
+///
+/// assert_eq!(wheel.read().interval_and_lower(3.seconds()), Some(1.0));
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct KahanSumAggregator;
+
+impl Aggregator for KahanSumAggregator {
+    const IDENTITY: Self::PartialAggregate = (0.0, 0.0);
+    const TAG: &'static str = "KahanSumAggregator";
+
+    type Input = f64;
+    type MutablePartialAggregate = (f64, f64);
+    type PartialAggregate = (f64, f64);
+    type Aggregate = f64;
+
+    #[inline]
+    fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+        (input, 0.0)
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+        *a = Self::combine(*a, (input, 0.0));
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        let sum = a.0 + b.0;
+        // recover the low-order bits of whichever operand lost them in the addition
+        let lost = if a.0.abs() >= b.0.abs() {
+            (a.0 - sum) + b.0
+        } else {
+            (b.0 - sum) + a.0
+        };
+        (sum, a.1 + b.1 + lost)
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a.0 + a.1
+    }
+
+    #[inline]
+    fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+        Some(a.0 + a.1)
+    }
+
+    #[inline]
+    fn identity_is_noop() -> bool {
+        true
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{duration::NumericalDuration, Entry, RwWheel};
@@ -179,6 +298,82 @@ mod tests {
         assert_eq!(U64SumAggregator::prefix_query(&prefix_sum, 0, 2), Some(6));
     }
 
+    #[test]
+    fn combine_mutable_bulk_test() {
+        let inputs = (0..1000u64).map(|i| i * 7 % 31);
+
+        let mut single = U64SumAggregator::lift(5);
+        for input in inputs.clone() {
+            U64SumAggregator::combine_mutable(&mut single, input);
+        }
+
+        let mut bulk = U64SumAggregator::lift(5);
+        U64SumAggregator::combine_mutable_bulk(&mut bulk, inputs);
+        assert_eq!(bulk, single);
+
+        // empty batches leave the partial untouched
+        U64SumAggregator::combine_mutable_bulk(&mut bulk, core::iter::empty());
+        assert_eq!(bulk, single);
+    }
+
+    #[test]
+    fn i32_sum_overflow_test() {
+        let mut wheel = RwWheel::<I32SumAggregator>::new(0);
+        for second in 0..4u64 {
+            wheel.insert(Entry::new(i32::MAX, second * 1000));
+        }
+        wheel.insert(Entry::new(i32::MIN, 4000));
+        wheel.advance(5.seconds());
+
+        // the partial aggregate holds the exact sum
+        let max = i32::MAX as i64;
+        assert_eq!(
+            wheel.read().interval(4.seconds()),
+            Some(3 * max + i32::MIN as i64)
+        );
+        assert_eq!(
+            wheel.read().interval(5.seconds()),
+            Some(4 * max + i32::MIN as i64)
+        );
+
+        // lowering saturates at the bounds of i32
+        assert_eq!(wheel.read().interval_and_lower(5.seconds()), Some(i32::MAX));
+        assert_eq!(I32SumAggregator::lower(i64::MIN), i32::MIN);
+        assert_eq!(I32SumAggregator::lower(-7), -7);
+    }
+
+    #[test]
+    fn kahan_sum_test() {
+        let mut kahan = RwWheel::<KahanSumAggregator>::new(0);
+        let mut naive = RwWheel::<F64SumAggregator>::new(0);
+        let seconds = 180u64;
+        // large magnitudes that swallow the small values in naive f64 arithmetic
+        let value = |i: u64| match i % 3 {
+            0 => 1e16,
+            1 => 0.1,
+            _ => -1e16,
+        };
+        for i in 0..seconds {
+            for _ in 0..10 {
+                kahan.insert(Entry::new(value(i), i * 1000));
+                naive.insert(Entry::new(value(i), i * 1000));
+            }
+        }
+        kahan.advance_to(seconds * 1000);
+        naive.advance_to(seconds * 1000);
+
+        // 60 seconds with ten inserts of 0.1 each
+        let exact = 60.0;
+        let kahan_sum = kahan.read().interval_and_lower(180.seconds()).unwrap();
+        let naive_sum = naive.read().interval_and_lower(180.seconds()).unwrap();
+        assert!((kahan_sum - exact).abs() < 1e-9);
+        assert!((kahan_sum - exact).abs() < (naive_sum - exact).abs());
+
+        // combining partials also compensates
+        let partial = KahanSumAggregator::combine_slice(&[(1e16, 0.0), (1.0, 0.0), (-1e16, 0.0)]);
+        assert_eq!(partial.map(KahanSumAggregator::lower), Some(1.0));
+    }
+
     #[cfg(feature = "simd")]
     #[test]
     fn combine_simd() {
@@ -1,5 +1,8 @@
 use crate::aggregator::Aggregator;
-use core::{fmt::Debug, marker::PhantomData};
+use core::{cmp::Ordering, fmt::Debug, marker::PhantomData};
+
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String};
 
 mod entry;
 mod key;
@@ -39,12 +42,28 @@ where
     A::PartialAggregate: Ord + Copy,
 {
     const IDENTITY: Self::PartialAggregate = TopNState::identity();
+    const TAG: &'static str = "TopNAggregator";
 
     type Input = (Key, A::Input);
     type MutablePartialAggregate = TopNMap<Key, A>;
     type PartialAggregate = TopNState<Key, N, A>;
     type Aggregate = TopNState<Key, N, A>;
 
+    fn qualified_tag() -> String {
+        let order = match OrderBy::ordering() {
+            Ordering::Less => "Ascending",
+            _ => "Descending",
+        };
+        format!(
+            "{}<{}, {}, {}, {}>",
+            Self::TAG,
+            core::mem::size_of::<Key>(),
+            N,
+            A::qualified_tag(),
+            order
+        )
+    }
+
     #[inline]
     fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
         let mut map = TopNMap::default();
@@ -0,0 +1,96 @@
+use crate::aggregator::{Aggregator, InverseFn, PartialAggregateType};
+
+impl PartialAggregateType for () {}
+
+/// Unit aggregator that performs no aggregation
+///
+/// The partial aggregate is `()` and all operations are trivial, which makes it possible to use
+/// µWheel purely for its time-driven machinery such as windows and timers.
+/// It also serves as a minimal reference implementation of the [Aggregator] trait.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::unit::UnitAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<UnitAggregator> = RwWheel::new(0);
+/// wheel.insert(Entry::new((), 1000));
+/// wheel.advance(5.seconds());
+/// assert_eq!(wheel.watermark(), 5000);
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct UnitAggregator;
+
+impl Aggregator for UnitAggregator {
+    const IDENTITY: Self::PartialAggregate = ();
+    const TAG: &'static str = "UnitAggregator";
+
+    type Input = ();
+    type MutablePartialAggregate = ();
+    type PartialAggregate = ();
+    type Aggregate = ();
+
+    #[inline]
+    fn lift(_input: Self::Input) -> Self::MutablePartialAggregate {}
+
+    #[inline]
+    fn combine_mutable(_a: &mut Self::MutablePartialAggregate, _input: Self::Input) {}
+
+    #[inline]
+    fn freeze(_a: Self::MutablePartialAggregate) -> Self::PartialAggregate {}
+
+    #[inline]
+    fn combine(_a: Self::PartialAggregate, _b: Self::PartialAggregate) -> Self::PartialAggregate {}
+
+    #[inline]
+    fn lower(_a: Self::PartialAggregate) -> Self::Aggregate {}
+
+    #[inline]
+    fn combine_inverse() -> Option<InverseFn<Self::PartialAggregate>> {
+        Some(|_, _| ())
+    }
+
+    #[inline]
+    fn exact_inverse() -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{duration::NumericalDuration, Entry, RwWheel, Window, WindowAggregate};
+
+    #[test]
+    fn unit_window_boundaries_test() {
+        let mut wheel: RwWheel<UnitAggregator> = RwWheel::new(0);
+        wheel.window(Window::sliding(30.seconds(), 10.seconds()));
+        wheel.insert(Entry::new((), 5000));
+
+        // no window has been completed yet
+        assert!(wheel.advance_to(29000).is_empty());
+
+        let results = wheel.advance_to(50000);
+        assert_eq!(
+            results,
+            [
+                WindowAggregate {
+                    window_start_ms: 0,
+                    window_end_ms: 30000,
+                    aggregate: (),
+                },
+                WindowAggregate {
+                    window_start_ms: 10000,
+                    window_end_ms: 40000,
+                    aggregate: (),
+                },
+                WindowAggregate {
+                    window_start_ms: 20000,
+                    window_end_ms: 50000,
+                    aggregate: (),
+                },
+            ]
+        );
+        assert_eq!(wheel.watermark(), 50000);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::aggregator::Aggregator;
+
+/// Aggregator that computes the weighted mean of values carrying a per-entry weight
+///
+/// Values are inserted together with their weight, i.e., `(value, weight)`. The partial aggregate
+/// is `(weighted_sum, weight_total)` which combines by adding both components, and lowering divides
+/// the weighted sum by the total weight. Unlike the avg aggregators where every value counts once,
+/// the weight of each entry may vary.
+///
+/// Use [Aggregator::lower_f64] to get `None` rather than `NaN` for ranges without any weight.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::weighted::WeightedSumAggregator, Entry, RwWheel, NumericalDuration};
+///
+/// let mut wheel: RwWheel<WeightedSumAggregator> = RwWheel::new(0);
+/// wheel.insert(Entry::new((10.0, 1.0), 0));
+/// wheel.insert(Entry::new((40.0, 2.0), 1000));
+/// wheel.advance(2.seconds());
+///
+/// assert_eq!(wheel.read().interval(2.seconds()), Some((90.0, 3.0)));
+/// assert_eq!(wheel.read().interval_and_lower(2.seconds()), Some(30.0));
+/// ```
+#[derive(Default, Debug, Clone, Copy)]
+pub struct WeightedSumAggregator;
+
+impl Aggregator for WeightedSumAggregator {
+    const IDENTITY: Self::PartialAggregate = (0.0, 0.0);
+    const TAG: &'static str = "WeightedSumAggregator";
+
+    type Input = (f64, f64);
+    type MutablePartialAggregate = (f64, f64);
+    type PartialAggregate = (f64, f64);
+    type Aggregate = f64;
+
+    #[inline]
+    fn lift((value, weight): Self::Input) -> Self::MutablePartialAggregate {
+        (value * weight, weight)
+    }
+
+    #[inline]
+    fn combine_mutable(a: &mut Self::MutablePartialAggregate, (value, weight): Self::Input) {
+        a.0 += value * weight;
+        a.1 += weight;
+    }
+
+    fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+        a
+    }
+
+    #[inline]
+    fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+        (a.0 + b.0, a.1 + b.1)
+    }
+
+    #[inline]
+    fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+        a.0 / a.1
+    }
+
+    #[inline]
+    fn lower_f64(a: Self::PartialAggregate) -> Option<f64> {
+        if a.1 == 0.0 {
+            None
+        } else {
+            Some(a.0 / a.1)
+        }
+    }
+
+    #[inline]
+    fn combine_inverse(
+    ) -> Option<fn(Self::PartialAggregate, Self::PartialAggregate) -> Self::PartialAggregate> {
+        Some(|a, b| (a.0 - b.0, a.1 - b.1))
+    }
+
+    #[inline]
+    fn identity_is_noop() -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Conf, Entry, HawConf, RwWheel, WheelRange};
+
+    #[test]
+    fn weighted_mean_test() {
+        let mut wheel: RwWheel<WeightedSumAggregator> = RwWheel::new(0);
+        let seconds = 180u64;
+        // (value, weight) pairs with weights that vary per entry
+        let pair = |i: u64| (((i * 37) % 101) as f64, (i % 5 + 1) as f64);
+
+        for i in 0..seconds {
+            wheel.insert(Entry::new(pair(i), i * 1000));
+        }
+        wheel.advance_to(seconds * 1000);
+
+        let expected = |seconds: core::ops::Range<u64>| {
+            let (sum, weight) = seconds.map(pair).fold((0.0, 0.0), |(sum, weight), (v, w)| {
+                (sum + v * w, weight + w)
+            });
+            sum / weight
+        };
+
+        // spans the minutes wheel only, minutes and seconds, and a partial seconds range
+        for (start, end) in [(0, 180), (60, 180), (120, 180), (150, 180)] {
+            let range = WheelRange::new_unchecked(start * 1000, end * 1000);
+            let mean = wheel.read().combine_range_and_lower(range).unwrap();
+            assert!((mean - expected(start..end)).abs() < 1e-9);
+        }
+
+        // prefix-enabled wheels give the same result through the inverse combine
+        let mut prefix: RwWheel<WeightedSumAggregator> =
+            RwWheel::with_conf(Conf::default().with_haw_conf(HawConf::default().with_prefix_sum()));
+        for i in 0..seconds {
+            prefix.insert(Entry::new(pair(i), i * 1000));
+        }
+        prefix.advance_to(seconds * 1000);
+        let range = WheelRange::new_unchecked(130000, 170000);
+        let mean = prefix.read().combine_range_and_lower(range).unwrap();
+        assert!((mean - expected(130..170)).abs() < 1e-9);
+
+        assert_eq!(WeightedSumAggregator::lower_f64((0.0, 0.0)), None);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::{wheels::read::aggregation::combine_or_insert, Aggregator, WheelRange};
+
 #[cfg(not(feature = "std"))]
 use alloc::vec::Vec;
 
@@ -37,10 +39,90 @@ impl<T> DeltaState<T> {
     }
 }
 
+/// Merges aligned delta streams from multiple sources into a single stream
+///
+/// Deltas at the same position of each stream are combined through [Aggregator::combine] in stream order.
+/// Streams of differing lengths are treated as `None` beyond their end, so the merged stream is as long
+/// as the longest input stream. The result may be applied to a wheel through [crate::Haw::delta_advance].
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{merge_delta_streams, aggregator::sum::U32SumAggregator};
+///
+/// let a = vec![Some(1), None, Some(3)];
+/// let b = vec![Some(10), Some(20)];
+/// let merged = merge_delta_streams::<U32SumAggregator>([a, b]);
+/// assert_eq!(merged, vec![Some(11), Some(20), Some(3)]);
+/// ```
+pub fn merge_delta_streams<A: Aggregator>(
+    streams: impl IntoIterator<Item = impl IntoIterator<Item = Option<A::PartialAggregate>>>,
+) -> Vec<Option<A::PartialAggregate>> {
+    let mut merged: Vec<Option<A::PartialAggregate>> = Vec::new();
+    for stream in streams {
+        for (i, delta) in stream.into_iter().enumerate() {
+            if i == merged.len() {
+                merged.push(None);
+            }
+            if let Some(delta) = delta {
+                combine_or_insert::<A>(&mut merged[i], delta);
+            }
+        }
+    }
+    merged
+}
+
+/// Combines the deltas of a [DeltaState] that fall within the given range [start, end)
+///
+/// Answers a range query directly over a sequence of deltas without building and advancing a [crate::Haw],
+/// where each delta represents a second starting from [DeltaState::oldest_ts]. Seconds without a delta are skipped.
+///
+/// Returns `None` if the range is empty or not fully covered by the deltas
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{combine_range_over_deltas, aggregator::sum::U32SumAggregator, DeltaState, WheelRange};
+///
+/// let state = DeltaState::new(10000, vec![Some(10), None, Some(5), Some(15)]);
+/// let range = WheelRange::new_unchecked(11000, 14000);
+/// assert_eq!(combine_range_over_deltas::<U32SumAggregator>(&state, range), Some(20));
+/// assert_eq!(combine_range_over_deltas::<U32SumAggregator>(&state, WheelRange::new_unchecked(9000, 12000)), None);
+/// ```
+pub fn combine_range_over_deltas<A: Aggregator>(
+    state: &DeltaState<A::PartialAggregate>,
+    range: impl Into<WheelRange>,
+) -> Option<A::PartialAggregate> {
+    let range = range.into();
+    let oldest = state.oldest_ts / 1000;
+    let start = range.start.unix_timestamp() as u64;
+    let end = range.end.unix_timestamp() as u64;
+
+    if start >= end || start < oldest || end > oldest + state.deltas.len() as u64 {
+        return None;
+    }
+
+    let slots = &state.deltas[(start - oldest) as usize..(end - oldest) as usize];
+    Some(
+        slots
+            .iter()
+            .flatten()
+            .fold(A::IDENTITY, |acc, delta| A::combine(acc, *delta)),
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::{aggregator::sum::U32SumAggregator, wheels::read::ReaderWheel, NumericalDuration};
+    use crate::{
+        aggregator::sum::U32SumAggregator,
+        wheels::read::ReaderWheel,
+        Haw,
+        HawConf,
+        NumericalDuration,
+        RetentionPolicy,
+        RwWheel,
+    };
 
     #[test]
     fn build_wheel_from_delta_state_test() {
@@ -85,4 +167,88 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn combine_range_over_deltas_test() {
+        // 2023-11-09 00:00:00
+        let start = 1699488000000;
+        let deltas: Vec<Option<u32>> = (0..7200u32)
+            .map(|sec| (sec % 7 != 0).then_some(sec % 13))
+            .collect();
+        let state = DeltaState::new(start, deltas.clone());
+
+        let conf = HawConf::default()
+            .with_watermark(start)
+            .with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U32SumAggregator> = Haw::new(conf);
+        haw.delta_advance(deltas);
+
+        let ranges = [
+            (0, 1),
+            (0, 7200),
+            (15, 45),
+            (60, 3600),
+            (3600, 7200),
+            (59, 3661),
+            (7199, 7200),
+        ];
+        for (from, to) in ranges {
+            let range = WheelRange::new_unchecked(start + from * 1000, start + to * 1000);
+            assert_eq!(
+                combine_range_over_deltas::<U32SumAggregator>(&state, range),
+                haw.combine_range(range),
+                "range [{from}, {to})"
+            );
+        }
+
+        // ranges outside the deltas
+        let range = WheelRange::new_unchecked(start - 1000, start + 1000);
+        assert_eq!(
+            combine_range_over_deltas::<U32SumAggregator>(&state, range),
+            None
+        );
+        let range = WheelRange::new_unchecked(start, start + 7201 * 1000);
+        assert_eq!(
+            combine_range_over_deltas::<U32SumAggregator>(&state, range),
+            None
+        );
+    }
+
+    #[test]
+    fn merge_delta_streams_test() {
+        // two shards emitting per-second deltas of differing lengths
+        let shard_a: Vec<Option<u32>> = (0..50u32).map(|s| (s % 3 != 0).then_some(s)).collect();
+        let shard_b: Vec<Option<u32>> = (0..35u32)
+            .map(|s| (s % 5 == 0).then_some(100 + s))
+            .collect();
+
+        let merged = merge_delta_streams::<U32SumAggregator>([shard_a.clone(), shard_b.clone()]);
+        assert_eq!(merged.len(), 50);
+        assert_eq!(merged[0], Some(100));
+        assert_eq!(merged[3], None);
+        assert_eq!(merged[40], Some(40));
+
+        let mut fan_in: Haw<U32SumAggregator> = Haw::default();
+        fan_in.delta_advance(merged);
+
+        // applying the partials of both shards to a single wheel
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        for shard in [&shard_a, &shard_b] {
+            for (second, delta) in shard.iter().enumerate() {
+                if let Some(partial) = delta {
+                    wheel.insert_partial(*partial, second as u64 * 1000);
+                }
+            }
+        }
+        wheel.advance(50.seconds());
+
+        let range = WheelRange::new_unchecked(0, 50000);
+        assert_eq!(fan_in.range(range), wheel.read().range(range));
+        assert_eq!(
+            fan_in.interval(50.seconds()),
+            wheel.read().interval(50.seconds())
+        );
+
+        assert!(merge_delta_streams::<U32SumAggregator>(Vec::<Vec<Option<u32>>>::new()).is_empty());
+    }
 }
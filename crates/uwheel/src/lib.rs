@@ -17,8 +17,22 @@
 //!    - Enables min-max aggregation
 //! - `all` (_enabled by default_)
 //!    - Enables all aggregation
+//! - `bloom` (_enabled by default_)
+//!    - Enables bloom filter aggregation
+//! - `collect` (_enabled by default_)
+//!    - Enables collecting raw values of short ranges
+//! - `unit` (_enabled by default_)
+//!    - Enables the unit aggregator that performs no aggregation
+//! - `ewma` (_enabled by default_, _implicitly enables `std`_)
+//!    - Enables exponentially-weighted moving average aggregation
+//! - `weighted` (_enabled by default_)
+//!    - Enables weighted mean aggregation of `(value, weight)` entries
+//! - `small_set` (_enabled by default_)
+//!    - Enables tracking the exact set of distinct values of low-cardinality dimensions
 //! - `top_n`
 //!    - Enables Top-N aggregation
+//! - `keyed`
+//!    - Enables ``KeyedWheel`` for maintaining a wheel per key
 //! - `simd` (_requires `nightly`_)
 //!    - Enables support to speed up aggregation functions with SIMD operations
 //! - `sync` (_implicitly enables `std`_)
@@ -29,6 +43,8 @@
 //!    - Enables serde support
 //! - `timer`
 //!    - Enables scheduling user-defined functions
+//! - `test-util`
+//!    - Enables generating deterministic synthetic workloads for benchmarks and tests
 #![cfg_attr(docsrs, feature(doc_auto_cfg))]
 #![cfg_attr(feature = "simd", feature(portable_simd))]
 #![cfg_attr(not(feature = "std"), no_std)]
@@ -55,7 +71,7 @@ pub mod duration;
 /// Various wheels used by µWheel
 pub mod wheels;
 
-pub use delta::DeltaState;
+pub use delta::{combine_range_over_deltas, merge_delta_streams, DeltaState};
 pub use duration::{Duration, NumericalDuration};
 
 #[macro_use]
@@ -64,10 +80,34 @@ mod macros;
 
 pub use aggregator::Aggregator;
 
+#[cfg(feature = "test-util")]
+pub use wheels::read::{SyntheticSpec, ValueDistribution};
 pub use wheels::{
     read::{
         aggregation::conf::{CompressionPolicy, RetentionPolicy, WheelConf},
-        hierarchical::{Haw, HawConf, WheelRange},
+        hierarchical::{
+            combine_range_across,
+            estimated_bytes,
+            total_slots,
+            AdaptiveLayout,
+            ConcatError,
+            Coverage,
+            CycleExceeded,
+            DecimatedPoint,
+            DiffError,
+            EvictionFn,
+            Granularity,
+            Haw,
+            HawConf,
+            LevelSize,
+            MergeError,
+            QueryError,
+            QueryResult,
+            SeedError,
+            SizeBreakdown,
+            WheelDiff,
+            WheelRange,
+        },
     },
     Conf,
     RwWheel,
@@ -76,6 +116,7 @@ pub use window::{Window, WindowAggregate};
 
 #[doc(hidden)]
 pub use time::OffsetDateTime;
+pub use time::UtcOffset;
 #[doc(hidden)]
 pub use wheels::read::{DAYS, HOURS, MINUTES, SECONDS, WEEKS, YEARS};
 
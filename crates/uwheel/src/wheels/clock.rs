@@ -0,0 +1,230 @@
+use std::{
+    sync::mpsc::{self, Receiver, Sender},
+    thread,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use super::RwWheel;
+use crate::{aggregator::Aggregator, window::WindowAggregate};
+
+/// A source of the current time used to drive a [RwWheel] through a [ClockDriver]
+///
+/// Implemented for closures returning the time which is useful for mocking time in simulations and tests.
+pub trait Clock {
+    /// Returns the current time in milliseconds since unix epoch
+    fn now(&self) -> u64;
+}
+
+impl<F: Fn() -> u64> Clock for F {
+    fn now(&self) -> u64 {
+        self()
+    }
+}
+
+/// A [Clock] reading the wall-clock time of the system
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_millis() as u64)
+    }
+}
+
+/// Advances a [RwWheel] to the current time of a [Clock] and sends emitted windows through a channel
+///
+/// Created through [RwWheel::with_clock]. Entries are inserted through [Self::wheel_mut] while the driver is
+/// polled periodically, either by the caller through [Self::poll] or by blocking on [Self::run_until].
+pub struct ClockDriver<A: Aggregator, C: Clock> {
+    wheel: RwWheel<A>,
+    clock: C,
+    sender: Sender<WindowAggregate<A::PartialAggregate>>,
+}
+
+impl<A: Aggregator, C: Clock> ClockDriver<A, C> {
+    /// Advances the wheel to the current time of the clock
+    ///
+    /// Windows emitted while advancing are sent through the channel returned by [RwWheel::with_clock].
+    /// Returns the number of emitted windows.
+    pub fn poll(&mut self) -> usize {
+        let now = self.clock.now();
+        if now <= self.wheel.watermark() {
+            return 0;
+        }
+        let windows = self.wheel.advance_to(now);
+        let emitted = windows.len();
+        for window in windows {
+            // the receiver may have been dropped if the caller is not interested in windows
+            let _ = self.sender.send(window);
+        }
+        emitted
+    }
+
+    /// Polls the driver every `interval` until `stop` returns `true`
+    ///
+    /// Blocks the current thread while sleeping between polls.
+    pub fn run_until(
+        &mut self,
+        interval: core::time::Duration,
+        mut stop: impl FnMut(&RwWheel<A>) -> bool,
+    ) {
+        loop {
+            self.poll();
+            if stop(&self.wheel) {
+                return;
+            }
+            thread::sleep(interval);
+        }
+    }
+
+    /// Returns a reference to the driven wheel
+    pub fn wheel(&self) -> &RwWheel<A> {
+        &self.wheel
+    }
+
+    /// Returns a mutable reference to the driven wheel for inserting entries
+    pub fn wheel_mut(&mut self) -> &mut RwWheel<A> {
+        &mut self.wheel
+    }
+
+    /// Returns a reference to the clock driving the wheel
+    pub fn clock(&self) -> &C {
+        &self.clock
+    }
+
+    /// Consumes the driver and returns the wheel
+    pub fn into_inner(self) -> RwWheel<A> {
+        self.wheel
+    }
+}
+
+impl<A: Aggregator> RwWheel<A> {
+    /// Turns the wheel into a self-driving aggregator advanced by the given [Clock]
+    ///
+    /// Returns a [ClockDriver] that advances the wheel to the current time of the clock whenever it is polled,
+    /// together with a channel receiving the windows installed through [RwWheel::window] as they are emitted.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Entry, NumericalDuration, RwWheel, Window};
+    /// use std::{cell::Cell, rc::Rc};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.window(Window::tumbling(2.seconds()));
+    ///
+    /// // a mock clock controlled by the caller
+    /// let time = Rc::new(Cell::new(0));
+    /// let clock = time.clone();
+    /// let (mut driver, windows) = wheel.with_clock(move || clock.get());
+    ///
+    /// driver.wheel_mut().insert(Entry::new(5, 1000));
+    /// time.set(2000);
+    /// assert_eq!(driver.poll(), 1);
+    /// assert_eq!(windows.try_recv().unwrap().aggregate, 5);
+    /// ```
+    pub fn with_clock<C: Clock>(
+        self,
+        clock: C,
+    ) -> (
+        ClockDriver<A, C>,
+        Receiver<WindowAggregate<A::PartialAggregate>>,
+    ) {
+        let (sender, receiver) = mpsc::channel();
+        let driver = ClockDriver {
+            wheel: self,
+            clock,
+            sender,
+        };
+        (driver, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use core::cell::Cell;
+    use std::rc::Rc;
+
+    use super::*;
+    use crate::{aggregator::sum::U32SumAggregator, duration::*, Entry, Window};
+
+    #[test]
+    fn clock_driver_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        wheel.window(Window::tumbling(10.seconds()));
+
+        let time = Rc::new(Cell::new(0));
+        let clock = time.clone();
+        let (mut driver, windows) = wheel.with_clock(move || clock.get());
+
+        for second in 0..35 {
+            driver.wheel_mut().insert(Entry::new(1, second * 1000));
+        }
+
+        // no window has ended yet
+        time.set(5000);
+        assert_eq!(driver.poll(), 0);
+        assert_eq!(driver.wheel().watermark(), 5000);
+        assert!(windows.try_recv().is_err());
+
+        time.set(10000);
+        assert_eq!(driver.poll(), 1);
+
+        // the clock may jump past several windows between polls
+        time.set(35500);
+        assert_eq!(driver.poll(), 2);
+
+        let emitted: Vec<_> = windows
+            .try_iter()
+            .map(|w| (w.window_start_ms, w.window_end_ms, w.aggregate))
+            .collect();
+        assert_eq!(
+            emitted,
+            vec![(0, 10000, 10), (10000, 20000, 10), (20000, 30000, 10)]
+        );
+
+        // a clock behind the watermark does not advance the wheel
+        time.set(20000);
+        assert_eq!(driver.poll(), 0);
+
+        // runs until the next window has been emitted
+        let step = time.clone();
+        driver.run_until(core::time::Duration::ZERO, |wheel| {
+            step.set(step.get() + 1000);
+            wheel.watermark() >= 40000
+        });
+        assert_eq!(windows.try_recv().unwrap().window_end_ms, 40000);
+        assert_eq!(driver.into_inner().watermark(), 40000);
+    }
+
+    // timer actions and profiler stats are not Send
+    #[cfg(all(feature = "sync", not(feature = "timer"), not(feature = "profiler")))]
+    #[test]
+    fn clock_driver_thread_test() {
+        use std::sync::{
+            atomic::{AtomicU64, Ordering},
+            Arc,
+        };
+
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        wheel.window(Window::tumbling(2.seconds()));
+        wheel.on_tick(|_, _| {});
+
+        let time = Arc::new(AtomicU64::new(0));
+        let clock = time.clone();
+        let (mut driver, windows) = wheel.with_clock(move || clock.load(Ordering::Relaxed));
+        driver.wheel_mut().insert(Entry::new(3, 1000));
+        time.store(4000, Ordering::Relaxed);
+
+        // the driver may be moved onto a dedicated thread
+        let handle = thread::spawn(move || {
+            driver.poll();
+            driver
+        });
+        let driver = handle.join().expect("Failed to join the thread.");
+        assert_eq!(driver.wheel().watermark(), 4000);
+        assert_eq!(windows.try_iter().map(|w| w.aggregate).sum::<u32>(), 3);
+    }
+}
@@ -0,0 +1,299 @@
+use core::{cmp::Reverse, hash::Hash};
+use hashbrown::HashMap;
+
+#[cfg(not(feature = "std"))]
+use alloc::{collections::BinaryHeap, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::BinaryHeap;
+
+use super::{read::hierarchical::WheelRange, Conf, RwWheel};
+use crate::{aggregator::Aggregator, duration::Duration, Entry};
+
+/// A keyed wheel maintaining one [RwWheel] per key
+///
+/// Enables group-by analytics over time without managing a wheel per key manually.
+/// All sub-wheels share the same configuration and are advanced together so that their watermarks stay aligned.
+/// Wheels for new keys are created lazily at the current watermark of the keyed wheel.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{aggregator::sum::U32SumAggregator, wheels::KeyedWheel, Entry, NumericalDuration, WheelRange};
+///
+/// let mut wheel: KeyedWheel<&str, U32SumAggregator> = KeyedWheel::new(0);
+/// wheel.insert("a", Entry::new(10, 1000));
+/// wheel.insert("b", Entry::new(20, 1000));
+/// wheel.advance(2.seconds());
+///
+/// let range = WheelRange::new_unchecked(1000, 2000);
+/// let grouped = wheel.combine_range(range);
+/// assert_eq!(grouped.get("a"), Some(&10));
+/// assert_eq!(wheel.combine_range_key(&"b", range), Some(20));
+/// ```
+pub struct KeyedWheel<K, A>
+where
+    K: Hash + Eq + Clone,
+    A: Aggregator,
+{
+    /// Configuration used for every sub-wheel
+    conf: Conf,
+    /// The shared low watermark of all sub-wheels
+    watermark: u64,
+    /// Wheel per key
+    wheels: HashMap<K, RwWheel<A>>,
+}
+
+impl<K, A> KeyedWheel<K, A>
+where
+    K: Hash + Eq + Clone,
+    A: Aggregator,
+{
+    /// Creates a new keyed wheel starting from the given time
+    pub fn new(time: u64) -> Self {
+        Self::with_conf(Conf::default(), time)
+    }
+
+    /// Creates a new keyed wheel starting from the given time where every sub-wheel uses the specified configuration
+    pub fn with_conf(conf: Conf, time: u64) -> Self {
+        Self {
+            conf,
+            watermark: time,
+            wheels: HashMap::new(),
+        }
+    }
+
+    /// Returns the current watermark of the keyed wheel
+    pub fn watermark(&self) -> u64 {
+        self.watermark
+    }
+
+    /// Returns the number of keys
+    pub fn len(&self) -> usize {
+        self.wheels.len()
+    }
+
+    /// Returns `true` if no key has been inserted
+    pub fn is_empty(&self) -> bool {
+        self.wheels.is_empty()
+    }
+
+    /// Returns an iterator over the keys of the wheel
+    pub fn keys(&self) -> impl Iterator<Item = &K> {
+        self.wheels.keys()
+    }
+
+    /// Returns a reference to the wheel of the given key
+    pub fn wheel(&self, key: &K) -> Option<&RwWheel<A>> {
+        self.wheels.get(key)
+    }
+
+    /// Inserts an entry into the wheel of the given key
+    ///
+    /// Entries with timestamps below the current low watermark ([Self::watermark]) are dropped.
+    #[inline]
+    pub fn insert(&mut self, key: K, e: impl Into<Entry<A::Input>>) {
+        let (conf, watermark) = (self.conf, self.watermark);
+        self.wheels
+            .entry(key)
+            .or_insert_with(|| Self::new_wheel(conf, watermark))
+            .insert(e);
+    }
+
+    /// Advance the watermark of all sub-wheels by the given [Duration]
+    #[inline]
+    pub fn advance(&mut self, duration: Duration) {
+        let to = self.watermark + duration.whole_milliseconds() as u64;
+        self.advance_to(to);
+    }
+
+    /// Advances the watermark of all sub-wheels to the specified watermark
+    pub fn advance_to(&mut self, watermark: u64) {
+        for wheel in self.wheels.values_mut() {
+            wheel.advance_to(watermark);
+        }
+        self.watermark = watermark;
+    }
+
+    /// Combines partial aggregates within the given range for every key
+    ///
+    /// Keys whose wheel cannot answer the range are omitted from the result.
+    pub fn combine_range(&self, range: impl Into<WheelRange>) -> HashMap<K, A::PartialAggregate> {
+        let range = range.into();
+        self.wheels
+            .iter()
+            .filter_map(|(key, wheel)| {
+                wheel
+                    .read()
+                    .combine_range(range)
+                    .map(|partial| (key.clone(), partial))
+            })
+            .collect()
+    }
+
+    /// Combines partial aggregates within the given range for a single key
+    ///
+    /// Returns `None` if the key does not exist or its wheel cannot answer the range.
+    pub fn combine_range_key(
+        &self,
+        key: &K,
+        range: impl Into<WheelRange>,
+    ) -> Option<A::PartialAggregate> {
+        self.wheels
+            .get(key)
+            .and_then(|wheel| wheel.read().combine_range(range))
+    }
+
+    /// Returns the `n` keys with the largest partial aggregates within the given range
+    ///
+    /// Results are ordered from largest to smallest aggregate and ties are broken by ascending key.
+    /// Uses a bounded heap of size `n` rather than sorting the aggregates of all keys.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, wheels::KeyedWheel, Entry, NumericalDuration, WheelRange};
+    ///
+    /// let mut wheel: KeyedWheel<&str, U32SumAggregator> = KeyedWheel::new(0);
+    /// wheel.insert("a", Entry::new(10, 1000));
+    /// wheel.insert("b", Entry::new(30, 1000));
+    /// wheel.insert("c", Entry::new(20, 1000));
+    /// wheel.advance(2.seconds());
+    ///
+    /// let top = wheel.top_n(WheelRange::new_unchecked(0, 2000), 2);
+    /// assert_eq!(top, vec![("b", 30), ("c", 20)]);
+    /// ```
+    pub fn top_n(&self, range: impl Into<WheelRange>, n: usize) -> Vec<(K, A::PartialAggregate)>
+    where
+        K: Ord,
+        A::PartialAggregate: Ord,
+    {
+        if n == 0 {
+            return Vec::new();
+        }
+        let range = range.into();
+
+        // min-heap on (aggregate, reversed key) so that the weakest candidate sits on top
+        let mut heap = BinaryHeap::with_capacity(n + 1);
+        for (key, wheel) in self.wheels.iter() {
+            let Some(partial) = wheel.read().combine_range(range) else {
+                continue;
+            };
+            if heap.len() < n {
+                heap.push(Reverse((partial, Reverse(key))));
+            } else if let Some(Reverse(weakest)) = heap.peek() {
+                if (partial, Reverse(key)) > *weakest {
+                    heap.pop();
+                    heap.push(Reverse((partial, Reverse(key))));
+                }
+            }
+        }
+
+        heap.into_sorted_vec()
+            .into_iter()
+            .map(|Reverse((partial, Reverse(key)))| (key.clone(), partial))
+            .collect()
+    }
+
+    fn new_wheel(conf: Conf, watermark: u64) -> RwWheel<A> {
+        let haw_conf = conf.reader_conf.haw_conf.with_watermark(watermark);
+        RwWheel::with_conf(conf.with_haw_conf(haw_conf))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregator::sum::U64SumAggregator, duration::NumericalDuration};
+
+    #[test]
+    fn keyed_combine_range_test() {
+        let keys = ["a", "b", "c"];
+        let mut keyed: KeyedWheel<&str, U64SumAggregator> = KeyedWheel::new(0);
+        let mut baselines: Vec<RwWheel<U64SumAggregator>> =
+            keys.iter().map(|_| RwWheel::new(0)).collect();
+
+        for second in 0..120u64 {
+            for (idx, key) in keys.iter().enumerate() {
+                // skip some seconds for the last key
+                if idx == 2 && second % 3 == 0 {
+                    continue;
+                }
+                let value = second * (idx as u64 + 1);
+                keyed.insert(*key, Entry::new(value, second * 1000));
+                baselines[idx].insert(Entry::new(value, second * 1000));
+            }
+        }
+
+        keyed.advance(2.minutes());
+        for baseline in baselines.iter_mut() {
+            baseline.advance(2.minutes());
+        }
+
+        assert_eq!(keyed.len(), 3);
+        assert_eq!(keyed.watermark(), 120000);
+        for key in keys {
+            assert_eq!(keyed.wheel(&key).unwrap().watermark(), 120000);
+        }
+
+        let ranges = [
+            WheelRange::new_unchecked(0, 120000),
+            WheelRange::new_unchecked(60000, 120000),
+            WheelRange::new_unchecked(90000, 120000),
+        ];
+
+        for range in ranges {
+            let grouped = keyed.combine_range(range);
+            assert_eq!(grouped.len(), keys.len());
+            for (idx, key) in keys.iter().enumerate() {
+                let expected = baselines[idx].read().combine_range(range);
+                assert_eq!(grouped.get(key).copied(), expected);
+                assert_eq!(keyed.combine_range_key(key, range), expected);
+            }
+        }
+        assert_eq!(keyed.combine_range_key(&"d", ranges[0]), None);
+
+        // wheels of late keys start at the current watermark
+        keyed.insert("d", Entry::new(5, 121000));
+        keyed.advance(2.seconds());
+        assert_eq!(keyed.wheel(&"d").unwrap().watermark(), 122000);
+        assert_eq!(
+            keyed.combine_range_key(&"d", WheelRange::new_unchecked(121000, 122000)),
+            Some(5)
+        );
+    }
+
+    #[test]
+    fn keyed_top_n_test() {
+        let mut keyed: KeyedWheel<u32, U64SumAggregator> = KeyedWheel::new(0);
+
+        // skewed data: key k receives k^2 per second, keys 20 and 21 share the same total
+        for key in 0..20u32 {
+            for second in 0..60u64 {
+                keyed.insert(key, Entry::new((key as u64).pow(2), second * 1000));
+            }
+        }
+        for key in [21u32, 20] {
+            keyed.insert(key, Entry::new(19 * 19 * 60, 30000));
+        }
+        keyed.advance(1.minutes());
+
+        let range = WheelRange::new_unchecked(0, 60000);
+        let top = keyed.top_n(range, 4);
+        assert_eq!(
+            top,
+            vec![
+                (19, 19 * 19 * 60),
+                (20, 19 * 19 * 60),
+                (21, 19 * 19 * 60),
+                (18, 18 * 18 * 60)
+            ]
+        );
+
+        // matches a full sort of all keys
+        let mut all: Vec<_> = keyed.combine_range(range).into_iter().collect();
+        all.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        assert_eq!(keyed.top_n(range, 10), all[..10]);
+        assert_eq!(keyed.top_n(range, 100), all);
+        assert!(keyed.top_n(range, 0).is_empty());
+    }
+}
@@ -1,3 +1,9 @@
+/// Clock-driven advancing of wheels in real time
+#[cfg(feature = "std")]
+mod clock;
+/// Keyed wheel maintaining a wheel per key
+#[cfg(feature = "keyed")]
+pub mod keyed;
 /// Reader Wheel
 ///
 /// Single reader or multi-reader with the ``sync`` feature enabled.
@@ -17,19 +23,26 @@ mod stats;
 mod timer;
 
 use crate::{aggregator::Aggregator, duration::Duration, window::WindowAggregate, Entry};
-use core::fmt::Debug;
+use core::{cmp, fmt::Debug};
 use write::DEFAULT_WRITE_AHEAD_SLOTS;
 
+#[cfg(feature = "std")]
+pub use clock::{Clock, ClockDriver, SystemClock};
+#[cfg(feature = "keyed")]
+pub use keyed::KeyedWheel;
 pub use read::{DAYS, HOURS, MINUTES, SECONDS, WEEKS, YEARS};
 pub use wheel_ext::WheelExt;
-pub use write::WriterWheel;
+pub use write::{InsertError, WriterWheel};
 
-use self::read::{hierarchical::HawConf, ReaderWheel};
+use self::read::{
+    hierarchical::{CycleExceeded, Haw, HawConf, MergeError, WheelRange},
+    ReaderWheel,
+};
 
 use crate::window::Window;
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{boxed::Box, vec::Vec};
 
 #[cfg(feature = "profiler")]
 use uwheel_stats::profile_scope;
@@ -84,10 +97,52 @@ where
     writer: WriterWheel<A>,
     /// A multiple-reader wheel designed for efficient querying of aggregate across arbitrary time ranges
     reader: ReaderWheel<A>,
+    /// An optional hook invoked with every sealed second during advance
+    #[cfg_attr(feature = "serde", serde(skip))]
+    tick_hook: Option<TickHook<A>>,
+    /// Operations applied to the wheel if recording has been started
+    #[cfg_attr(feature = "serde", serde(skip))]
+    recorder: Option<Vec<Op<A>>>,
     #[cfg(feature = "profiler")]
     stats: stats::Stats,
 }
 
+/// An operation applied to a [RwWheel] as captured by [RwWheel::start_recording]
+///
+/// Advances are recorded as the watermark they advanced towards so that replaying them
+/// through [RwWheel::replay] performs the exact same ticks.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(bound = ""))]
+#[derive(Debug, Clone)]
+pub enum Op<A: Aggregator> {
+    /// An entry inserted through [RwWheel::insert]
+    Insert(Entry<A::Input>),
+    /// A batch of inputs inserted through [RwWheel::insert_bulk]
+    InsertBulk(u64, Vec<A::Input>),
+    /// A partial aggregate inserted through [RwWheel::insert_partial]
+    InsertPartial(A::PartialAggregate, u64),
+    /// An advance of the watermark towards the given time
+    AdvanceTo(u64),
+}
+
+crate::cfg_not_sync! {
+    /// A user-defined function receiving the timestamp and frozen partial aggregate of a sealed second
+    ///
+    /// See [RwWheel::on_tick] for more information.
+    pub trait TickFn<P>: FnMut(u64, P) + 'static {}
+    impl<P, F: FnMut(u64, P) + 'static> TickFn<P> for F {}
+}
+
+crate::cfg_sync! {
+    /// A user-defined function receiving the timestamp and frozen partial aggregate of a sealed second
+    ///
+    /// Must be `Send + Sync` so that the wheel can be moved and shared across threads. See [RwWheel::on_tick] for more information.
+    pub trait TickFn<P>: FnMut(u64, P) + Send + Sync + 'static {}
+    impl<P, F: FnMut(u64, P) + Send + Sync + 'static> TickFn<P> for F {}
+}
+
+type TickHook<A> = Box<dyn TickFn<<A as Aggregator>::PartialAggregate>>;
+
 impl<A: Aggregator> Default for RwWheel<A> {
     fn default() -> Self {
         Self::with_conf(Default::default())
@@ -98,6 +153,8 @@ impl<A> RwWheel<A>
 where
     A: Aggregator,
 {
+    const CYCLE_LENGTH_SECS: u64 = Haw::<A>::CYCLE_LENGTH.whole_seconds() as u64;
+
     /// Creates a new Wheel starting from the given time
     ///
     /// Time is represented as milliseconds since unix timestamp
@@ -117,12 +174,22 @@ where
     /// let wheel: RwWheel<U32SumAggregator> = RwWheel::with_conf(conf);
     /// ```
     pub fn with_conf(conf: Conf) -> Self {
+        let mut writer = WriterWheel::with_capacity_and_watermark(
+            conf.writer_conf.write_ahead_capacity,
+            conf.reader_conf.haw_conf.watermark,
+        );
+        if let Some(max_capacity) = conf.writer_conf.write_ahead_max_capacity {
+            writer = writer.growable(max_capacity);
+        }
+        // the milliseconds wheel is fed by a writer that ticks once per millisecond
+        if let Some(millis) = conf.reader_conf.haw_conf.milliseconds {
+            writer = writer.with_tick_size(millis.tick_size_ms);
+        }
         Self {
-            writer: WriterWheel::with_capacity_and_watermark(
-                conf.writer_conf.write_ahead_capacity,
-                conf.reader_conf.haw_conf.watermark,
-            ),
+            writer,
             reader: ReaderWheel::with_conf(conf.reader_conf.haw_conf),
+            tick_hook: None,
+            recorder: None,
             #[cfg(feature = "profiler")]
             stats: stats::Stats::default(),
         }
@@ -146,6 +213,28 @@ where
         self.reader.window(window.into());
     }
 
+    /// Installs a periodic window aggregation query aligned to the local calendar time of the given offset
+    ///
+    /// See [crate::Haw::window_with_offset] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Window, aggregator::sum::U32SumAggregator, RwWheel, NumericalDuration, UtcOffset};
+    ///
+    /// // daily windows firing at midnight in UTC+2
+    /// let offset = UtcOffset::from_hms(2, 0, 0).unwrap();
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.window_with_offset(Window::tumbling(1.days()), offset);
+    ///
+    /// // the first window starts at 22:00:00 UTC
+    /// let windows = wheel.advance(2.days());
+    /// assert_eq!(windows[0].window_end_ms, (22 + 24) * 3600 * 1000);
+    /// ```
+    pub fn window_with_offset(&mut self, window: impl Into<Window>, offset: time::UtcOffset) {
+        self.reader.window_with_offset(window.into(), offset);
+    }
+
     /// Inserts an entry into the wheel
     ///
     /// # Safety
@@ -167,9 +256,133 @@ where
         #[cfg(feature = "profiler")]
         profile_scope!(&self.stats.insert);
 
+        let e = e.into();
+        self.record(|| Op::Insert(e));
         self.writer.insert(e);
     }
 
+    /// Inserts data that occurred `ago` relative to the current watermark
+    ///
+    /// The timestamp of the entry is computed as `watermark - ago` at the time of insertion, so a negative
+    /// duration inserts into the write-ahead slots ahead of the watermark. Returns [InsertError::Late]
+    /// instead of silently dropping the data if the timestamp is below the watermark.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(10000);
+    /// // lands in the current second
+    /// assert!(wheel.insert_relative(1, 0.seconds()).is_ok());
+    /// // lands 2 seconds ahead of the watermark
+    /// assert!(wheel.insert_relative(2, (-2).seconds()).is_ok());
+    /// // has already been sealed
+    /// assert!(wheel.insert_relative(3, 1.seconds()).is_err());
+    /// ```
+    pub fn insert_relative(&mut self, data: A::Input, ago: Duration) -> Result<(), InsertError> {
+        let watermark = self.watermark();
+        let timestamp = watermark as i128 - ago.whole_milliseconds();
+        if timestamp < watermark as i128 {
+            return Err(InsertError::Late {
+                timestamp: timestamp.max(0) as u64,
+                watermark,
+            });
+        }
+        self.insert(Entry::new(
+            data,
+            u64::try_from(timestamp).unwrap_or(u64::MAX),
+        ));
+        Ok(())
+    }
+
+    /// Inserts a batch of inputs sharing the same timestamp into the wheel
+    ///
+    /// See [WriterWheel::insert_bulk] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.insert_bulk(1000, [1, 2, 3]);
+    /// wheel.advance(2.seconds());
+    /// assert_eq!(wheel.read().interval(2.seconds()), Some(6));
+    /// ```
+    #[inline]
+    pub fn insert_bulk(&mut self, timestamp: u64, inputs: impl IntoIterator<Item = A::Input>) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.insert);
+
+        match self.recorder.as_mut() {
+            Some(ops) => {
+                let inputs: Vec<A::Input> = inputs.into_iter().collect();
+                ops.push(Op::InsertBulk(timestamp, inputs.clone()));
+                self.writer.insert_bulk(timestamp, inputs);
+            }
+            None => self.writer.insert_bulk(timestamp, inputs),
+        }
+    }
+
+    /// Inserts a stream of entries while advancing the watermark as they cross second boundaries
+    ///
+    /// Before an entry is inserted, the watermark is advanced to the start of the second of the entry if it is newer
+    /// than the current watermark. Out-of-order entries that are still ahead of the watermark (e.g., within the
+    /// current second or the write-ahead section) are accepted while entries behind it are dropped as in [Self::insert].
+    ///
+    /// Returns possible window aggregates if any window is installed (see [RwWheel::window]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// // (value, timestamp) pairs
+    /// wheel.ingest([(1, 200), (2, 1500), (3, 1100), (4, 3000)]);
+    /// assert_eq!(wheel.watermark(), 3000);
+    /// assert_eq!(wheel.read().interval(3.seconds()), Some(6));
+    /// ```
+    pub fn ingest(
+        &mut self,
+        entries: impl IntoIterator<Item = impl Into<Entry<A::Input>>>,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        let mut windows = Vec::new();
+        for entry in entries {
+            let entry = entry.into();
+            let second = entry.timestamp - entry.timestamp % 1000;
+            if second > self.watermark() {
+                windows.extend(self.advance_to(second));
+            }
+            self.insert(entry);
+        }
+        windows
+    }
+
+    /// Inserts a pre-aggregated partial aggregate at the given timestamp into the wheel
+    ///
+    /// Useful when ingesting already summarized data, for instance from another wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.insert_partial(100, 1000);
+    /// wheel.advance(2.seconds());
+    /// assert_eq!(wheel.read().interval(2.seconds()), Some(100));
+    /// ```
+    #[inline]
+    pub fn insert_partial(&mut self, partial: A::PartialAggregate, timestamp: u64) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.insert);
+
+        self.record(|| Op::InsertPartial(partial, timestamp));
+        self.writer.insert_partial(partial, timestamp);
+    }
+
     /// Returns a reference to the writer wheel
     pub fn write(&self) -> &WriterWheel<A> {
         &self.writer
@@ -178,9 +391,48 @@ where
     pub fn read(&self) -> &ReaderWheel<A> {
         &self.reader
     }
-    /// Merges another read wheel with same size into this one
-    pub fn merge_read_wheel(&self, other: &ReaderWheel<A>) {
-        self.read().merge(other);
+    /// Combines partial aggregates within the given date range [start, end) into a final partial aggregate
+    ///
+    /// Shortcut for [ReaderWheel::combine_range] on [Self::read]. Like all reads, only data below the
+    /// watermark is visible, entries still held in the write-ahead wheel are not included until advanced.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Entry, RwWheel, WheelRange, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.insert(Entry::new(1, 1000));
+    /// wheel.insert(Entry::new(5, 3000));
+    /// wheel.advance(3.seconds());
+    ///
+    /// assert_eq!(wheel.combine_range(WheelRange::new_unchecked(0, 3000)), Some(1));
+    /// assert_eq!(wheel.interval(2.seconds()), Some(1));
+    /// assert_eq!(wheel.landmark(), Some(1));
+    /// ```
+    #[inline]
+    pub fn combine_range(&self, range: impl Into<WheelRange>) -> Option<A::PartialAggregate> {
+        self.reader.combine_range(range)
+    }
+    /// Returns the partial aggregate in the given time interval [(watermark - `duration`), watermark)
+    ///
+    /// Shortcut for [ReaderWheel::interval] on [Self::read], see [Self::combine_range] for an example.
+    #[inline]
+    pub fn interval(&self, dur: Duration) -> Option<A::PartialAggregate> {
+        self.reader.interval(dur)
+    }
+    /// Executes a Landmark Window that combines total partial aggregates across all wheels
+    ///
+    /// Shortcut for [ReaderWheel::landmark] on [Self::read], see [Self::combine_range] for an example.
+    #[inline]
+    pub fn landmark(&self) -> Option<A::PartialAggregate> {
+        self.reader.landmark()
+    }
+    /// Merges another read wheel into this one
+    ///
+    /// Returns an error if the wheels have incompatible configurations (see [MergeError]).
+    pub fn merge_read_wheel(&self, other: &ReaderWheel<A>) -> Result<(), MergeError> {
+        self.read().merge(other)
     }
     /// Returns the current watermark of this wheel
     pub fn watermark(&self) -> u64 {
@@ -205,6 +457,51 @@ where
         self.advance_to(to)
     }
 
+    /// Advance the watermark of the wheel by the given [Duration]
+    ///
+    /// Returns an error if the duration exceeds [crate::Haw::CYCLE_LENGTH] in which case all wheels have been cleared.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Haw, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// assert!(wheel.advance_checked(5.seconds()).is_ok());
+    ///
+    /// let too_far = Haw::<U32SumAggregator>::CYCLE_LENGTH.whole_seconds() + 1;
+    /// assert!(wheel.advance_checked(too_far.seconds()).is_err());
+    /// ```
+    #[inline]
+    pub fn advance_checked(
+        &mut self,
+        duration: Duration,
+    ) -> Result<Vec<WindowAggregate<A::PartialAggregate>>, CycleExceeded> {
+        let to = self.watermark() + duration.whole_milliseconds() as u64;
+        self.record(|| Op::AdvanceTo(to));
+        if duration.whole_seconds() as u64 > Self::CYCLE_LENGTH_SECS {
+            // clears all wheels and reports the exceeded cycle
+            return self.reader.advance_checked(duration, &mut self.writer);
+        }
+        Ok(self.advance_to_hooked(to))
+    }
+
+    /// Advance the watermark of the wheel by the given [Duration] saturating at [crate::Haw::CYCLE_LENGTH]
+    ///
+    /// Unlike [RwWheel::advance], a duration exceeding the cycle length does not clear the wheel.
+    /// The wheel still ticks once per second which makes a saturated advance expensive,
+    /// see [crate::Haw::advance_saturating] for more information.
+    #[inline]
+    pub fn advance_saturating(
+        &mut self,
+        duration: Duration,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        let ticks = cmp::min(duration.whole_seconds() as u64, Self::CYCLE_LENGTH_SECS);
+        let to = self.watermark() + ticks * 1000;
+        self.record(|| Op::AdvanceTo(to));
+        self.advance_to_hooked(to)
+    }
+
     /// Advances the time of the wheel to the specified watermark.
     ///
     /// May return possible window aggregates if any window is installed (see [RwWheel::window]).
@@ -227,7 +524,143 @@ where
         #[cfg(feature = "profiler")]
         profile_scope!(&self.stats.advance);
 
-        self.reader.advance_to(watermark, &mut self.writer)
+        self.record(|| Op::AdvanceTo(watermark));
+        self.advance_to_hooked(watermark)
+    }
+
+    // Advances to the watermark while passing every sealed second to the tick hook (if any)
+    #[inline]
+    fn advance_to_hooked(&mut self, watermark: u64) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        match self.tick_hook.as_mut() {
+            Some(hook) => self
+                .reader
+                .advance_to_with(watermark, &mut self.writer, hook),
+            None => self.reader.advance_to(watermark, &mut self.writer),
+        }
+    }
+
+    /// Registers a hook that is invoked with every sealed second while advancing
+    ///
+    /// The hook receives the start timestamp of the second together with its frozen partial aggregate,
+    /// or [Aggregator::IDENTITY] if the second received no data. This allows custom rollup pipelines
+    /// to observe the exact data fed into the wheel. Any previously registered hook is replaced.
+    ///
+    /// The hook is invoked by every advance of the wheel except for an advance exceeding [crate::Haw::CYCLE_LENGTH]
+    /// which clears all wheels without sealing any second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Entry, RwWheel};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// wheel.on_tick(|ts, partial| println!("{ts}: {partial}"));
+    /// wheel.insert(Entry::new(5, 0));
+    /// wheel.advance_to(2000);
+    /// ```
+    pub fn on_tick(&mut self, hook: impl TickFn<A::PartialAggregate>) {
+        self.tick_hook = Some(Box::new(hook));
+    }
+
+    /// Removes a previously registered tick hook
+    pub fn clear_tick_hook(&mut self) {
+        self.tick_hook = None;
+    }
+
+    /// Advances the time of the wheel towards the specified watermark by performing at most `steps` ticks
+    ///
+    /// Returns possible window aggregates together with the number of ticks left until the watermark is reached.
+    /// See [crate::Haw::advance_steps] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, RwWheel};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+    /// let mut remaining = usize::MAX;
+    /// while remaining > 0 {
+    ///     let (_windows, left) = wheel.advance_steps(60000, 16);
+    ///     remaining = left;
+    /// }
+    /// assert_eq!(wheel.watermark(), 60000);
+    /// ```
+    #[inline]
+    pub fn advance_steps(
+        &mut self,
+        watermark: u64,
+        steps: usize,
+    ) -> (Vec<WindowAggregate<A::PartialAggregate>>, usize) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.advance);
+
+        // never tick past a full cycle at once so that the wheels are not cleared
+        let ticks = watermark.saturating_sub(self.watermark()) / 1000;
+        let steps = cmp::min(cmp::min(ticks, steps as u64), Self::CYCLE_LENGTH_SECS);
+        let to = self.watermark() + steps * 1000;
+        self.record(|| Op::AdvanceTo(to));
+        let windows = self.advance_to_hooked(to);
+        (windows, (ticks - steps) as usize)
+    }
+
+    /// Starts recording the inserts and advances applied to the wheel
+    ///
+    /// The recorded operations can be replayed into a fresh wheel through [RwWheel::replay] to deterministically
+    /// reproduce its state. Note that configuration such as installed windows is not recorded, so recording
+    /// should be started right after creating the wheel. Any previous recording is discarded.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Conf, Entry, RwWheel, NumericalDuration};
+    ///
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::with_conf(Conf::default());
+    /// wheel.start_recording();
+    /// wheel.insert(Entry::new(10, 1000));
+    /// wheel.advance(2.seconds());
+    ///
+    /// let ops = wheel.take_recording();
+    /// let replayed: RwWheel<U32SumAggregator> = RwWheel::replay(Conf::default(), ops);
+    /// assert_eq!(replayed.watermark(), 2000);
+    /// assert_eq!(replayed.read().interval(2.seconds()), Some(10));
+    /// ```
+    pub fn start_recording(&mut self) {
+        self.recorder = Some(Vec::new());
+    }
+
+    /// Returns the operations recorded so far or `None` if recording has not been started
+    pub fn recording(&self) -> Option<&[Op<A>]> {
+        self.recorder.as_deref()
+    }
+
+    /// Stops recording and returns the recorded operations
+    pub fn take_recording(&mut self) -> Vec<Op<A>> {
+        self.recorder.take().unwrap_or_default()
+    }
+
+    /// Creates a new wheel from the given configuration and applies the recorded operations in order
+    ///
+    /// See [RwWheel::start_recording] for more information.
+    pub fn replay(conf: Conf, ops: impl IntoIterator<Item = Op<A>>) -> Self {
+        let mut wheel = Self::with_conf(conf);
+        for op in ops {
+            match op {
+                Op::Insert(entry) => wheel.insert(entry),
+                Op::InsertBulk(timestamp, inputs) => wheel.insert_bulk(timestamp, inputs),
+                Op::InsertPartial(partial, timestamp) => wheel.insert_partial(partial, timestamp),
+                Op::AdvanceTo(watermark) => {
+                    wheel.advance_to(watermark);
+                }
+            }
+        }
+        wheel
+    }
+
+    #[inline]
+    fn record(&mut self, op: impl FnOnce() -> Op<A>) {
+        if let Some(ops) = self.recorder.as_mut() {
+            ops.push(op());
+        }
     }
 
     /// Returns an estimation of bytes used by the wheel
@@ -317,11 +750,14 @@ where
 pub struct WriterConf {
     /// Defines the capacity of write-ahead slots
     write_ahead_capacity: usize,
+    /// Defines the capacity the write-ahead slots may grow to
+    write_ahead_max_capacity: Option<usize>,
 }
 impl Default for WriterConf {
     fn default() -> Self {
         Self {
             write_ahead_capacity: DEFAULT_WRITE_AHEAD_SLOTS,
+            write_ahead_max_capacity: None,
         }
     }
 }
@@ -359,6 +795,22 @@ impl Conf {
         self.writer_conf.write_ahead_capacity = capacity;
         self
     }
+    /// Allows the write-ahead slots to grow up to `max_capacity` slots
+    ///
+    /// See [WriterWheel::growable] for more information.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::Conf;
+    ///
+    /// // Start with 64 write-ahead slots and grow up to 1024 on bursts
+    /// let rw_conf = Conf::default().with_write_ahead(64).with_growable_write_ahead(1024);
+    /// ```
+    pub fn with_growable_write_ahead(mut self, max_capacity: usize) -> Self {
+        self.writer_conf.write_ahead_max_capacity = Some(max_capacity);
+        self
+    }
     /// Configures the reader wheel to use the given [HawConf]
     ///
     /// # Example
@@ -382,11 +834,108 @@ mod tests {
     use core::cell::RefCell;
     #[cfg(feature = "timer")]
     use std::rc::Rc;
+    use std::sync::{Arc, Mutex};
 
     use super::*;
     use crate::{aggregator::sum::U32SumAggregator, duration::*, *};
     use proptest::prelude::*;
 
+    #[test]
+    fn ingest_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        wheel.window(Window::tumbling(2.seconds()));
+
+        // (value, timestamp) pairs with out-of-order and late entries
+        let stream = [
+            (1, 0),
+            (2, 900),
+            (4, 1500),
+            (8, 1100),
+            (16, 3200), // crosses into a new second
+            (32, 2500), // behind the watermark: dropped
+            (64, 3999),
+            (128, 5000),
+            (256, 6100),
+            (512, 4900), // behind the watermark: dropped
+        ];
+        let windows = wheel.ingest(stream);
+        assert_eq!(wheel.watermark(), 6000);
+        assert_eq!(
+            windows,
+            vec![
+                WindowAggregate {
+                    window_start_ms: 0,
+                    window_end_ms: 2000,
+                    aggregate: 1 + 2 + 4 + 8,
+                },
+                WindowAggregate {
+                    window_start_ms: 2000,
+                    window_end_ms: 4000,
+                    aggregate: 16 + 64,
+                },
+                WindowAggregate {
+                    window_start_ms: 4000,
+                    window_end_ms: 6000,
+                    aggregate: 128,
+                },
+            ]
+        );
+
+        let windows = wheel.advance(2.seconds());
+        assert_eq!(windows[0].aggregate, 256);
+        assert_eq!(
+            wheel.read().interval(8.seconds()),
+            Some(1 + 2 + 4 + 8 + 16 + 64 + 128 + 256)
+        );
+    }
+
+    #[test]
+    fn growable_write_ahead_test() {
+        let conf = Conf::default()
+            .with_write_ahead(8)
+            .with_growable_write_ahead(128);
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::with_conf(conf);
+
+        // a burst far ahead of the watermark
+        for second in 0..100 {
+            wheel.insert(Entry::new(1, second * 1000));
+        }
+        assert!(wheel.write().write_ahead_len() >= 100);
+
+        wheel.advance(100.seconds());
+        assert_eq!(wheel.read().interval(100.seconds()), Some(100));
+        assert_eq!(wheel.read().interval(1.seconds()), Some(1));
+    }
+
+    #[test]
+    fn insert_partial_test() {
+        use crate::aggregator::avg::U64AvgAggregator;
+
+        let mut wheel: RwWheel<U64AvgAggregator> = RwWheel::new(0);
+        // (sum, count) partials from an upstream source
+        let partials = [((30, 3), 1000), ((10, 1), 1000), ((50, 2), 3000)];
+        for (partial, ts) in partials {
+            wheel.insert_partial(partial, ts);
+        }
+        // raw entries are combined with partials of the same slot
+        wheel.insert(Entry::new(10, 3000));
+        // partials beyond the write-ahead section overflow and are inserted later on
+        wheel.insert_partial((100, 4), 120000);
+        // late partials are dropped
+        wheel.advance_to(5000);
+        wheel.insert_partial((1000, 1), 2000);
+
+        let expected = [(30, 3), (10, 1), (50, 2), (10, 1)]
+            .into_iter()
+            .fold(U64AvgAggregator::IDENTITY, U64AvgAggregator::combine);
+        let range = WheelRange::new_unchecked(0, 5000);
+        assert_eq!(wheel.read().combine_range(range), Some(expected));
+        assert_eq!(wheel.read().interval(2.seconds()), Some((60, 3)));
+
+        wheel.advance_to(125000);
+        assert_eq!(wheel.read().interval(5.seconds()), Some((100, 4)));
+    }
+
     #[test]
     fn delta_generate_test() {
         let haw_conf = HawConf::default().with_deltas();
@@ -459,6 +1008,78 @@ mod tests {
         assert_eq!(deserialized_wheel.read().interval(1.seconds()), Some(250));
     }
 
+    #[cfg(feature = "serde")]
+    #[test]
+    fn rw_wheel_serde_aggregator_mismatch_test() {
+        use crate::aggregator::max::U32MaxAggregator;
+
+        let mut rw_wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        rw_wheel.insert(Entry::new(250, 1000));
+        rw_wheel.advance_to(5000);
+
+        let serialized = bincode::serialize(&rw_wheel).unwrap();
+
+        // same partial aggregate layout but a different aggregator
+        let err = bincode::deserialize::<RwWheel<U32MaxAggregator>>(&serialized)
+            .err()
+            .expect("deserialization with a different aggregator should fail");
+        assert!(err.to_string().contains("aggregator mismatch"));
+        // the tags are spelled out rather than derived from the (unstable) type name
+        assert!(err
+            .to_string()
+            .contains("\"U32SumAggregator\" but deserialized as \"U32MaxAggregator\""));
+
+        assert!(bincode::deserialize::<RwWheel<U32SumAggregator>>(&serialized).is_ok());
+    }
+
+    #[cfg(all(feature = "serde", feature = "ewma"))]
+    #[test]
+    fn rw_wheel_serde_generic_aggregator_mismatch_test() {
+        use crate::aggregator::ewma::EwmaAggregator;
+
+        let mut rw_wheel: RwWheel<EwmaAggregator<60>> = RwWheel::new(0);
+        rw_wheel.insert(Entry::new((2.0, 1000), 1000));
+        rw_wheel.advance_to(5000);
+
+        let serialized = bincode::serialize(&rw_wheel).unwrap();
+
+        // same aggregator with a different half-life
+        let err = bincode::deserialize::<RwWheel<EwmaAggregator<86400>>>(&serialized)
+            .err()
+            .expect("deserialization with a different half-life should fail");
+        assert!(err
+            .to_string()
+            .contains("\"EwmaAggregator<60>\" but deserialized as \"EwmaAggregator<86400>\""));
+
+        assert!(bincode::deserialize::<RwWheel<EwmaAggregator<60>>>(&serialized).is_ok());
+    }
+
+    #[cfg(all(feature = "serde", feature = "top_n", feature = "max"))]
+    #[test]
+    fn rw_wheel_serde_top_n_aggregator_mismatch_test() {
+        use crate::aggregator::{max::U32MaxAggregator, top_n::TopNAggregator};
+
+        let mut rw_wheel: RwWheel<TopNAggregator<u32, 10, U32SumAggregator>> = RwWheel::new(0);
+        rw_wheel.insert(Entry::new((1, 250), 1000));
+        rw_wheel.advance_to(5000);
+
+        let serialized = bincode::serialize(&rw_wheel).unwrap();
+
+        // same top-n parameters with a different inner aggregator
+        let err =
+            bincode::deserialize::<RwWheel<TopNAggregator<u32, 10, U32MaxAggregator>>>(&serialized)
+                .err()
+                .expect("deserialization with a different inner aggregator should fail");
+        assert!(err.to_string().contains(
+            "\"TopNAggregator<4, 10, U32SumAggregator, Ascending>\" but deserialized as \"TopNAggregator<4, 10, U32MaxAggregator, Ascending>\""
+        ));
+
+        assert!(
+            bincode::deserialize::<RwWheel<TopNAggregator<u32, 10, U32SumAggregator>>>(&serialized)
+                .is_ok()
+        );
+    }
+
     #[cfg(feature = "timer")]
     #[test]
     fn timer_once_test() {
@@ -517,6 +1138,250 @@ mod tests {
         assert_eq!(*sum.borrow(), 1750);
     }
 
+    #[cfg(all(feature = "timer", feature = "serde"))]
+    #[test]
+    fn timer_pending_serde_test() {
+        let mut rw_wheel: RwWheel<U32SumAggregator> = RwWheel::default();
+        let far = 60.days().whole_milliseconds() as u64; // beyond the timer cycle
+        for at in [far, 3000, 8000, 500_000] {
+            let _ = rw_wheel.read().schedule_once(at, |_| {});
+        }
+        // repeating timers are not reported
+        let _ = rw_wheel.read().schedule_repeat(4000, 5.seconds(), |_| {});
+        rw_wheel.advance_to(5000);
+
+        let pending = rw_wheel.read().pending_timers();
+        assert_eq!(pending, vec![8000, 500_000, far]);
+
+        // timers are skipped during serialization but their times can be persisted
+        let serialized = bincode::serialize(&(&rw_wheel, &pending)).unwrap();
+        let (deserialized, times) =
+            bincode::deserialize::<(RwWheel<U32SumAggregator>, Vec<u64>)>(&serialized).unwrap();
+        assert!(deserialized.read().pending_timers().is_empty());
+
+        let fired = Rc::new(RefCell::new(Vec::new()));
+        for at in times {
+            let fired = fired.clone();
+            let _ = deserialized
+                .read()
+                .schedule_once(at, move |haw| fired.borrow_mut().push(haw.watermark()));
+        }
+        assert_eq!(deserialized.read().pending_timers(), pending);
+
+        let mut deserialized = deserialized;
+        deserialized.advance_to(10000);
+        assert_eq!(*fired.borrow(), vec![8000]);
+        assert_eq!(deserialized.read().pending_timers(), vec![500_000, far]);
+    }
+
+    #[test]
+    fn insert_relative_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        wheel.advance(10.seconds());
+
+        assert!(wheel.insert_relative(1, 0.seconds()).is_ok());
+        assert!(wheel.insert_relative(2, (-3).seconds()).is_ok());
+        assert!(wheel.insert_relative(4, (-3500).milliseconds()).is_ok());
+        assert_eq!(
+            wheel.insert_relative(8, 500.milliseconds()),
+            Err(InsertError::Late {
+                timestamp: 9500,
+                watermark: 10000
+            })
+        );
+        assert_eq!(
+            wheel.insert_relative(8, 20.seconds()),
+            Err(InsertError::Late {
+                timestamp: 0,
+                watermark: 10000
+            })
+        );
+
+        // the relative timestamp is computed from the watermark at insertion time
+        wheel.advance(1.seconds());
+        assert!(wheel.insert_relative(16, (-2).seconds()).is_ok());
+
+        wheel.advance(5.seconds());
+        let slots = wheel
+            .read()
+            .range(WheelRange::new_unchecked(10000, 16000))
+            .unwrap();
+        assert_eq!(
+            slots,
+            vec![
+                (10000, 1),
+                (11000, 0),
+                (12000, 0),
+                (13000, 22),
+                (14000, 0),
+                (15000, 0)
+            ]
+        );
+    }
+
+    // Registers a tick hook collecting the sealed seconds
+    fn collect_ticks(wheel: &mut RwWheel<U32SumAggregator>) -> Arc<Mutex<Vec<(u64, u32)>>> {
+        let sealed = Arc::new(Mutex::new(Vec::new()));
+        let inner = sealed.clone();
+        wheel.on_tick(move |ts, partial| inner.lock().unwrap().push((ts, partial)));
+        sealed
+    }
+
+    #[test]
+    fn tick_hook_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        let sealed = collect_ticks(&mut wheel);
+
+        wheel.insert(Entry::new(1, 0));
+        wheel.insert(Entry::new(2, 500));
+        wheel.insert(Entry::new(10, 2000));
+        wheel.advance(3.seconds());
+        assert_eq!(*sealed.lock().unwrap(), vec![(0, 3), (1000, 0), (2000, 10)]);
+
+        // sealed partials match what the wheel stores
+        wheel.insert(Entry::new(7, 3000));
+        wheel.advance_to(5000);
+        assert_eq!(sealed.lock().unwrap().len(), 5);
+        assert_eq!(sealed.lock().unwrap()[3], (3000, 7));
+        assert_eq!(wheel.read().interval(2.seconds()), Some(7));
+
+        wheel.clear_tick_hook();
+        wheel.advance(1.seconds());
+        assert_eq!(sealed.lock().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn tick_hook_advance_checked_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        let sealed = collect_ticks(&mut wheel);
+
+        wheel.insert(Entry::new(4, 1000));
+        assert!(wheel.advance_checked(2.seconds()).is_ok());
+        assert_eq!(*sealed.lock().unwrap(), vec![(0, 0), (1000, 4)]);
+
+        // exceeding the cycle clears the wheels without sealing any second
+        let too_far = Haw::<U32SumAggregator>::CYCLE_LENGTH.whole_seconds() + 1;
+        assert!(wheel.advance_checked(too_far.seconds()).is_err());
+        assert_eq!(sealed.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn millisecond_wheel_test() {
+        let haw_conf = HawConf::default().with_milliseconds(3000);
+        let mut wheel: RwWheel<U32SumAggregator> =
+            RwWheel::with_conf(Conf::default().with_haw_conf(haw_conf));
+        assert_eq!(wheel.write().tick_size_ms(), 1);
+
+        for (data, timestamp) in [(1, 1), (2, 1), (4, 250), (8, 999), (16, 1500)] {
+            wheel.insert(Entry::new(data, timestamp));
+        }
+        wheel.advance_to(2000);
+        assert_eq!(wheel.watermark(), 2000);
+
+        let read = wheel.read();
+        assert_eq!(read.combine_millis(1, 2), Some(3));
+        assert_eq!(read.combine_millis(2, 250), Some(0));
+        assert_eq!(read.combine_millis(250, 1000), Some(12));
+        assert_eq!(read.combine_millis(999, 1501), Some(24));
+        assert_eq!(read.combine_millis(0, 2000), Some(31));
+
+        // the combined milliseconds feed the seconds wheel
+        assert_eq!(
+            read.combine_range(WheelRange::new_unchecked(0, 1000)),
+            Some(15)
+        );
+        assert_eq!(read.interval(2.seconds()), Some(31));
+
+        // ranges beyond the watermark or without a milliseconds wheel cannot be answered
+        assert_eq!(read.combine_millis(1500, 2001), None);
+        assert_eq!(read.combine_millis(5, 5), None);
+        let seconds: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        assert_eq!(seconds.read().combine_millis(0, 1), None);
+    }
+
+    #[test]
+    fn advance_past_cycle_test() {
+        let cycle = Haw::<U32SumAggregator>::CYCLE_LENGTH.whole_seconds();
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        wheel.insert(Entry::new(4, 1000));
+        wheel.advance(2.seconds());
+        assert_eq!(wheel.read().interval(2.seconds()), Some(4));
+
+        // stepping towards a watermark beyond the cycle never clears the wheels
+        let far = wheel.watermark() + (cycle as u64 + 1) * 1000;
+        let (_, remaining) = wheel.advance_steps(far, 2);
+        assert_eq!(remaining, cycle as usize - 1);
+        assert_eq!(wheel.read().interval(4.seconds()), Some(4));
+
+        // a checked advance reports the cleared wheels
+        assert_eq!(
+            wheel.advance_checked((cycle + 1).seconds()).unwrap_err(),
+            CycleExceeded {
+                ticks: cycle as u64 + 1
+            }
+        );
+        assert_eq!(wheel.read().interval(4.seconds()), None);
+
+        // while a regular advance clears the wheels silently
+        wheel.insert(Entry::new(4, wheel.watermark()));
+        wheel.advance(1.seconds());
+        assert_eq!(wheel.read().interval(1.seconds()), Some(4));
+        assert!(wheel.advance((cycle + 1).seconds()).is_empty());
+        assert_eq!(wheel.read().interval(1.seconds()), None);
+    }
+
+    #[test]
+    fn tick_hook_advance_saturating_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        let sealed = collect_ticks(&mut wheel);
+
+        wheel.insert(Entry::new(4, 2000));
+        wheel.advance_saturating(3.seconds());
+        assert_eq!(*sealed.lock().unwrap(), vec![(0, 0), (1000, 0), (2000, 4)]);
+        assert_eq!(wheel.watermark(), 3000);
+    }
+
+    #[test]
+    fn tick_hook_advance_steps_test() {
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        let sealed = collect_ticks(&mut wheel);
+
+        for second in 0..10 {
+            wheel.insert(Entry::new(second as u32, second * 1000));
+        }
+        let (_, remaining) = wheel.advance_steps(10000, 4);
+        assert_eq!(remaining, 6);
+        assert_eq!(sealed.lock().unwrap().len(), 4);
+
+        let (_, remaining) = wheel.advance_steps(10000, 16);
+        assert_eq!(remaining, 0);
+        let expected: Vec<_> = (0..10)
+            .map(|second| (second * 1000, second as u32))
+            .collect();
+        assert_eq!(*sealed.lock().unwrap(), expected);
+        assert_eq!(wheel.read().interval(10.seconds()), Some(45));
+    }
+
+    // timer actions and profiler stats are not Send
+    #[cfg(all(feature = "sync", not(feature = "timer"), not(feature = "profiler")))]
+    #[test]
+    fn rw_wheel_send_test() {
+        fn assert_send<T: Send>(_: &T) {}
+
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(0);
+        let sealed = collect_ticks(&mut wheel);
+        assert_send(&wheel);
+
+        let handle = std::thread::spawn(move || {
+            wheel.insert(Entry::new(1, 0));
+            wheel.advance(1.seconds());
+            wheel
+        });
+        let wheel = handle.join().expect("Failed to join the thread.");
+        assert_eq!(wheel.watermark(), 1000);
+        assert_eq!(*sealed.lock().unwrap(), vec![(0, 1)]);
+    }
+
     #[cfg(feature = "sync")]
     #[test]
     fn read_wheel_move_thread_test() {
@@ -578,6 +1443,31 @@ mod tests {
         wheel.advance_to(time);
     }
 
+    #[test]
+    fn forwarded_queries_test() {
+        let mut wheel = RwWheel::<U32SumAggregator>::new(0);
+        for i in 0..150u64 {
+            wheel.insert(Entry::new(i as u32, i * 1000));
+        }
+        wheel.advance(120.seconds());
+
+        let range = WheelRange::new_unchecked(30000, 110000);
+        assert_eq!(
+            wheel.combine_range(range),
+            wheel.read().combine_range(range)
+        );
+        assert_eq!(
+            wheel.interval(70.seconds()),
+            wheel.read().interval(70.seconds())
+        );
+        assert_eq!(wheel.landmark(), wheel.read().landmark());
+
+        // entries in the write-ahead wheel are not visible until advanced
+        assert_eq!(wheel.landmark(), Some((0..120).sum()));
+        wheel.advance(30.seconds());
+        assert_eq!(wheel.landmark(), Some((0..150).sum()));
+    }
+
     #[test]
     fn mixed_timestamp_insertions_test() {
         let mut time = 1000;
@@ -681,7 +1571,7 @@ mod tests {
 
         let fresh_wheel_time = 0;
         let fresh_wheel = RwWheel::<U32SumAggregator>::new(fresh_wheel_time);
-        fresh_wheel.read().merge(wheel.read());
+        fresh_wheel.read().merge(wheel.read()).unwrap();
 
         assert_eq!(fresh_wheel.read().watermark(), wheel.read().watermark());
         assert_eq!(fresh_wheel.read().landmark(), wheel.read().landmark());
@@ -705,13 +1595,121 @@ mod tests {
         fresh_wheel.insert(Entry::new(5u32, 8000));
         fresh_wheel.advance(9.seconds());
 
-        wheel.read().merge(fresh_wheel.read());
+        wheel.read().merge(fresh_wheel.read()).unwrap();
 
         assert_eq!(wheel.read().landmark(), Some(6));
         // assert_eq!(wheel.read().interval(2.seconds()), Some(5));
         assert_eq!(wheel.read().interval(10.seconds()), Some(6));
     }
 
+    #[test]
+    fn merge_different_layouts_test() {
+        let filled = |conf: HawConf, value: fn(u64) -> u32| {
+            let mut wheel: RwWheel<U32SumAggregator> =
+                RwWheel::with_conf(Conf::default().with_haw_conf(conf));
+            for second in 0..30u64 {
+                wheel.insert(Entry::new(value(second), second * 1000));
+            }
+            wheel.advance(30.seconds());
+            wheel
+        };
+        let prefix_conf = HawConf::default()
+            .with_prefix_sum()
+            .with_retention_policy(RetentionPolicy::Keep);
+
+        let prefix_wheel = filled(prefix_conf, |_| 1);
+        let wheel = filled(HawConf::default(), |second| second as u32);
+        let baseline = filled(HawConf::default(), |second| 1 + second as u32);
+
+        // array-layout wheel into a prefix-enabled wheel and vice versa
+        prefix_wheel.merge_read_wheel(wheel.read()).unwrap();
+        let wheel = filled(HawConf::default(), |second| second as u32);
+        wheel
+            .merge_read_wheel(filled(prefix_conf, |_| 1).read())
+            .unwrap();
+
+        for merged in [prefix_wheel.read(), wheel.read()] {
+            assert_eq!(merged.landmark(), baseline.read().landmark());
+            for (start, end) in [(0, 30000), (5000, 20000), (29000, 30000)] {
+                let range = WheelRange::new_unchecked(start, end);
+                assert_eq!(
+                    merged.combine_range(range),
+                    baseline.read().combine_range(range)
+                );
+            }
+        }
+        assert!(prefix_wheel.read().as_ref().seconds().unwrap().is_prefix());
+
+        // wheels with different tick sizes cannot be merged
+        let conf = HawConf {
+            seconds: WheelConf::new(500, 120),
+            ..Default::default()
+        };
+        let other: RwWheel<U32SumAggregator> =
+            RwWheel::with_conf(Conf::default().with_haw_conf(conf));
+        assert_eq!(
+            wheel.merge_read_wheel(other.read()),
+            Err(MergeError::IncompatibleTickSize {
+                granularity: Granularity::Second,
+                expected: 1000,
+                found: 500
+            })
+        );
+        assert_eq!(wheel.read().landmark(), baseline.read().landmark());
+    }
+
+    // Applies the (kind, value, offset) operations to a recording wheel and replays the recorded operations
+    fn record_and_replay(
+        ops: Vec<(u8, u32, u64)>,
+    ) -> (RwWheel<U32SumAggregator>, RwWheel<U32SumAggregator>) {
+        let conf = Conf::default().with_haw_conf(HawConf::default().with_watermark(0));
+        let mut wheel: RwWheel<U32SumAggregator> = RwWheel::with_conf(conf);
+        wheel.start_recording();
+
+        for (kind, value, offset) in ops {
+            let timestamp = wheel.watermark() + offset;
+            match kind {
+                0 | 1 => wheel.insert(Entry::new(value, timestamp)),
+                2 => wheel.insert_bulk(timestamp, [value, value + 1]),
+                3 => wheel.insert_partial(value, timestamp),
+                _ => {
+                    wheel.advance_to(timestamp - timestamp % 1000);
+                }
+            }
+        }
+        wheel.advance_steps(wheel.watermark() + 10_000, 3);
+        wheel.advance_saturating(20.seconds());
+
+        let ops = wheel.take_recording();
+        assert!(wheel.recording().is_none());
+        let mut replayed: RwWheel<U32SumAggregator> = RwWheel::replay(conf, ops);
+
+        // flush pending writes of both wheels before comparing
+        for wheel in [&mut wheel, &mut replayed] {
+            wheel.advance(2.minutes());
+        }
+        (wheel, replayed)
+    }
+
+    // Ranges ending at the watermark that are compared between a wheel and its replay
+    fn replay_ranges(watermark: u64) -> impl Iterator<Item = WheelRange> {
+        [1u64, 7, 30, 60, 120, 240].into_iter().map(move |seconds| {
+            WheelRange::new_unchecked(watermark.saturating_sub(seconds * 1000), watermark)
+        })
+    }
+
+    #[test]
+    fn record_replay_zero_insert_test() {
+        // a single zero inserted at the initial watermark, previously kept as a proptest regression seed
+        let (wheel, replayed) = record_and_replay(vec![(0, 0, 0)]);
+        assert_eq!(replayed.watermark(), wheel.watermark());
+        assert_eq!(replayed.read().landmark(), wheel.read().landmark());
+        assert_eq!(wheel.read().landmark(), Some(0));
+        for range in replay_ranges(wheel.watermark()) {
+            assert_eq!(replayed.read().range(range), wheel.read().range(range));
+        }
+    }
+
     fn create_and_advance_wheel(start: u64, end: u64) -> u64 {
         let mut wheel: RwWheel<U32SumAggregator> = RwWheel::new(start);
         wheel.advance_to(end);
@@ -724,5 +1722,15 @@ mod tests {
             let advanced_time = create_and_advance_wheel(start, watermark);
             prop_assert!(advanced_time >= start);
         }
+
+        #[test]
+        fn record_replay_test(ops in prop::collection::vec((0u8..5, 0u32..100, 0u64..90_000), 1..200)) {
+            let (wheel, replayed) = record_and_replay(ops);
+            prop_assert_eq!(replayed.watermark(), wheel.watermark());
+            prop_assert_eq!(replayed.read().landmark(), wheel.read().landmark());
+            for range in replay_ranges(wheel.watermark()) {
+                prop_assert_eq!(replayed.read().range(range), wheel.read().range(range));
+            }
+        }
     }
 }
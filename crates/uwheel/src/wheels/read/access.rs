@@ -0,0 +1,128 @@
+// NOTE: 64-bit atomics are unavailable on some no_std targets such as thumbv7m
+use core::sync::atomic::{AtomicUsize, Ordering};
+
+use super::hierarchical::Granularity;
+
+/// Number of standard deviations above the mean at which a granularity is considered an outlier
+const OUTLIER_THRESHOLD: f64 = 1.5;
+
+/// Access statistics for a single wheel granularity
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct WheelAccess {
+    /// Number of wheel aggregations executed on the wheel
+    pub queries: u64,
+    /// Total number of slots scanned by those aggregations
+    pub scans: u64,
+    /// Whether the scan volume of this granularity is an outlier compared to the others
+    pub outlier: bool,
+}
+
+impl WheelAccess {
+    /// Returns the average number of slots scanned per query
+    pub fn avg_scans(&self) -> f64 {
+        if self.queries == 0 {
+            0.0
+        } else {
+            self.scans as f64 / self.queries as f64
+        }
+    }
+}
+
+/// Per-granularity access statistics of a [Haw](super::Haw)
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct AccessStats {
+    /// Access statistics of the seconds wheel
+    pub seconds: WheelAccess,
+    /// Access statistics of the minutes wheel
+    pub minutes: WheelAccess,
+    /// Access statistics of the hours wheel
+    pub hours: WheelAccess,
+    /// Access statistics of the days wheel
+    pub days: WheelAccess,
+    /// Access statistics of the weeks wheel
+    pub weeks: WheelAccess,
+    /// Access statistics of the years wheel
+    pub years: WheelAccess,
+}
+
+impl AccessStats {
+    /// Returns `true` if any granularity has been flagged as an outlier
+    pub fn has_outliers(&self) -> bool {
+        self.as_array().iter().any(|w| w.outlier)
+    }
+
+    #[inline]
+    pub(crate) fn as_array(&self) -> [WheelAccess; 6] {
+        [
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days,
+            self.weeks,
+            self.years,
+        ]
+    }
+}
+
+/// Internal access counters that are updated while executing queries
+#[derive(Debug, Default)]
+pub(crate) struct AccessCounters {
+    queries: [AtomicUsize; 6],
+    scans: [AtomicUsize; 6],
+}
+
+impl AccessCounters {
+    /// Records a wheel aggregation over `scans` slots at the given granularity
+    #[inline]
+    pub fn record(&self, granularity: Granularity, scans: usize) {
+        let idx = granularity as usize;
+        self.queries[idx].fetch_add(1, Ordering::Relaxed);
+        self.scans[idx].fetch_add(scans, Ordering::Relaxed);
+    }
+
+    /// Resets all counters
+    pub fn reset(&self) {
+        for counter in self.queries.iter().chain(self.scans.iter()) {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+
+    /// Takes a snapshot of the counters and flags granularities with outlier scan volumes
+    pub fn snapshot(&self) -> AccessStats {
+        let mut accesses: [WheelAccess; 6] = core::array::from_fn(|idx| WheelAccess {
+            queries: self.queries[idx].load(Ordering::Relaxed) as u64,
+            scans: self.scans[idx].load(Ordering::Relaxed) as u64,
+            outlier: false,
+        });
+
+        let n = accesses.len() as f64;
+        let mean = accesses.iter().map(|w| w.scans as f64).sum::<f64>() / n;
+        let variance = accesses
+            .iter()
+            .map(|w| {
+                let diff = w.scans as f64 - mean;
+                diff * diff
+            })
+            .sum::<f64>()
+            / n;
+
+        // compare squared distances to avoid requiring sqrt in no_std environments
+        if variance > 0.0 {
+            for access in accesses.iter_mut() {
+                let diff = access.scans as f64 - mean;
+                access.outlier =
+                    diff > 0.0 && diff * diff > OUTLIER_THRESHOLD * OUTLIER_THRESHOLD * variance;
+            }
+        }
+
+        let [seconds, minutes, hours, days, weeks, years] = accesses;
+        AccessStats {
+            seconds,
+            minutes,
+            hours,
+            days,
+            weeks,
+            years,
+        }
+    }
+}
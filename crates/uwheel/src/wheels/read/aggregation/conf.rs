@@ -103,36 +103,54 @@ pub struct WheelConf {
 
 impl WheelConf {
     /// Initiates a new Configuration
-    pub fn new(tick_size_ms: u64, capacity: usize) -> Self {
+    pub const fn new(tick_size_ms: u64, capacity: usize) -> Self {
         Self {
             capacity,
-            watermark: Default::default(),
-            data_layout: Default::default(),
+            watermark: 0,
+            data_layout: DataLayout::Normal,
             tick_size_ms,
-            retention: Default::default(),
-            mode: Default::default(),
+            retention: RetentionPolicy::Drop,
+            mode: WheelMode::Stream,
         }
     }
     /// Sets the watermark
-    pub fn with_watermark(mut self, watermark: u64) -> Self {
+    pub const fn with_watermark(mut self, watermark: u64) -> Self {
         self.watermark = watermark;
         self
     }
 
     /// Configures the wheel to use the given data layout
-    pub fn with_data_layout(mut self, layout: DataLayout) -> Self {
+    pub const fn with_data_layout(mut self, layout: DataLayout) -> Self {
         self.data_layout = layout;
         self
     }
 
+    /// Configures the wheel to use the [DataLayout::Prefix] layout
+    ///
+    /// # Safety
+    ///
+    /// The aggregator must implement `combine_inverse` otherwise a panic will occur during wheel initialization.
+    pub const fn with_prefix(self) -> Self {
+        self.with_data_layout(DataLayout::Prefix)
+    }
+
+    /// Configures the wheel to use the [DataLayout::Compressed] layout with the given chunk size
+    ///
+    /// # Safety
+    ///
+    /// The aggregator must implement `compression` otherwise a panic will occur during wheel initialization.
+    pub const fn with_compression(self, chunk_size: usize) -> Self {
+        self.with_data_layout(DataLayout::Compressed(chunk_size))
+    }
+
     /// Configures the wheel to use the given wheel mode
-    pub fn with_mode(mut self, mode: WheelMode) -> Self {
+    pub const fn with_mode(mut self, mode: WheelMode) -> Self {
         self.mode = mode;
         self
     }
 
     /// Configures the wheel to use the given retention policy
-    pub fn with_retention_policy(mut self, policy: RetentionPolicy) -> Self {
+    pub const fn with_retention_policy(mut self, policy: RetentionPolicy) -> Self {
         self.retention = policy;
         self
     }
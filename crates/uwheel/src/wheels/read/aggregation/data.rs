@@ -46,6 +46,13 @@ impl<A: Aggregator> Data<A> {
             Data::CompressedDeque(arr) => arr.size_bytes(),
         }
     }
+    // Returns the bytes used by the prefix array of a prefix-sum layout
+    pub fn prefix_size_bytes(&self) -> usize {
+        match self {
+            Data::PrefixDeque(arr) => arr.prefix_size_bytes(),
+            _ => 0,
+        }
+    }
     pub fn is_empty(&self) -> bool {
         self.len() == 0
     }
@@ -80,10 +87,27 @@ impl<A: Aggregator> Data<A> {
         }
     }
 
+    /// Merges another data layout into this one
+    ///
+    /// Prefix-enabled data is normalized to a regular deque before combining.
+    ///
+    /// # Panics
+    ///
+    /// Panics if either side uses a compressed layout.
     pub fn merge(&mut self, other: &Self) {
-        match (self, other) {
-            (Data::Deque(arr), Data::Deque(arr_other)) => arr.merge(arr_other),
-            _ => unimplemented!("Only Deque Merging supported as of now"),
+        let normalized;
+        let other = match other {
+            Data::Deque(arr) => arr,
+            Data::PrefixDeque(parr) => {
+                normalized = MutablePartialDeque::from_slice(parr.slots_slice());
+                &normalized
+            }
+            Data::CompressedDeque(_) => unimplemented!("Merging compressed data is not supported"),
+        };
+        match self {
+            Data::Deque(arr) => arr.merge(other),
+            Data::PrefixDeque(parr) => parr.merge(other),
+            Data::CompressedDeque(_) => unimplemented!("Merging compressed data is not supported"),
         }
     }
 
@@ -116,14 +116,13 @@ impl<A: Aggregator> MutablePartialDeque<A> {
         } else {
             let Range { start, end } = into_range(&range, self.inner.len());
             let slots = end - start;
-            Some(
-                self.inner
-                    .iter()
-                    .skip(start)
-                    .take(slots)
-                    .copied()
-                    .fold(A::IDENTITY, A::combine),
-            )
+            let iter = self.inner.iter().skip(start).take(slots).copied();
+            // slots are stored from the most recent to the oldest
+            if A::commutative() {
+                Some(iter.fold(A::IDENTITY, A::combine))
+            } else {
+                Some(iter.rev().fold(A::IDENTITY, A::combine))
+            }
         }
     }
 
@@ -207,8 +206,10 @@ impl<A: Aggregator> PrefixDeque<A> {
     }
     #[doc(hidden)]
     pub fn size_bytes(&self) -> usize {
-        let bytes = core::mem::size_of::<A::PartialAggregate>() * self.slots.len();
-        bytes * 2 // since slots + prefix same size
+        self.slots.size_bytes() + self.prefix_size_bytes()
+    }
+    pub(crate) fn prefix_size_bytes(&self) -> usize {
+        self.prefix.size_bytes()
     }
     pub(crate) fn len(&self) -> usize {
         self.slots.len()
@@ -221,6 +222,10 @@ impl<A: Aggregator> PrefixDeque<A> {
         self.slots.pop_back();
         self.rebuild_prefix();
     }
+    pub(crate) fn merge(&mut self, other: &MutablePartialDeque<A>) {
+        self.slots.merge(other);
+        self.rebuild_prefix();
+    }
     pub(crate) fn slots_slice(&self) -> &[A::PartialAggregate] {
         self.slots.as_slice()
     }
@@ -1,4 +1,3 @@
-use core::ops::RangeBounds;
 use time::{Duration, OffsetDateTime};
 
 #[cfg(not(feature = "std"))]
@@ -8,7 +7,7 @@ use super::{conf::WheelConf, Wheel};
 use crate::{
     aggregator::Aggregator,
     wheels::read::{
-        hierarchical::{Granularity, WheelRange},
+        hierarchical::{Granularity, WheelRange, WEEKS},
         plan::{Aggregation, WheelAggregation},
     },
 };
@@ -31,10 +30,12 @@ impl<A: Aggregator> MaybeWheel<A> {
             wheel.clear();
         }
     }
+    pub fn conf(&self) -> &WheelConf {
+        &self.conf
+    }
     pub fn merge(&mut self, other: &Self) {
-        if let Some(wheel) = self.inner.as_mut() {
-            // TODO: fix better checks
-            wheel.merge(other.as_ref().unwrap());
+        if let (Some(wheel), Some(other)) = (self.inner.as_mut(), other.as_ref()) {
+            wheel.merge(other);
         }
     }
 
@@ -55,6 +56,8 @@ impl<A: Aggregator> MaybeWheel<A> {
                         Granularity::Minute => Duration::MINUTE.whole_milliseconds(),
                         Granularity::Hour => Duration::HOUR.whole_milliseconds(),
                         Granularity::Day => Duration::DAY.whole_milliseconds(),
+                        Granularity::Week => Duration::WEEK.whole_milliseconds(),
+                        Granularity::Year => Duration::WEEK.whole_milliseconds() * WEEKS as i128,
                     } as u64;
 
                     wheel
@@ -69,16 +72,6 @@ impl<A: Aggregator> MaybeWheel<A> {
                 })
             })
     }
-    #[inline]
-    pub fn combine_range<R>(&self, range: R) -> Option<A::PartialAggregate>
-    where
-        R: RangeBounds<usize>,
-    {
-        self.inner
-            .as_ref()
-            .and_then(|wheel| wheel.combine_range(range))
-    }
-
     #[doc(hidden)]
     #[allow(dead_code)]
     #[inline]
@@ -116,16 +109,21 @@ impl<A: Aggregator> MaybeWheel<A> {
         slots: usize,
         gran: Granularity,
     ) -> Option<(usize, usize)> {
-        let watermark_date =
-            |wm: u64| OffsetDateTime::from_unix_timestamp((wm as i64) / 1000).unwrap();
+        let watermark_date = |wm: u64| {
+            i64::try_from(wm / 1000)
+                .ok()
+                .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+        };
         self.inner.as_ref().and_then(|wheel| {
-            let watermark = watermark_date(wheel.watermark());
+            let watermark = watermark_date(wheel.watermark())?;
             let distance = watermark - start;
             let slot_distance = match gran {
                 Granularity::Second => distance.whole_seconds(),
                 Granularity::Minute => distance.whole_minutes(),
                 Granularity::Hour => distance.whole_hours(),
                 Granularity::Day => distance.whole_days(),
+                Granularity::Week => distance.whole_weeks(),
+                Granularity::Year => distance.whole_weeks() / WEEKS as i64,
             } as usize;
             let start_slot = slot_distance.saturating_sub(slots);
             let end_slot = start_slot + slots;
@@ -149,6 +147,8 @@ impl<A: Aggregator> MaybeWheel<A> {
                 Granularity::Minute => diff.whole_minutes(),
                 Granularity::Hour => diff.whole_hours(),
                 Granularity::Day => diff.whole_days(),
+                Granularity::Week => diff.whole_weeks(),
+                Granularity::Year => diff.whole_weeks() / WEEKS as i64,
             }) as usize;
 
             Aggregation::Scan(slots)
@@ -1,9 +1,13 @@
-use crate::{aggregator::Aggregator, duration::Duration};
+use crate::{aggregator::Aggregator, duration::Duration, wheels::read::hierarchical::Granularity};
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{collections::VecDeque, vec::Vec};
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
 use core::{
     assert,
+    cmp,
     fmt::Debug,
     mem,
     ops::{Range, RangeBounds},
@@ -79,20 +83,55 @@ fn into_range(range: &impl RangeBounds<usize>, len: usize) -> Range<usize> {
 pub struct WheelSlot<A: Aggregator> {
     /// A possible partial aggregate
     pub total: A::PartialAggregate,
+    /// Whether any data was aggregated into the slot
+    pub occupied: bool,
 }
 impl<A: Aggregator> WheelSlot<A> {
     /// Creates a new wheel slot
+    ///
+    /// The slot is marked as occupied if `total` is `Some`.
     pub fn new(total: Option<A::PartialAggregate>) -> Self {
         Self {
+            occupied: total.is_some(),
             total: total.unwrap_or(A::IDENTITY),
         }
     }
+    /// Projects the slot onto the next granularity through [Aggregator::project_on_rollup]
+    #[inline]
+    pub(crate) fn rollup(self, granularity: Granularity) -> Self {
+        Self {
+            total: A::project_on_rollup(self.total, granularity),
+            occupied: self.occupied,
+        }
+    }
     #[cfg(test)]
     fn with_total(total: Option<A::PartialAggregate>) -> Self {
         Self::new(total)
     }
 }
 
+/// Slots appended to a [Wheel] since a base snapshot together with the rotation state they led to
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(bound = "A: Default"))]
+#[derive(Clone, Debug)]
+pub(crate) struct SlotDiff<A: Aggregator> {
+    /// Number of ticks since the base snapshot
+    ticks: usize,
+    /// Appended slots that are still retained ordered from the oldest to the most recent
+    slots: Vec<WheelSlot<A>>,
+    total: Option<A::PartialAggregate>,
+    rotation_count: usize,
+    rotation_occupied: bool,
+    watermark: u64,
+}
+
+impl<A: Aggregator> SlotDiff<A> {
+    /// Returns the number of encoded slots
+    pub(crate) fn len(&self) -> usize {
+        self.slots.len()
+    }
+}
+
 /// An Aggregate wheel maintaining partial aggregates for a specific time dimension
 ///
 /// The wheel maintains partial aggregates per slot, but also updates a `total` partial aggregate for each tick in the wheel.
@@ -110,6 +149,12 @@ pub struct Wheel<A: Aggregator> {
     num_slots: usize,
     /// Partial aggregate for a full rotation
     total: Option<A::PartialAggregate>,
+    /// Per-slot flags indicating whether data was inserted (aligned with `data`)
+    #[cfg_attr(feature = "serde", serde(default))]
+    occupancy: VecDeque<bool>,
+    /// Whether any slot of the current rotation was occupied
+    #[cfg_attr(feature = "serde", serde(default))]
+    rotation_occupied: bool,
     /// The current watermark for this wheel
     watermark: u64,
     /// Configured tick size in milliseconds (seconds wheel -> 1000ms)
@@ -122,8 +167,13 @@ pub struct Wheel<A: Aggregator> {
     data: Data<A>,
     /// Keeps track whether we have done a full rotation (rotation_count == num_slots)
     rotation_count: usize,
+    /// Number of consecutive slots at the head inserted through [Self::insert_empty_slot] or skipped
+    #[cfg_attr(feature = "serde", serde(skip))]
+    empty_run: usize,
     #[cfg(test)]
     pub(crate) total_ticks: usize,
+    #[cfg(test)]
+    pub(crate) total_inserts: usize,
     #[cfg(feature = "profiler")]
     stats: Stats,
 }
@@ -155,13 +205,18 @@ impl<A: Aggregator> Wheel<A> {
             num_slots,
             data,
             total: None,
+            occupancy: VecDeque::with_capacity(num_slots),
+            rotation_occupied: false,
             watermark: conf.watermark,
             tick_size_ms: conf.tick_size_ms,
             retention: conf.retention,
             mode: conf.mode,
             rotation_count: 0,
+            empty_run: 0,
             #[cfg(test)]
             total_ticks: 0,
+            #[cfg(test)]
+            total_inserts: 0,
             #[cfg(feature = "profiler")]
             stats: Stats::default(),
         }
@@ -171,11 +226,24 @@ impl<A: Aggregator> Wheel<A> {
     pub fn watermark(&self) -> u64 {
         self.watermark
     }
+    // Overrides the watermark of the wheel, used for slots that do not span a fixed tick size
+    pub(crate) fn set_watermark(&mut self, watermark: u64) {
+        self.watermark = watermark;
+    }
     /// Returns the current watermark of the wheel as a Duration
     pub fn now(&self) -> Duration {
         Duration::milliseconds(self.watermark as i64)
     }
 
+    /// Returns the start timestamp in milliseconds of the slot at the given age
+    ///
+    /// Age `0` refers to the most recent slot which covers `[watermark - tick_size, watermark)`.
+    #[inline]
+    pub fn slot_timestamp(&self, age: usize) -> u64 {
+        self.watermark
+            .saturating_sub((age as u64 + 1) * self.tick_size_ms)
+    }
+
     /// Combines partial aggregates of the last `subtrahend` slots
     ///
     /// - If given a interval, returns the combined partial aggregate based on that interval,
@@ -235,6 +303,20 @@ impl<A: Aggregator> Wheel<A> {
         self.at(subtrahend).map(|res| A::lower(*res))
     }
 
+    /// Returns whether data was inserted into the slot `subtrahend` slots backwards from the head
+    ///
+    /// Returns `None` if out of bounds.
+    #[inline]
+    pub fn is_occupied(&self, subtrahend: usize) -> Option<bool> {
+        self.occupancy.get(subtrahend).copied()
+    }
+
+    /// Returns the current data layout of the wheel
+    #[inline]
+    pub fn data_layout(&self) -> DataLayout {
+        self.data.layout()
+    }
+
     /// Returns ``true`` if the underlying data is a PrefixDeque
     #[inline]
     pub fn is_prefix(&self) -> bool {
@@ -334,16 +416,58 @@ impl<A: Aggregator> Wheel<A> {
         self.combine_range(range).map(A::lower)
     }
 
+    /// Combines the partial aggregates of an explicit set of slots
+    ///
+    /// Each index refers to a slot position backwards from the head, e.g., `&[3, 7, 11]` on a minutes wheel
+    /// combines the minutes 3, 7, and 11 slots ago. The slots are resolved through the current data layout
+    /// so prefix-enabled and compressed wheels return the original slot partials.
+    ///
+    /// Returns `None` if any index is out of bounds.
+    pub fn combine_slots(&self, indices: &[usize]) -> Option<A::PartialAggregate> {
+        indices.iter().try_fold(A::IDENTITY, |acc, &idx| {
+            if idx >= self.len() {
+                return None;
+            }
+            self.data
+                .combine_range(idx..idx + 1)
+                .map(|partial| A::combine(acc, partial))
+        })
+    }
+
     /// Shift the tail and clear any old entry
     #[inline]
     fn clear_tail(&mut self) {
-        if !self.data.is_empty() && self.retention.should_drop() {
+        if self.evicts_tail() {
             self.data.pop_back();
-        } else if let RetentionPolicy::KeepWithLimit(limit) = self.retention {
-            if self.data.len() > self.capacity + limit {
-                self.data.pop_back();
-            }
-        };
+            self.occupancy.pop_back();
+        }
+    }
+
+    // Returns true if the oldest slot is dropped by the next tick under the configured retention policy
+    #[inline]
+    fn evicts_tail(&self) -> bool {
+        if self.data.is_empty() || !self.is_full() {
+            return false;
+        }
+        match self.retention {
+            RetentionPolicy::KeepWithLimit(limit) => self.data.len() > self.capacity + limit,
+            retention => retention.should_drop(),
+        }
+    }
+
+    /// Returns the start timestamp and partial aggregate of the slot that the next tick evicts
+    ///
+    /// Returns `None` if the next tick keeps all slots under the configured [RetentionPolicy].
+    pub fn next_eviction(&self) -> Option<(u64, A::PartialAggregate)> {
+        if !self.evicts_tail() {
+            return None;
+        }
+        let age = self.data.len() - 1;
+        // the slot at index 0 starts at the current watermark until the tick bumps it
+        let timestamp = self
+            .watermark
+            .saturating_sub(age as u64 * self.tick_size_ms);
+        self.data.get(age).map(|partial| (timestamp, *partial))
     }
     /// Returns the current rotation position in the wheel
     pub fn rotation_count(&self) -> usize {
@@ -356,9 +480,20 @@ impl<A: Aggregator> Wheel<A> {
         self.capacity - self.rotation_count
     }
 
-    fn size_bytesz(&self) -> Option<usize> {
+    pub(crate) fn size_bytesz(&self) -> Option<usize> {
         let data_size = self.data.size_bytes(); // as it is on the heap
-        Some(mem::size_of::<Self>() + data_size)
+        Some(mem::size_of::<Self>() + data_size + self.occupancy_size_bytes())
+    }
+
+    // Returns the bytes used by the slots and the prefix array of the wheel data
+    pub(crate) fn data_size_bytes(&self) -> (usize, usize) {
+        let prefix = self.data.prefix_size_bytes();
+        (self.data.size_bytes() - prefix, prefix)
+    }
+
+    // Returns the bytes used to track which slots data was inserted into
+    pub(crate) fn occupancy_size_bytes(&self) -> usize {
+        mem::size_of::<bool>() * self.occupancy.len()
     }
 
     /// Clears the wheel
@@ -389,7 +524,13 @@ impl<A: Aggregator> Wheel<A> {
         #[cfg(feature = "profiler")]
         profile_scope!(&self.stats.insert);
 
+        #[cfg(test)]
+        {
+            self.total_inserts += 1;
+        }
+
         self.data.push_front(entry);
+        self.empty_run = 0;
 
         // If explicit SIMD support is available but the wheel is configured in Index mode, then
         // avoid making the inner deque contigious.
@@ -403,6 +544,48 @@ impl<A: Aggregator> Wheel<A> {
     pub fn insert_slot(&mut self, slot: WheelSlot<A>) {
         // update roll-up aggregates
         self.insert_head(slot.total);
+        self.occupancy.push_front(slot.occupied);
+    }
+
+    /// Inserts an [Aggregator::IDENTITY] slot that is marked as unoccupied into the head of the wheel
+    ///
+    /// If [Aggregator::identity_is_noop] holds, the slot is not combined into the partial aggregate of the current rotation
+    /// and once the wheel only holds such empty slots, [Self::skips_empty_slot] allows skipping the insert altogether.
+    #[inline]
+    pub fn insert_empty_slot(&mut self) {
+        let empty_run = self.empty_run;
+        self.insert_head(A::IDENTITY);
+        self.occupancy.push_front(false);
+        self.empty_run = empty_run + 1;
+    }
+
+    /// Returns true if inserting an empty slot and ticking would leave the slots of the wheel unchanged
+    ///
+    /// Holds once every retained slot was inserted through [Self::insert_empty_slot] and the tick drops the oldest one.
+    #[inline]
+    pub(crate) fn skips_empty_slot(&self) -> bool {
+        let len = self.data.len() + 1;
+        let drops_tail = match self.retention {
+            RetentionPolicy::KeepWithLimit(limit) => len > self.capacity + limit,
+            retention => retention.should_drop(),
+        };
+        A::identity_is_noop()
+            && self.empty_run >= self.data.len()
+            && len >= self.capacity
+            && drops_tail
+    }
+
+    /// Ticks the wheel for an empty slot without inserting it
+    ///
+    /// Must only be called if [Self::skips_empty_slot] holds since the slots are left untouched.
+    #[inline]
+    pub(crate) fn tick_empty(&mut self) -> Option<WheelSlot<A>> {
+        debug_assert!(self.skips_empty_slot());
+        self.watermark += self.tick_size_ms;
+        // combining an empty slot is a no-op
+        self.total.get_or_insert(A::IDENTITY);
+        self.empty_run += 1;
+        self.rotate()
     }
 
     /// Merge two Wheels of similar granularity
@@ -417,6 +600,12 @@ impl<A: Aggregator> Wheel<A> {
         }
 
         self.data.merge(&other.data);
+        self.empty_run = 0;
+
+        self.rotation_occupied |= other.rotation_occupied;
+        for (occupied, other_occupied) in self.occupancy.iter_mut().zip(other.occupancy.iter()) {
+            *occupied |= *other_occupied;
+        }
     }
 
     /// Tick the wheel by 1 slot
@@ -425,16 +614,30 @@ impl<A: Aggregator> Wheel<A> {
         // bump internal low watermark
         self.watermark += self.tick_size_ms;
 
+        let occupied = self.is_occupied(0).unwrap_or(false);
+
         // Possibly update the partial aggregate for the current rotation
         if let Some(curr) = self.data.get(0) {
-            combine_or_insert::<A>(&mut self.total, *curr);
+            if self.empty_run > 0 && A::identity_is_noop() {
+                // combining an empty slot is a no-op
+                self.total.get_or_insert(A::IDENTITY);
+            } else {
+                combine_or_insert::<A>(&mut self.total, *curr);
+            }
         }
+        self.rotation_occupied |= occupied;
 
         // If the wheel is full, we clear the oldest entry
         if self.is_full() {
             self.clear_tail();
         }
 
+        self.rotate()
+    }
+
+    // Advances the rotation of the wheel and returns the rotation data on a full rotation
+    #[inline]
+    fn rotate(&mut self) -> Option<WheelSlot<A>> {
         self.rotation_count += 1;
 
         #[cfg(test)]
@@ -446,15 +649,62 @@ impl<A: Aggregator> Wheel<A> {
         if self.rotation_count == self.capacity {
             // our total partial aggregate to be rolled up
             let total = self.total.take();
+            let occupied = mem::take(&mut self.rotation_occupied);
 
             // reset count
             self.rotation_count = 0;
-            Some(WheelSlot::new(total))
+            Some(WheelSlot {
+                total: total.unwrap_or(A::IDENTITY),
+                occupied,
+            })
         } else {
             None
         }
     }
 
+    /// Returns the slots appended by the last `ticks` ticks and the current rotation state
+    pub(crate) fn diff(&self, ticks: usize) -> SlotDiff<A> {
+        // appended slots may already have been dropped by the retention policy
+        let retained = cmp::min(ticks, self.data.len());
+        let slots = (0..retained)
+            .rev()
+            .filter_map(|age| {
+                self.data.get(age).map(|total| WheelSlot {
+                    total: *total,
+                    occupied: self.is_occupied(age).unwrap_or(false),
+                })
+            })
+            .collect();
+
+        SlotDiff {
+            ticks,
+            slots,
+            total: self.total,
+            rotation_count: self.rotation_count,
+            rotation_occupied: self.rotation_occupied,
+            watermark: self.watermark,
+        }
+    }
+
+    /// Appends the slots of a diff taken from a wheel that was at the same watermark as this one
+    pub(crate) fn apply_diff(&mut self, diff: &SlotDiff<A>) {
+        // if appended slots were dropped, then so were all the slots of this wheel
+        if diff.ticks > diff.slots.len() {
+            self.clear();
+        }
+        for slot in diff.slots.iter().cloned() {
+            self.insert_slot(slot);
+            if self.is_full() {
+                self.clear_tail();
+            }
+        }
+
+        self.total = diff.total;
+        self.rotation_count = diff.rotation_count;
+        self.rotation_occupied = diff.rotation_occupied;
+        self.watermark = diff.watermark;
+    }
+
     /// Check whether this wheel is utilising all its slots
     #[inline]
     pub fn is_full(&self) -> bool {
@@ -477,7 +727,7 @@ mod tests {
             sum::{U32SumAggregator, U64SumAggregator},
             Compression,
         },
-        wheels::read::hierarchical::HOUR_TICK_MS,
+        wheels::read::hierarchical::{HOUR_TICK_MS, MINUTE_TICK_MS},
     };
 
     #[derive(Clone, Debug, Default)]
@@ -485,6 +735,7 @@ mod tests {
 
     impl Aggregator for PcoSumAggregator {
         const IDENTITY: Self::PartialAggregate = 0;
+        const TAG: &'static str = "PcoSumAggregator";
 
         type Input = u32;
         type PartialAggregate = u32;
@@ -697,4 +948,32 @@ mod tests {
         }
         assert_eq!(wheel.total_slots(), 24 + 10);
     }
+
+    #[test]
+    fn combine_slots_test() {
+        let conf = WheelConf::new(MINUTE_TICK_MS, 60).with_retention_policy(RetentionPolicy::Keep);
+        let mut wheel = Wheel::<U64SumAggregator>::new(conf);
+        let mut prefix_wheel =
+            Wheel::<U64SumAggregator>::new(conf.with_data_layout(DataLayout::Prefix));
+
+        for i in 0..30 {
+            wheel.insert_slot(WheelSlot::with_total(Some(i * 10)));
+            prefix_wheel.insert_slot(WheelSlot::with_total(Some(i * 10)));
+            wheel.tick();
+            prefix_wheel.tick();
+        }
+
+        // minutes 3, 7, and 11 ago
+        let indices = [3, 7, 11];
+        let expected = indices
+            .iter()
+            .map(|idx| *wheel.at(*idx).unwrap())
+            .fold(0, U64SumAggregator::combine);
+
+        assert_eq!(wheel.combine_slots(&indices), Some(expected));
+        assert_eq!(prefix_wheel.combine_slots(&indices), Some(expected));
+
+        assert_eq!(wheel.combine_slots(&[]), Some(0));
+        assert_eq!(wheel.combine_slots(&[3, 30]), None);
+    }
 }
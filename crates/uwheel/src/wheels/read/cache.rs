@@ -0,0 +1,94 @@
+use super::hierarchical::WheelRange;
+
+#[cfg(not(feature = "std"))]
+use alloc::collections::VecDeque;
+#[cfg(feature = "std")]
+use std::collections::VecDeque;
+
+// A std mutex keeps the cache and thus the wheel `Sync`, while `no_std` falls back to a `RefCell`
+#[cfg(feature = "std")]
+type Lock<T> = std::sync::Mutex<T>;
+#[cfg(not(feature = "std"))]
+type Lock<T> = core::cell::RefCell<T>;
+
+#[cfg(feature = "std")]
+#[inline]
+fn lock<T>(lock: &Lock<T>) -> std::sync::MutexGuard<'_, T> {
+    lock.lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+#[cfg(not(feature = "std"))]
+#[inline]
+fn lock<T>(lock: &Lock<T>) -> core::cell::RefMut<'_, T> {
+    lock.borrow_mut()
+}
+
+#[cfg(feature = "std")]
+#[inline]
+fn get_mut<T>(lock: &mut Lock<T>) -> &mut T {
+    lock.get_mut()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+#[cfg(not(feature = "std"))]
+#[inline]
+fn get_mut<T>(lock: &mut Lock<T>) -> &mut T {
+    lock.get_mut()
+}
+
+/// A cached combine range result
+#[derive(Debug, Clone, Copy)]
+struct CacheEntry<P> {
+    range: WheelRange,
+    watermark: u64,
+    result: Option<P>,
+}
+
+/// A small LRU cache of combine range results keyed by `(WheelRange, watermark)`
+///
+/// Entries are kept from most to least recently used which makes lookups a linear scan.
+/// This is intended for a handful of entries such as the ranges polled by a dashboard.
+///
+/// Without the `std` feature the entries are kept in a `RefCell` which makes the cache (and thus [Haw](super::Haw)) `!Sync`.
+#[derive(Debug, Default)]
+pub(crate) struct QueryCache<P> {
+    entries: Lock<VecDeque<CacheEntry<P>>>,
+}
+
+impl<P: Copy> QueryCache<P> {
+    /// Returns the cached result of the range at the given watermark and marks it as most recently used
+    #[inline]
+    pub fn get(&self, range: WheelRange, watermark: u64) -> Option<Option<P>> {
+        let mut entries = lock(&self.entries);
+        let pos = entries
+            .iter()
+            .position(|e| e.range == range && e.watermark == watermark)?;
+        let entry = entries.remove(pos)?;
+        entries.push_front(entry);
+        Some(entry.result)
+    }
+
+    /// Caches the result of the range at the given watermark evicting the least recently used entry if full
+    #[inline]
+    pub fn insert(&self, range: WheelRange, watermark: u64, result: Option<P>, capacity: usize) {
+        let mut entries = lock(&self.entries);
+        entries.retain(|e| !(e.range == range && e.watermark == watermark));
+        entries.push_front(CacheEntry {
+            range,
+            watermark,
+            result,
+        });
+        entries.truncate(capacity);
+    }
+
+    /// Returns the number of cached entries
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        lock(&self.entries).len()
+    }
+
+    /// Removes all cached entries
+    #[inline]
+    pub fn clear(&mut self) {
+        get_mut(&mut self.entries).clear();
+    }
+}
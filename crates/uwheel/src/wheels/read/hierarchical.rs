@@ -1,17 +1,22 @@
 use core::{
     cmp,
     fmt::{self, Display},
+    mem,
 };
-use time::OffsetDateTime;
+use time::{OffsetDateTime, UtcOffset};
 
 use super::{
     super::write::WriterWheel,
+    access::{AccessCounters, AccessStats, WheelAccess},
     aggregation::{
         conf::{DataLayout, RetentionPolicy, WheelMode},
         maybe::MaybeWheel,
+        SlotDiff,
         Wheel,
+        WheelSlot,
     },
-    plan::{ExecutionPlan, WheelAggregation, WheelRanges},
+    cache::QueryCache,
+    plan::{ExecutionPlan, PlanKind, WheelAggregation, WheelRanges},
 };
 
 use crate::{
@@ -30,7 +35,7 @@ use crate::{
 use proptest::prelude::*;
 
 #[cfg(not(feature = "std"))]
-use alloc::vec::Vec;
+use alloc::{boxed::Box, format, string::String, vec, vec::Vec};
 
 #[cfg(feature = "profiler")]
 use super::stats::Stats;
@@ -39,11 +44,13 @@ use uwheel_stats::profile_scope;
 
 crate::cfg_timer! {
     #[cfg(not(feature = "std"))]
-    use alloc::{boxed::Box, rc::Rc};
+    use alloc::rc::Rc;
     use crate::wheels::timer::{RawTimerWheel, TimerWheel, TimerError, TimerAction};
 }
 use super::aggregation::conf::WheelConf;
 
+/// Default Millisecond tick represented in milliseconds
+pub const MILLISECOND_TICK_MS: u64 = 1;
 /// Default Second tick represented in milliseconds
 pub const SECOND_TICK_MS: u64 = time::Duration::SECOND.whole_milliseconds() as u64;
 /// Default Minute tick represented in milliseconds
@@ -63,6 +70,8 @@ pub const YEAR_TICK_MS: u64 = WEEK_TICK_MS * 52;
 pub struct HawConf {
     /// Initial watermark of the wheel
     pub watermark: u64,
+    /// Config for the optional milliseconds wheel (`None` disables it)
+    pub milliseconds: Option<WheelConf>,
     /// Config for the seconds wheel
     pub seconds: WheelConf,
     /// Config for the minutes wheel
@@ -79,25 +88,47 @@ pub struct HawConf {
     pub optimizer: Optimizer,
     /// Flag indicating whether to maintain deltas within the wheel
     pub generate_deltas: bool,
+    /// Maximum number of combine range results to cache between advances (`0` disables caching)
+    pub query_cache_size: usize,
+    /// Flag indicating whether to record per-granularity access statistics
+    pub access_stats: bool,
+    /// Flag indicating whether to skip identity work for seconds without data
+    pub suppress_identity_fill: bool,
+    /// Flag indicating whether the years wheel rolls up calendar years rather than 52 weeks
+    pub calendar_years: bool,
+    /// Maximum length of a range that may be queried (`None` disables the limit)
+    pub max_query_range: Option<Duration>,
+    /// Unix timestamp in milliseconds of the epoch that watermarks are relative to
+    pub epoch_offset: u64,
 }
 
 impl Default for HawConf {
     fn default() -> Self {
-        Self {
-            watermark: 0,
-            seconds: WheelConf::new(SECOND_TICK_MS, SECONDS),
-            minutes: WheelConf::new(MINUTE_TICK_MS, MINUTES),
-            hours: WheelConf::new(HOUR_TICK_MS, HOURS),
-            days: WheelConf::new(DAY_TICK_MS, DAYS),
-            weeks: WheelConf::new(WEEK_TICK_MS, WEEKS),
-            years: WheelConf::new(YEAR_TICK_MS, YEARS),
-            optimizer: Default::default(),
-            generate_deltas: false,
-        }
+        Self::DEFAULT
     }
 }
 
 impl HawConf {
+    /// The default configuration usable in `const` contexts
+    pub const DEFAULT: Self = Self {
+        watermark: 0,
+        milliseconds: None,
+        seconds: WheelConf::new(SECOND_TICK_MS, SECONDS),
+        minutes: WheelConf::new(MINUTE_TICK_MS, MINUTES),
+        hours: WheelConf::new(HOUR_TICK_MS, HOURS),
+        days: WheelConf::new(DAY_TICK_MS, DAYS),
+        weeks: WheelConf::new(WEEK_TICK_MS, WEEKS),
+        years: WheelConf::new(YEAR_TICK_MS, YEARS),
+        optimizer: Optimizer::DEFAULT,
+        generate_deltas: false,
+        query_cache_size: 0,
+        access_stats: false,
+        suppress_identity_fill: false,
+        calendar_years: false,
+        max_query_range: None,
+        epoch_offset: 0,
+    };
+
     /// Configures the initial watermark
     pub fn with_watermark(mut self, watermark: u64) -> Self {
         self.seconds.set_watermark(watermark);
@@ -117,6 +148,9 @@ impl HawConf {
     /// If using ``WheelMode::Index`` in combination with explicit SIMD support,
     /// then make sure to convert wheels to support SIMD (see ``Haw::to_simd_wheels``).
     pub fn with_mode(mut self, mode: WheelMode) -> Self {
+        if let Some(millis) = self.milliseconds.as_mut() {
+            millis.set_mode(mode);
+        }
         self.seconds.set_mode(mode);
         self.minutes.set_mode(mode);
         self.hours.set_mode(mode);
@@ -129,6 +163,9 @@ impl HawConf {
 
     /// Configures all wheels with prefix-sum enabled
     pub fn with_prefix_sum(mut self) -> Self {
+        if let Some(millis) = self.milliseconds.as_mut() {
+            millis.set_data_layout(DataLayout::Prefix);
+        }
         self.seconds.set_data_layout(DataLayout::Prefix);
         self.minutes.set_data_layout(DataLayout::Prefix);
         self.hours.set_data_layout(DataLayout::Prefix);
@@ -141,6 +178,9 @@ impl HawConf {
 
     /// Configures a global retention policy across all granularities
     pub fn with_retention_policy(mut self, policy: RetentionPolicy) -> Self {
+        if let Some(millis) = self.milliseconds.as_mut() {
+            millis.set_retention_policy(policy);
+        }
         self.seconds.set_retention_policy(policy);
         self.minutes.set_retention_policy(policy);
         self.hours.set_retention_policy(policy);
@@ -151,42 +191,261 @@ impl HawConf {
         self
     }
 
+    /// Configures an optional milliseconds granularity with the given number of slots beneath the seconds wheel
+    ///
+    /// The milliseconds wheel retains the partial aggregates of the most recent milliseconds which are queried
+    /// through [Haw::combine_millis]. When enabled, each second is sealed by ticking a [WriterWheel] with a tick
+    /// size of [MILLISECOND_TICK_MS] once per millisecond and the combined milliseconds are fed into the seconds
+    /// wheel. The [RwWheel](crate::RwWheel) configures its writer accordingly while a [Haw] advanced through other
+    /// means (e.g., [Haw::delta_advance]) fills the milliseconds wheel with empty slots that cannot be queried.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, Conf, Entry, HawConf, RwWheel};
+    ///
+    /// let conf = Conf::default().with_haw_conf(HawConf::default().with_milliseconds(2000));
+    /// let mut wheel: RwWheel<U32SumAggregator> = RwWheel::with_conf(conf);
+    /// wheel.insert(Entry::new(1, 250));
+    /// wheel.insert(Entry::new(2, 750));
+    /// wheel.advance_to(1000);
+    ///
+    /// assert_eq!(wheel.read().combine_millis(0, 500), Some(1));
+    /// assert_eq!(wheel.read().combine_millis(0, 1000), Some(3));
+    /// ```
+    pub const fn with_milliseconds(mut self, slots: usize) -> Self {
+        self.milliseconds = Some(WheelConf::new(MILLISECOND_TICK_MS, slots));
+        self
+    }
     /// Configures the seconds granularity
-    pub fn with_seconds(mut self, seconds: WheelConf) -> Self {
+    pub const fn with_seconds(mut self, seconds: WheelConf) -> Self {
         self.seconds = seconds;
         self
     }
     /// Configures the minutes granularity
-    pub fn with_minutes(mut self, minutes: WheelConf) -> Self {
+    pub const fn with_minutes(mut self, minutes: WheelConf) -> Self {
         self.minutes = minutes;
         self
     }
     /// Configures the hours granularity
-    pub fn with_hours(mut self, hours: WheelConf) -> Self {
+    pub const fn with_hours(mut self, hours: WheelConf) -> Self {
         self.hours = hours;
         self
     }
     /// Configures the days granularity
-    pub fn with_days(mut self, days: WheelConf) -> Self {
+    pub const fn with_days(mut self, days: WheelConf) -> Self {
         self.days = days;
         self
     }
     /// Configures the weeks granularity
-    pub fn with_weeks(mut self, weeks: WheelConf) -> Self {
+    pub const fn with_weeks(mut self, weeks: WheelConf) -> Self {
         self.weeks = weeks;
         self
     }
     /// Configures the years granularity
-    pub fn with_years(mut self, years: WheelConf) -> Self {
+    pub const fn with_years(mut self, years: WheelConf) -> Self {
         self.years = years;
         self
     }
 
+    /// Configures the wheel to adapt its data layout based on query access patterns
+    ///
+    /// Granularities selected by the [AdaptiveLayout] policy are converted to [DataLayout::Prefix]
+    /// during advance. A plain threshold selects granularities whose average scan count exceeds it.
+    /// Only applies to aggregators with an exact inverse (see [Aggregator::exact_inverse]).
+    pub fn with_adaptive_layout(mut self, layout: impl Into<AdaptiveLayout>) -> Self {
+        self.optimizer.adaptive_layout(Some(layout.into()));
+        self
+    }
+
     /// Configures the wheel to generate and maintain deltas
     pub fn with_deltas(mut self) -> Self {
         self.generate_deltas = true;
         self
     }
+
+    /// Configures the wheel to record per-granularity access statistics of executed wheel aggregations
+    ///
+    /// The statistics are returned by [Haw::access_stats]. Recording is also enabled by an adaptive layout
+    /// (see [Self::with_adaptive_layout]) and otherwise skipped to keep queries free of the bookkeeping.
+    pub fn with_access_stats(mut self) -> Self {
+        self.access_stats = true;
+        self
+    }
+
+    /// Configures a least-recently-used cache of up to `size` combine range results
+    ///
+    /// Repeated identical queries between advances are then served from the cache.
+    /// The cache is invalidated whenever the wheel ticks.
+    pub fn with_query_cache(mut self, size: usize) -> Self {
+        self.query_cache_size = size;
+        self
+    }
+
+    /// Configures the wheel to skip the work of filling seconds without data with [Aggregator::IDENTITY]
+    ///
+    /// Seconds without data are not combined into the roll-up aggregates, and once the seconds wheel
+    /// only retains such empty seconds, further empty seconds are not inserted at all. Only applies to
+    /// aggregators where [Aggregator::identity_is_noop] holds, so query results are unchanged.
+    pub fn with_identity_fill_suppression(mut self) -> Self {
+        self.suppress_identity_fill = true;
+        self
+    }
+
+    /// Configures the years wheel to roll up calendar years that start on January 1 (UTC)
+    ///
+    /// By default a year is 52 weeks long, which keeps every wheel slot at a fixed size and lets the
+    /// weeks wheel roll up into the years wheel, but drifts from calendar years by a day or two each year.
+    /// With calendar years, the years wheel is instead fed the days completed since the previous January 1,
+    /// so year slots match calendar years and ranges spanning whole calendar years are answered by the years wheel.
+    ///
+    /// The trade-off is that year slots differ in length (365 or 366 days, or less for the first year)
+    /// and the rotations of the weeks wheel are discarded.
+    ///
+    /// # Panics
+    ///
+    /// [Haw::new] panics if the initial watermark, shifted by the [epoch offset](Self::with_epoch_offset),
+    /// is not aligned to a UTC day.
+    pub fn with_calendar_years(mut self) -> Self {
+        self.calendar_years = true;
+        self
+    }
+
+    /// Configures the maximum length of a range that may be queried
+    ///
+    /// Protects shared wheels from accidental full-wheel scans by untrusted callers. Ranges longer than `max`
+    /// are rejected before planning: [Haw::try_combine_range] and [Haw::combine_range_at] return
+    /// [QueryError::RangeTooLarge] while the other range queries (e.g., [Haw::combine_range], [Haw::range],
+    /// [Haw::group_by] or [Haw::combine_range_fold]) return `None`, `false` or an empty result.
+    pub fn with_max_query_range(mut self, max: Duration) -> Self {
+        self.max_query_range = Some(max);
+        self
+    }
+
+    /// Configures the epoch that the watermark and timestamps of the wheel are relative to
+    ///
+    /// By default the wheel uses the unix epoch. With an offset, a watermark of `0` corresponds to the unix
+    /// timestamp `offset_ms` which lets data relative to a domain-specific epoch be inserted without converting
+    /// every timestamp. Date ranges (see [WheelRange]) still refer to absolute instants and are translated into the
+    /// epoch of the wheel. The offset should be aligned to a UTC day so that the slots of the wheels line up with dates.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, HawConf, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// // 2000-01-01T00:00:00Z
+    /// let epoch = 946684800000;
+    /// let mut haw: Haw<U32SumAggregator> = Haw::new(HawConf::default().with_epoch_offset(epoch));
+    /// haw.delta_advance([Some(1), Some(2), Some(3)]);
+    ///
+    /// assert_eq!(haw.watermark(), 3000);
+    /// assert_eq!(haw.combine_range(WheelRange::new_unchecked(epoch + 1000, epoch + 3000)), Some(5));
+    /// ```
+    pub fn with_epoch_offset(mut self, offset_ms: u64) -> Self {
+        self.epoch_offset = offset_ms;
+        self
+    }
+}
+
+/// Returns the total number of slots of a fully utilised [Haw] with the given configuration
+///
+/// Usable in `const` contexts, for instance to size static buffers in `no_std` environments.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{total_slots, Haw, HawConf};
+///
+/// const SLOTS: usize = total_slots(&HawConf::DEFAULT);
+/// assert_eq!(SLOTS, Haw::<uwheel::aggregator::sum::U64SumAggregator>::TOTAL_WHEEL_SLOTS);
+/// ```
+pub const fn total_slots(conf: &HawConf) -> usize {
+    let millis = match conf.milliseconds {
+        Some(millis) => millis.capacity,
+        None => 0,
+    };
+    millis
+        + conf.seconds.capacity
+        + conf.minutes.capacity
+        + conf.hours.capacity
+        + conf.days.capacity
+        + conf.weeks.capacity
+        + conf.years.capacity
+}
+
+/// Returns the estimated memory in bytes of a fully utilised [Haw] with the given configuration
+///
+/// Matches [Haw::size_bytes] once every granularity holds `capacity` slots. A [DataLayout::Prefix]
+/// wheel counts twice its slots while a [DataLayout::Compressed] wheel is estimated by its uncompressed size.
+/// Each slot additionally counts the flag tracking whether data was inserted into it.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{estimated_bytes, HawConf, aggregator::sum::U64SumAggregator};
+///
+/// const BYTES: usize = estimated_bytes::<U64SumAggregator>(&HawConf::DEFAULT);
+/// assert!(BYTES > 0);
+/// ```
+pub const fn estimated_bytes<A: Aggregator>(conf: &HawConf) -> usize {
+    const fn level<A: Aggregator>(conf: &WheelConf) -> usize {
+        let slot_bytes = conf.capacity * mem::size_of::<A::PartialAggregate>();
+        let data_bytes = match conf.data_layout {
+            DataLayout::Prefix => slot_bytes * 2,
+            DataLayout::Normal | DataLayout::Compressed(_) => slot_bytes,
+        };
+        let occupancy_bytes = conf.capacity * mem::size_of::<bool>();
+        mem::size_of::<Wheel<A>>() + data_bytes + occupancy_bytes
+    }
+    let millis = match conf.milliseconds {
+        Some(ref millis) => level::<A>(millis),
+        None => 0,
+    };
+    millis
+        + level::<A>(&conf.seconds)
+        + level::<A>(&conf.minutes)
+        + level::<A>(&conf.hours)
+        + level::<A>(&conf.days)
+        + level::<A>(&conf.weeks)
+        + level::<A>(&conf.years)
+}
+
+/// Combines the results of querying several wheels for the same range [start, end)
+///
+/// Answers occasional cross-source queries over wheels that are maintained separately without merging them
+/// (see [ReaderWheel::merge](crate::wheels::read::ReaderWheel::merge)). The wheels must have been advanced to the
+/// same watermark so that the range refers to the same point in time in each of them. Results are combined in
+/// the order of the given wheels.
+///
+/// Returns `None` if no wheel is given, the watermarks differ, or any of the wheels cannot answer the range.
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{combine_range_across, Haw, WheelRange, aggregator::sum::U32SumAggregator};
+///
+/// let mut a: Haw<U32SumAggregator> = Haw::default();
+/// let mut b: Haw<U32SumAggregator> = Haw::default();
+/// a.delta_advance([Some(1), Some(2)]);
+/// b.delta_advance([Some(10), None]);
+///
+/// let range = WheelRange::new_unchecked(0, 2000);
+/// assert_eq!(combine_range_across(&[&a, &b], range), Some(13));
+/// ```
+pub fn combine_range_across<A: Aggregator>(
+    wheels: &[&Haw<A>],
+    range: impl Into<WheelRange>,
+) -> Option<A::PartialAggregate> {
+    let range = range.into();
+    let (first, rest) = wheels.split_first()?;
+    if rest.iter().any(|haw| haw.watermark() != first.watermark()) {
+        return None;
+    }
+    wheels.iter().try_fold(A::IDENTITY, |acc, haw| {
+        haw.combine_range(range)
+            .map(|partial| A::combine(acc, partial))
+    })
 }
 
 /// Default capacity of second slots
@@ -229,8 +488,508 @@ impl Display for RangeError {
     }
 }
 
+/// Errors that may occur when executing time-based queries
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum QueryError {
+    /// A timestamp involved in the query cannot be represented as a date
+    InvalidTimestamp {
+        /// Invalid unix timestamp in milliseconds
+        ts_ms: u64,
+    },
+    /// The range cannot be expressed using slots of the requested granularity
+    UnalignedRange {
+        /// Requested granularity
+        granularity: Granularity,
+    },
+    /// The range exceeds the maximum query range configured by [HawConf::with_max_query_range]
+    RangeTooLarge {
+        /// Length of the requested range in milliseconds
+        range_ms: u64,
+        /// Maximum query range in milliseconds
+        max_ms: u64,
+    },
+}
+impl Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryError::InvalidTimestamp { ts_ms } => {
+                write!(f, "{ts_ms} cannot be represented as a date")
+            }
+            QueryError::UnalignedRange { granularity } => {
+                write!(f, "range is not aligned to {granularity:?} slots")
+            }
+            QueryError::RangeTooLarge { range_ms, max_ms } => {
+                write!(f, "range of {range_ms}ms exceeds the maximum of {max_ms}ms")
+            }
+        }
+    }
+}
+
+/// Describes how much of a queried range is covered by data retained in a [Haw]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Coverage {
+    /// Every slot of the range is available
+    Full,
+    /// Only a part of the range is available, e.g., the start has been evicted or the end is ahead of the watermark
+    Partial,
+    /// No slot of the range is available
+    None,
+}
+
+/// The result of a combine range query that distinguishes ranges without data from aggregated values
+///
+/// A sum over a range without data and a sum over data that adds up to zero both yield `0` through
+/// [Haw::combine_range] and `unwrap_or(0)`, whereas [Haw::combine_range_result] reports the former as [QueryResult::Empty].
+pub enum QueryResult<A: Aggregator> {
+    /// The partial aggregate of the slots that data was inserted into
+    Value(A::PartialAggregate),
+    /// The range contains no data or cannot be answered by the wheel
+    Empty,
+}
+
+impl<A: Aggregator> QueryResult<A> {
+    /// Returns `true` if the range contains no data
+    pub fn is_empty(&self) -> bool {
+        matches!(self, QueryResult::Empty)
+    }
+    /// Returns the partial aggregate or `None` if the range contains no data
+    pub fn value(self) -> Option<A::PartialAggregate> {
+        match self {
+            QueryResult::Value(partial) => Some(partial),
+            QueryResult::Empty => None,
+        }
+    }
+    /// Returns the partial aggregate or [Aggregator::IDENTITY] if the range contains no data
+    pub fn unwrap_or_identity(self) -> A::PartialAggregate {
+        self.value().unwrap_or(A::IDENTITY)
+    }
+}
+
+impl<A: Aggregator> Clone for QueryResult<A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<A: Aggregator> Copy for QueryResult<A> {}
+
+impl<A: Aggregator> fmt::Debug for QueryResult<A> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            QueryResult::Value(partial) => f.debug_tuple("Value").field(partial).finish(),
+            QueryResult::Empty => f.write_str("Empty"),
+        }
+    }
+}
+
+impl<A: Aggregator> PartialEq for QueryResult<A>
+where
+    A::PartialAggregate: PartialEq,
+{
+    fn eq(&self, other: &Self) -> bool {
+        self.value() == other.value()
+    }
+}
+
+/// Error returned when a [Haw] is advanced further than its [Haw::CYCLE_LENGTH]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub struct CycleExceeded {
+    /// Number of seconds the wheel was requested to advance
+    pub ticks: u64,
+}
+impl Display for CycleExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "advancing {} seconds exceeds the wheel cycle length, all wheels were cleared",
+            self.ticks
+        )
+    }
+}
+
+/// Errors that may occur when merging two [Haw]s
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum MergeError {
+    /// The wheels of a granularity are configured with different tick sizes
+    IncompatibleTickSize {
+        /// Granularity of the incompatible wheels
+        granularity: Granularity,
+        /// Tick size in milliseconds of the wheel being merged into
+        expected: u64,
+        /// Tick size in milliseconds of the other wheel
+        found: u64,
+    },
+    /// The wheels of a granularity use a compressed data layout which cannot be merged
+    CompressedLayout {
+        /// Granularity of the compressed wheel
+        granularity: Granularity,
+    },
+    /// Only one of the wheels is configured with calendar years (see [HawConf::with_calendar_years])
+    IncompatibleYearLength,
+    /// The wheels are relative to different epochs (see [HawConf::with_epoch_offset])
+    IncompatibleEpoch {
+        /// Epoch offset in milliseconds of the wheel being merged into
+        expected: u64,
+        /// Epoch offset in milliseconds of the other wheel
+        found: u64,
+    },
+    /// Only one of the wheels is configured with a milliseconds wheel (see [HawConf::with_milliseconds])
+    IncompatibleMilliseconds,
+}
+impl Display for MergeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MergeError::IncompatibleTickSize {
+                granularity,
+                expected,
+                found,
+            } => write!(
+                f,
+                "cannot merge {granularity:?} wheels with tick sizes {expected}ms and {found}ms"
+            ),
+            MergeError::CompressedLayout { granularity } => {
+                write!(f, "cannot merge compressed {granularity:?} wheels")
+            }
+            MergeError::IncompatibleYearLength => {
+                write!(
+                    f,
+                    "cannot merge wheels with calendar years and 52-week years"
+                )
+            }
+            MergeError::IncompatibleEpoch { expected, found } => write!(
+                f,
+                "cannot merge wheels with epoch offsets {expected}ms and {found}ms"
+            ),
+            MergeError::IncompatibleMilliseconds => write!(
+                f,
+                "cannot merge wheels with and without a milliseconds wheel"
+            ),
+        }
+    }
+}
+
+/// Errors that may occur when stitching two [Haw]s through [Haw::concat_history]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ConcatError {
+    /// The recent wheel does not retain any data
+    Empty,
+    /// The older wheel ends before the oldest data of the recent wheel
+    Gap {
+        /// Watermark in milliseconds of the older wheel
+        older_watermark: u64,
+        /// Start timestamp in milliseconds of the oldest data retained by the recent wheel
+        oldest: u64,
+    },
+}
+impl Display for ConcatError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConcatError::Empty => write!(f, "the recent wheel does not retain any data"),
+            ConcatError::Gap {
+                older_watermark,
+                oldest,
+            } => write!(
+                f,
+                "the older wheel ends at {older_watermark} but the recent wheel starts at {oldest}"
+            ),
+        }
+    }
+}
+
+/// Errors that may occur when diffing two [Haw]s or applying a [WheelDiff]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum DiffError {
+    /// The base wheel has been advanced beyond the wheel it is diffed against
+    BaseAhead {
+        /// Watermark in milliseconds of the base wheel
+        base: u64,
+        /// Watermark in milliseconds of the diffed wheel
+        watermark: u64,
+    },
+    /// The diff was taken against a base at a different watermark than the wheel it is applied to
+    WatermarkMismatch {
+        /// Watermark in milliseconds of the base the diff was taken against
+        expected: u64,
+        /// Watermark in milliseconds of the wheel the diff is applied to
+        found: u64,
+    },
+}
+impl Display for DiffError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DiffError::BaseAhead { base, watermark } => write!(
+                f,
+                "the base wheel at {base} is ahead of the diffed wheel at {watermark}"
+            ),
+            DiffError::WatermarkMismatch { expected, found } => write!(
+                f,
+                "the diff expects a wheel at {expected} but the wheel is at {found}"
+            ),
+        }
+    }
+}
+
+/// Errors that may occur when seeding a [Haw] through [Haw::seed_granularity]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum SeedError {
+    /// The partials span more time than has passed since the epoch of the wheel at the given watermark
+    StartBeforeEpoch {
+        /// Number of partials to seed
+        partials: usize,
+        /// Watermark in milliseconds that the partials end at
+        watermark: u64,
+    },
+}
+impl Display for SeedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SeedError::StartBeforeEpoch {
+                partials,
+                watermark,
+            } => write!(
+                f,
+                "{partials} partials ending at {watermark} would start before the epoch"
+            ),
+        }
+    }
+}
+
+/// Partial aggregates appended to a [Haw] since a prior snapshot of it
+///
+/// Created through [Haw::diff] and applied through [Haw::apply_diff]. Only the slots appended to each
+/// granularity wheel are encoded, which is typically far smaller than a full snapshot of the wheel.
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[cfg_attr(feature = "serde", serde(bound = "A: Default"))]
+#[derive(Clone, Debug)]
+pub struct WheelDiff<A: Aggregator> {
+    base_watermark: u64,
+    watermark: u64,
+    /// Appended slots indexed by [Granularity]
+    levels: [Option<SlotDiff<A>>; 6],
+    /// Appended slots of the milliseconds wheel
+    #[cfg_attr(feature = "serde", serde(default))]
+    millis: Option<SlotDiff<A>>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    unfed_millis: Vec<(u64, u64)>,
+    calendar_year: Option<WheelSlot<A>>,
+    deltas: Vec<Option<A::PartialAggregate>>,
+}
+
+impl<A: Aggregator> WheelDiff<A> {
+    /// Returns the watermark in milliseconds of the base the diff was taken against
+    pub fn base_watermark(&self) -> u64 {
+        self.base_watermark
+    }
+    /// Returns the watermark in milliseconds that the diff advances a wheel to
+    pub fn watermark(&self) -> u64 {
+        self.watermark
+    }
+    /// Returns the number of slots encoded across all granularities
+    pub fn len(&self) -> usize {
+        self.levels
+            .iter()
+            .chain(core::iter::once(&self.millis))
+            .flatten()
+            .map(SlotDiff::len)
+            .sum()
+    }
+    /// Returns true if the diff does not encode any slots
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A read view answering queries over the combined history of two time-disjoint [Haw]s
+///
+/// Created through [Haw::concat_history]. Ranges before the boundary are answered by the older wheel
+/// while the remainder is answered by the recent wheel.
+pub struct HistoryView<'a, A: Aggregator> {
+    older: &'a Haw<A>,
+    recent: &'a Haw<A>,
+    boundary: u64,
+}
+
+impl<A: Aggregator> HistoryView<'_, A> {
+    /// Returns the timestamp in milliseconds where the recent wheel takes over from the older wheel
+    pub fn boundary(&self) -> u64 {
+        self.boundary
+    }
+
+    /// Returns the watermark of the recent wheel
+    pub fn watermark(&self) -> u64 {
+        self.recent.watermark()
+    }
+
+    /// Combines partial aggregates within the given date range [start, end) across both wheels
+    ///
+    /// Returns `None` if neither wheel can answer its part of the range.
+    pub fn combine_range(&self, range: impl Into<WheelRange>) -> Option<A::PartialAggregate> {
+        let range = range.into();
+        let start_ms = self.recent.to_ms(range.start.unix_timestamp() as u64);
+        let end_ms = self.recent.to_ms(range.end.unix_timestamp() as u64);
+
+        if end_ms <= self.boundary {
+            return self.older.combine_range(range);
+        }
+        if start_ms >= self.boundary {
+            return self.recent.combine_range(range);
+        }
+
+        let older = self
+            .older
+            .combine_range(WheelRange::new(start_ms, self.boundary).ok()?);
+        let recent = self
+            .recent
+            .combine_range(WheelRange::new(self.boundary, end_ms).ok()?);
+
+        // combine in time order to support non-commutative aggregators
+        match (older, recent) {
+            (Some(older), Some(recent)) => Some(A::combine(older, recent)),
+            (older, recent) => older.or(recent),
+        }
+    }
+
+    /// Combines partial aggregates within the given date range [start, end) across both wheels and lowers it to a final aggregate
+    pub fn combine_range_and_lower(&self, range: impl Into<WheelRange>) -> Option<A::Aggregate> {
+        self.combine_range(range).map(A::lower)
+    }
+}
+
+/// A lazy iterator over the window aggregates fired while advancing a [Haw]
+///
+/// Created through [Haw::advance_iter].
+pub struct AdvanceIter<'a, A: Aggregator> {
+    haw: &'a mut Haw<A>,
+    waw: &'a mut WriterWheel<A>,
+    remaining: usize,
+    // windows fired by the last tick that have not been yielded yet
+    pending: Vec<WindowAggregate<A::PartialAggregate>>,
+}
+
+impl<A: Aggregator> AdvanceIter<'_, A> {
+    /// Returns the number of ticks left to perform
+    pub fn remaining_ticks(&self) -> usize {
+        self.remaining
+    }
+}
+
+impl<A: Aggregator> Iterator for AdvanceIter<'_, A> {
+    type Item = WindowAggregate<A::PartialAggregate>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if !self.pending.is_empty() {
+                return Some(self.pending.remove(0));
+            }
+            if self.remaining == 0 {
+                return None;
+            }
+            self.remaining -= 1;
+            self.haw.advance_tick(self.waw, &mut self.pending);
+            if self.remaining == 0 {
+                self.haw.adapt_layout_maybe();
+            }
+        }
+    }
+}
+
+/// A bucket of slots produced by [Haw::decimate]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DecimatedPoint<P> {
+    /// Start timestamp of the first slot in the bucket
+    pub timestamp: u64,
+    /// Number of slots combined into the bucket
+    pub slots: usize,
+    /// Combined partial aggregate of all slots in the bucket
+    pub aggregate: P,
+    /// Smallest slot within the bucket
+    pub min: P,
+    /// Largest slot within the bucket
+    pub max: P,
+}
+
+/// Memory used by a single granularity of a [Haw] as reported by [Haw::size_breakdown]
+#[derive(Debug, Copy, Clone, Default)]
+pub struct LevelSize {
+    /// Data layout of the wheel or `None` if the granularity has not been initialized
+    pub layout: Option<DataLayout>,
+    /// Fixed size of the wheel structure
+    pub overhead_bytes: usize,
+    /// Bytes used by the slots holding partial aggregates
+    pub slot_bytes: usize,
+    /// Bytes used by the prefix-sum array, only non-zero for the prefix layout
+    pub prefix_bytes: usize,
+    /// Bytes used to track which slots data was inserted into (see [Haw::occupancy])
+    pub occupancy_bytes: usize,
+}
+
+impl LevelSize {
+    /// Returns the total bytes used by the granularity
+    pub fn total(&self) -> usize {
+        self.overhead_bytes + self.slot_bytes + self.prefix_bytes + self.occupancy_bytes
+    }
+}
+
+/// Per-granularity memory breakdown of a [Haw]
+///
+/// Created through [Haw::size_breakdown].
+#[derive(Debug, Copy, Clone, Default)]
+pub struct SizeBreakdown {
+    /// Memory used by the optional milliseconds wheel
+    pub milliseconds: LevelSize,
+    /// Memory used by the seconds wheel
+    pub seconds: LevelSize,
+    /// Memory used by the minutes wheel
+    pub minutes: LevelSize,
+    /// Memory used by the hours wheel
+    pub hours: LevelSize,
+    /// Memory used by the days wheel
+    pub days: LevelSize,
+    /// Memory used by the weeks wheel
+    pub weeks: LevelSize,
+    /// Memory used by the years wheel
+    pub years: LevelSize,
+}
+
+impl SizeBreakdown {
+    /// Returns the memory used by the given granularity
+    pub fn level(&self, granularity: Granularity) -> &LevelSize {
+        match granularity {
+            Granularity::Second => &self.seconds,
+            Granularity::Minute => &self.minutes,
+            Granularity::Hour => &self.hours,
+            Granularity::Day => &self.days,
+            Granularity::Week => &self.weeks,
+            Granularity::Year => &self.years,
+        }
+    }
+
+    /// Returns the total bytes used across all granularities
+    pub fn total(&self) -> usize {
+        [
+            self.milliseconds,
+            self.seconds,
+            self.minutes,
+            self.hours,
+            self.days,
+            self.weeks,
+            self.years,
+        ]
+        .iter()
+        .map(LevelSize::total)
+        .sum()
+    }
+}
+
 /// A Wheel time range representing a closed-open interval of [start, end)
 ///
+/// Wheels are aligned to whole seconds, so bounds with a fractional second are rounded to cover the interval:
+/// the start is rounded down and the end is rounded up to the nearest second. For instance, the range
+/// `[1500, 3200)` in milliseconds covers the second buckets `[1000, 4000)`.
+///
+/// The end is excluded, so `[0, 60000)` covers the seconds `0` to `59`. Use [WheelRange::inclusive] to
+/// create a range from inclusive bounds instead.
+///
 /// # Example
 /// ```
 /// use uwheel::WheelRange;
@@ -268,12 +1027,14 @@ impl Arbitrary for WheelRange {
 
 impl WheelRange {
     /// Creates a WheelRange using a start and end timestamp as unix timestamps in milliseconds
+    ///
+    /// Fractional seconds are rounded to cover the interval (see [WheelRange]).
     pub fn new(start_ms: u64, end_ms: u64) -> Result<Self, RangeError> {
         // NOTE: internally we have to convert it to seconds for `OffsetDateTime`
-        let start = OffsetDateTime::from_unix_timestamp(start_ms as i64 / 1000)
+        let start = OffsetDateTime::from_unix_timestamp((start_ms / 1000) as i64)
             .map_err(|_| RangeError::InvalidStart { start_ms })?;
 
-        let end = OffsetDateTime::from_unix_timestamp(end_ms as i64 / 1000)
+        let end = OffsetDateTime::from_unix_timestamp(end_ms.div_ceil(1000) as i64)
             .map_err(|_| RangeError::InvalidEnd { end_ms })?;
 
         Ok(Self { start, end })
@@ -286,14 +1047,70 @@ impl WheelRange {
     pub fn new_unchecked(start_ms: u64, end_ms: u64) -> Self {
         Self::new(start_ms, end_ms).unwrap()
     }
-    #[doc(hidden)]
-    pub fn from(start: OffsetDateTime, end: OffsetDateTime) -> Self {
-        Self { start, end }
-    }
-}
-
-impl WheelRange {
-    /// Returns the lowest granularity of the Wheel range
+    /// Creates a WheelRange covering the closed interval [start, end] of unix timestamps in milliseconds
+    ///
+    /// Since wheel ranges are closed-open, the end is extended to the end of the second, minute, hour, or day
+    /// that `end` falls on, whichever is the lowest granularity the bounds are aligned to (see [Granularity]).
+    /// For instance, `inclusive(0, 59000)` equals `new(0, 60000)` while `inclusive(0, 120000)` ends at the
+    /// minute `2` and thus equals `new(0, 180000)`.
+    ///
+    /// Bounds with a fractional second are not aligned to any granularity above seconds, so the range
+    /// ends with the second that `end` falls on. For instance, `inclusive(0, 120500)` equals `new(0, 121000)`.
+    ///
+    /// Returns [RangeError::InvalidEnd] if the extended end is not a valid unix timestamp.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::WheelRange;
+    ///
+    /// let range = WheelRange::inclusive(1000, 5000).unwrap();
+    /// assert_eq!(range, WheelRange::new_unchecked(1000, 6000));
+    ///
+    /// // a single second
+    /// let range = WheelRange::inclusive(5000, 5000).unwrap();
+    /// assert_eq!(range, WheelRange::new_unchecked(5000, 6000));
+    ///
+    /// // a fractional end includes the second it falls on
+    /// let range = WheelRange::inclusive(1000, 5500).unwrap();
+    /// assert_eq!(range, WheelRange::new_unchecked(1000, 6000));
+    /// ```
+    pub fn inclusive(start_ms: u64, end_ms: u64) -> Result<Self, RangeError> {
+        let unit_ms = if !start_ms.is_multiple_of(1000) || !end_ms.is_multiple_of(1000) {
+            1000
+        } else {
+            match Self::new(start_ms, end_ms)?.lowest_granularity() {
+                Granularity::Second => 1000,
+                Granularity::Minute => 60 * 1000,
+                Granularity::Hour => 60 * 60 * 1000,
+                _ => 24 * 60 * 60 * 1000,
+            }
+        };
+        let end = (end_ms - end_ms % unit_ms)
+            .checked_add(unit_ms)
+            .ok_or(RangeError::InvalidEnd { end_ms })?;
+        Self::new(start_ms, end).map_err(|_| RangeError::InvalidEnd { end_ms })
+    }
+    /// Creates a WheelRange from a start and end date
+    ///
+    /// Fractional seconds are rounded to cover the interval (see [WheelRange]).
+    #[doc(hidden)]
+    pub fn from(start: OffsetDateTime, end: OffsetDateTime) -> Self {
+        let whole_end = end.replace_nanosecond(0).unwrap();
+        let end = if whole_end < end {
+            whole_end + time::Duration::SECOND
+        } else {
+            whole_end
+        };
+        Self {
+            start: start.replace_nanosecond(0).unwrap(),
+            end,
+        }
+    }
+}
+
+impl WheelRange {
+    /// Returns the lowest granularity of the Wheel range
     pub(crate) fn lowest_granularity(&self) -> Granularity {
         let is_seconds = self.start.second() != 0 || self.end.second() != 0;
         let is_minutes = self.start.minute() != 0 || self.end.minute() != 0;
@@ -311,14 +1128,36 @@ impl WheelRange {
             unimplemented!("Weeks and years not supported");
         }
     }
+    /// Returns the lowest granularity of the Wheel range including weeks and years
+    ///
+    /// Day-aligned ranges that span whole weeks are classified as [Granularity::Week]
+    /// and ranges that span whole years (52 weeks) as [Granularity::Year].
+    pub(crate) fn estimation_granularity(&self) -> Granularity {
+        match self.lowest_granularity() {
+            Granularity::Day => {
+                let days = self.duration().whole_days();
+                let weeks = self.duration().whole_weeks();
+                if days == 0 || days % DAYS as i64 != 0 {
+                    Granularity::Day
+                } else if weeks % WEEKS as i64 != 0 {
+                    Granularity::Week
+                } else {
+                    Granularity::Year
+                }
+            }
+            granularity => granularity,
+        }
+    }
     /// Returns an estimation of the number of scans
     pub fn scan_estimation(&self) -> i64 {
         let dur = self.duration();
-        match self.lowest_granularity() {
+        match self.estimation_granularity() {
             Granularity::Second => dur.whole_seconds(),
             Granularity::Minute => dur.whole_minutes(),
             Granularity::Hour => dur.whole_hours(),
             Granularity::Day => dur.whole_days(),
+            Granularity::Week => dur.whole_weeks(),
+            Granularity::Year => dur.whole_weeks() / WEEKS as i64,
         }
     }
 
@@ -329,13 +1168,22 @@ impl WheelRange {
     }
 }
 
+/// Time granularities of the wheels maintained by a [Haw]
 #[derive(Debug, Copy, PartialEq, Eq, Clone)]
 #[repr(usize)]
-pub(crate) enum Granularity {
+pub enum Granularity {
+    /// Seconds wheel
     Second,
+    /// Minutes wheel
     Minute,
+    /// Hours wheel
     Hour,
+    /// Days wheel
     Day,
+    /// Weeks wheel
+    Week,
+    /// Years wheel
+    Year,
 }
 
 /// Default threshold for SIMD-based Wheel Aggregations
@@ -348,28 +1196,126 @@ pub struct Heuristics {
     simd_threshold: usize,
 }
 
+impl Heuristics {
+    const DEFAULT: Self = Self {
+        simd_threshold: DEFAULT_SIMD_THRESHOLD,
+    };
+}
+
 impl Default for Heuristics {
     fn default() -> Self {
-        Self {
-            simd_threshold: DEFAULT_SIMD_THRESHOLD,
-        }
+        Self::DEFAULT
     }
 }
 
 /// HAW Query optimization configuration
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
-#[derive(Default, Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy)]
 pub struct Optimizer {
     /// Defines whether the optimizer should use framework provided hints
     use_hints: bool,
     /// A set of heuristics that the optimizer takes into context
     heuristics: Heuristics,
+    /// Policy selecting granularities that are converted to prefix-enabled wheels
+    adaptive_layout: Option<AdaptiveLayout>,
+}
+impl Default for Optimizer {
+    fn default() -> Self {
+        Self::DEFAULT
+    }
 }
+
 impl Optimizer {
+    const DEFAULT: Self = Self {
+        use_hints: false,
+        heuristics: Heuristics::DEFAULT,
+        adaptive_layout: None,
+    };
+
     /// Sets the use hints flag
     pub fn use_hints(&mut self, use_hints: bool) {
         self.use_hints = use_hints;
     }
+    /// Sets the adaptive layout policy
+    ///
+    /// If set, granularities selected by the policy are converted to [DataLayout::Prefix]
+    /// while advancing the wheel. Only applies to aggregators with an exact inverse.
+    pub fn adaptive_layout(&mut self, layout: Option<AdaptiveLayout>) {
+        self.adaptive_layout = layout;
+    }
+}
+
+/// Policy for converting frequently scanned granularities to [DataLayout::Prefix]
+#[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum AdaptiveLayout {
+    /// Converts granularities flagged as access outliers (see [WheelAccess::outlier])
+    Outliers,
+    /// Converts granularities whose average scan count exceeds the threshold
+    AvgScans(f64),
+}
+
+impl AdaptiveLayout {
+    #[inline]
+    fn selects(&self, access: &WheelAccess) -> bool {
+        match self {
+            Self::Outliers => access.outlier,
+            Self::AvgScans(threshold) => access.avg_scans() > *threshold,
+        }
+    }
+}
+
+impl From<f64> for AdaptiveLayout {
+    fn from(threshold: f64) -> Self {
+        Self::AvgScans(threshold)
+    }
+}
+
+/// Aggregator tag embedded in a serialized [Haw]
+///
+/// Serializes as [Aggregator::qualified_tag] and fails deserialization if the stored tag
+/// does not match the aggregator of the wheel being deserialized.
+struct AggregatorTag<A>(core::marker::PhantomData<A>);
+
+impl<A> Default for AggregatorTag<A> {
+    fn default() -> Self {
+        Self(core::marker::PhantomData)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<A: Aggregator> serde::Serialize for AggregatorTag<A> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&A::qualified_tag())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, A: Aggregator> serde::Deserialize<'de> for AggregatorTag<A> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TagVisitor<A>(core::marker::PhantomData<A>);
+
+        impl<A: Aggregator> serde::de::Visitor<'_> for TagVisitor<A> {
+            type Value = AggregatorTag<A>;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "aggregator tag {:?}", A::qualified_tag())
+            }
+
+            fn visit_str<E: serde::de::Error>(self, tag: &str) -> Result<Self::Value, E> {
+                let expected = A::qualified_tag();
+                if tag == expected {
+                    Ok(AggregatorTag::default())
+                } else {
+                    Err(E::custom(format_args!(
+                        "aggregator mismatch: wheel was serialized with {tag:?} but deserialized as {expected:?}"
+                    )))
+                }
+            }
+        }
+
+        deserializer.deserialize_str(TagVisitor(core::marker::PhantomData))
+    }
 }
 
 /// Hierarchical Aggregate Wheel
@@ -420,8 +1366,16 @@ pub struct Haw<A>
 where
     A: Aggregator,
 {
+    /// Identifies the aggregator of the wheel when serialized
+    tag: AggregatorTag<A>,
     /// The current low watermark for this wheel
     watermark: u64,
+    /// An optional milliseconds wheel beneath the seconds wheel (see [HawConf::with_milliseconds])
+    #[cfg_attr(feature = "serde", serde(default))]
+    millis_wheel: Option<Wheel<A>>,
+    /// Ranges [start, end) of the milliseconds wheel filled with empty slots rather than fed by a writer
+    #[cfg_attr(feature = "serde", serde(default))]
+    unfed_millis: Vec<(u64, u64)>,
     /// A seconds wheel which may or may not be initialized
     seconds_wheel: MaybeWheel<A>,
     /// A minutes wheel which may or may not be initialized
@@ -443,12 +1397,36 @@ where
     #[cfg(feature = "timer")]
     #[cfg_attr(feature = "serde", serde(skip))]
     /// A hierarchical timing wheel for scheduling user-defined functions
+    ///
+    /// Not serialized since closures cannot be, see [Self::pending_timers] for re-registering after load.
     timer: TimerWheel<A>,
+    /// Access counters per granularity used to guide data layout decisions
+    #[cfg_attr(feature = "serde", serde(skip))]
+    access: AccessCounters,
+    /// Cached combine range results for the current watermark
+    #[cfg_attr(feature = "serde", serde(skip))]
+    cache: QueryCache<A::PartialAggregate>,
+    /// Days completed since the start of the current calendar year if configured with calendar years
+    #[cfg_attr(feature = "serde", serde(default))]
+    calendar_year: Option<WheelSlot<A>>,
+    /// An optional user-defined function invoked with slots evicted by the retention policy
+    ///
+    /// Not serialized since closures cannot be.
+    #[cfg_attr(feature = "serde", serde(skip))]
+    eviction_hook: Option<EvictionHook<A>>,
     #[cfg(feature = "profiler")]
     /// A profiler that records latencies of various Haw operations
     stats: Stats,
 }
 
+/// A user-defined function receiving the granularity, start timestamp, and partial aggregate of an evicted slot
+///
+/// Must be `Send + Sync` so that registering a hook keeps [Haw] `Send + Sync`. See [Haw::on_evict] for more information.
+pub trait EvictionFn<P>: FnMut(Granularity, u64, P) + Send + Sync + 'static {}
+impl<P, F: FnMut(Granularity, u64, P) + Send + Sync + 'static> EvictionFn<P> for F {}
+
+type EvictionHook<A> = Box<dyn EvictionFn<<A as Aggregator>::PartialAggregate>>;
+
 impl<A: Aggregator> Default for Haw<A> {
     fn default() -> Self {
         Self::new(Default::default())
@@ -477,8 +1455,27 @@ where
 
     /// Creates a new Wheel from the given configuration
     pub fn new(conf: HawConf) -> Self {
+        // calendar years are aligned to UTC days and thus to the unix epoch rather than the epoch of the wheel
+        assert!(
+            !conf.calendar_years
+                || conf
+                    .watermark
+                    .checked_add(conf.epoch_offset)
+                    .is_some_and(|unix_ms| unix_ms.is_multiple_of(DAY_TICK_MS)),
+            "Calendar years require a watermark aligned to a UTC day"
+        );
+        assert!(
+            conf.milliseconds
+                .is_none_or(|millis| millis.tick_size_ms == MILLISECOND_TICK_MS),
+            "The milliseconds wheel requires a tick size of MILLISECOND_TICK_MS"
+        );
         Self {
+            tag: AggregatorTag::default(),
             watermark: conf.watermark,
+            millis_wheel: conf
+                .milliseconds
+                .map(|millis| Wheel::new(millis.with_watermark(conf.watermark))),
+            unfed_millis: Vec::new(),
             seconds_wheel: MaybeWheel::new(conf.seconds),
             minutes_wheel: MaybeWheel::new(conf.minutes),
             hours_wheel: MaybeWheel::new(conf.hours),
@@ -490,6 +1487,10 @@ where
             window_manager: None,
             #[cfg(feature = "timer")]
             timer: TimerWheel::new(RawTimerWheel::default()),
+            access: AccessCounters::default(),
+            cache: QueryCache::default(),
+            calendar_year: None,
+            eviction_hook: None,
             #[cfg(feature = "profiler")]
             stats: Stats::default(),
         }
@@ -526,11 +1527,15 @@ where
     }
     // Returns the current low watermark as [OffsetDateTime]
     #[inline]
-    fn now(&self) -> OffsetDateTime {
-        Self::to_offset_date(self.watermark)
+    fn now(&self) -> Result<OffsetDateTime, QueryError> {
+        self.to_offset_date(self.watermark)
     }
     /// Returns memory used in bytes for all levels
     pub fn size_bytes(&self) -> usize {
+        let millis = self
+            .millis_wheel
+            .as_ref()
+            .map_or(0, |wheel| wheel.size_bytesz().unwrap());
         let secs = self.seconds_wheel.size_bytes();
         let min = self.minutes_wheel.size_bytes();
         let hr = self.hours_wheel.size_bytes();
@@ -538,17 +1543,65 @@ where
         let week = self.weeks_wheel.size_bytes();
         let year = self.years_wheel.size_bytes();
 
-        secs + min + hr + day + week + year
+        millis + secs + min + hr + day + week + year
+    }
+
+    /// Returns the memory used per granularity split into slots and prefix-sum arrays
+    ///
+    /// The total of the breakdown equals [Self::size_bytes].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), Some(2)]);
+    ///
+    /// let breakdown = haw.size_breakdown();
+    /// assert_eq!(breakdown.total(), haw.size_bytes());
+    /// assert!(breakdown.level(Granularity::Second).slot_bytes > 0);
+    /// assert!(breakdown.level(Granularity::Day).layout.is_none());
+    /// ```
+    pub fn size_breakdown(&self) -> SizeBreakdown {
+        let level = |wheel: Option<&Wheel<A>>| {
+            wheel
+                .map(|wheel| {
+                    let (slot_bytes, prefix_bytes) = wheel.data_size_bytes();
+                    LevelSize {
+                        layout: Some(wheel.data_layout()),
+                        overhead_bytes: mem::size_of::<Wheel<A>>(),
+                        slot_bytes,
+                        prefix_bytes,
+                        occupancy_bytes: wheel.occupancy_size_bytes(),
+                    }
+                })
+                .unwrap_or_default()
+        };
+        SizeBreakdown {
+            milliseconds: level(self.millis_wheel.as_ref()),
+            seconds: level(self.wheel(Granularity::Second)),
+            minutes: level(self.wheel(Granularity::Minute)),
+            hours: level(self.wheel(Granularity::Hour)),
+            days: level(self.wheel(Granularity::Day)),
+            weeks: level(self.wheel(Granularity::Week)),
+            years: level(self.wheel(Granularity::Year)),
+        }
     }
 
     /// Returns how many ticks (seconds) are left until the wheel is fully utilised
     pub fn remaining_ticks(&self) -> u64 {
-        Self::TOTAL_SECS_IN_WHEEL - self.current_time_in_cycle().whole_seconds() as u64
+        Self::TOTAL_SECS_IN_WHEEL
+            .saturating_sub(self.current_time_in_cycle().whole_seconds() as u64)
     }
 
     /// Returns Duration that represents where the wheel currently is in its cycle
     #[inline]
     pub fn current_time_in_cycle(&self) -> Duration {
+        if self.conf.calendar_years {
+            let cycle_ms = self.watermark.saturating_sub(self.calendar_cycle_start());
+            return Duration::milliseconds(cycle_ms as i64);
+        }
         let secs = self.seconds_wheel.rotation_count() as u64;
         let min_secs = self.minutes_wheel.rotation_count() as u64 * Self::MINUTES_AS_SECS;
         let hr_secs = self.hours_wheel.rotation_count() as u64 * Self::HOURS_AS_SECS;
@@ -560,12 +1613,70 @@ where
     }
 
     #[inline]
-    const fn to_ms(ts: u64) -> u64 {
-        ts.saturating_mul(1000)
+    const fn secs_to_ms(secs: u64) -> u64 {
+        secs.saturating_mul(1000)
+    }
+    // Converts a unix timestamp in seconds to a timestamp in milliseconds relative to the epoch of the wheel
+    #[inline]
+    const fn to_ms(&self, unix_secs: u64) -> u64 {
+        Self::secs_to_ms(unix_secs).saturating_sub(self.conf.epoch_offset)
+    }
+    // Converts a timestamp in milliseconds relative to the epoch of the wheel to a date
+    #[inline]
+    fn to_offset_date(&self, ts: u64) -> Result<OffsetDateTime, QueryError> {
+        ts.checked_add(self.conf.epoch_offset)
+            .and_then(|unix_ms| i64::try_from(unix_ms / 1000).ok())
+            .and_then(|secs| OffsetDateTime::from_unix_timestamp(secs).ok())
+            .ok_or(QueryError::InvalidTimestamp { ts_ms: ts })
+    }
+    // Shifts a date by the epoch offset so that it lines up with the watermarks of the individual wheels
+    #[inline]
+    fn wheel_date(&self, date: OffsetDateTime) -> OffsetDateTime {
+        date - time::Duration::milliseconds(self.conf.epoch_offset as i64)
+    }
+    // Returns the start of the oldest calendar year within the current rotation of the years wheel
+    fn calendar_cycle_start(&self) -> u64 {
+        let year = self
+            .to_offset_date(self.watermark)
+            .map_or(1970, |date| date.year());
+        let years = self.years_wheel.rotation_count() as i32;
+        cmp::max(self.year_start(year - years), self.conf.watermark)
+    }
+
+    // Returns a timestamp that the slots of the weeks wheel are aligned to
+    #[inline]
+    fn week_origin(&self) -> u64 {
+        if self.conf.calendar_years {
+            // the weeks wheel keeps rotating every 52 weeks and is thus not aligned to the start of the cycle
+            let watermark = self
+                .weeks_wheel
+                .as_ref()
+                .map_or(self.weeks_wheel.conf().watermark, |wheel| wheel.watermark());
+            watermark % WEEK_TICK_MS
+        } else {
+            self.wheel_start()
+        }
     }
+
+    // Returns the start time of the current wheel cycle in milliseconds
     #[inline]
-    fn to_offset_date(ts: u64) -> OffsetDateTime {
-        OffsetDateTime::from_unix_timestamp(ts as i64 / 1000).unwrap()
+    fn wheel_start(&self) -> u64 {
+        self.watermark()
+            .saturating_sub(self.current_time_in_cycle().whole_milliseconds() as u64)
+    }
+
+    /// Eagerly allocates the wheels of all granularities
+    ///
+    /// Wheels are otherwise created lazily once data reaches their granularity, which causes allocations
+    /// while ticking. After calling this function, ticking wheels with [DataLayout::Normal] and a retention
+    /// policy that drops data performs no heap allocations.
+    pub fn reserve(&mut self) {
+        self.seconds_wheel.get_or_insert();
+        self.minutes_wheel.get_or_insert();
+        self.hours_wheel.get_or_insert();
+        self.days_wheel.get_or_insert();
+        self.weeks_wheel.get_or_insert();
+        self.years_wheel.get_or_insert();
     }
 
     /// Converts all wheels to be prefix-enabled
@@ -636,10 +1747,32 @@ where
     }
 
     /// Installs a periodic window aggregation query
+    ///
+    /// Sliding windows buffer a bounded number of pairs, see [Window::sliding] for the memory bound.
     pub fn window(&mut self, window: Window) {
         self.window_manager = Some(WindowManager::new(self.watermark, window));
     }
 
+    /// Installs a periodic window aggregation query aligned to the local calendar time of the given offset
+    ///
+    /// Windows start at local boundaries of the slide (the range for tumbling windows) rather than at the
+    /// current watermark, e.g., a daily tumbling window fires at local midnight. Session windows are not affected.
+    ///
+    /// The offset is fixed, so daylight saving time transitions are not taken into account: daily windows
+    /// always span 24 hours and fire one hour off local midnight while daylight saving time differs from the offset.
+    /// Install a new window with the updated offset to follow such transitions.
+    ///
+    /// Note that windows which are not aligned to the slots of a coarser granularity are answered by a finer one,
+    /// so a daily window at a non-zero offset requires the hours wheel to retain at least a day of data
+    /// (see [RetentionPolicy::KeepWithLimit]).
+    pub fn window_with_offset(&mut self, window: Window, offset: UtcOffset) {
+        self.window_manager = Some(WindowManager::with_offset(
+            self.watermark,
+            window,
+            offset.whole_seconds() as i64,
+        ));
+    }
+
     /// Advances the time of the wheel aligned by the lowest unit (Second)
     #[inline]
     pub(crate) fn advance_to(
@@ -651,6 +1784,46 @@ where
         self.advance(Duration::milliseconds(diff as i64), waw)
     }
 
+    /// Advances the time of the wheel to the given watermark while reporting every sealed second
+    ///
+    /// `on_tick` is invoked once per tick with the start timestamp of the sealed second and its frozen
+    /// partial aggregate, or [Aggregator::IDENTITY] if the second received no data.
+    /// If the advance exceeds [Self::CYCLE_LENGTH], all wheels are cleared and `on_tick` is never invoked.
+    ///
+    /// # Example
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U64SumAggregator, wheels::WriterWheel, Entry};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::default();
+    /// waw.insert(Entry::new(10, 1000));
+    ///
+    /// let mut sealed = Vec::new();
+    /// haw.advance_to_with(3000, &mut waw, |ts, partial| sealed.push((ts, partial)));
+    /// assert_eq!(sealed, vec![(0, 0), (1000, 10), (2000, 0)]);
+    /// ```
+    pub fn advance_to_with(
+        &mut self,
+        watermark: u64,
+        waw: &mut WriterWheel<A>,
+        mut on_tick: impl FnMut(u64, A::PartialAggregate),
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        let ticks = watermark.saturating_sub(self.watermark) / Self::SECOND_AS_MS;
+        if ticks > Self::CYCLE_LENGTH_SECS {
+            return self.advance_to(watermark, waw);
+        }
+
+        let mut windows = Vec::new();
+        for _ in 0..ticks {
+            let start = self.watermark;
+            let delta = self.advance_tick(waw, &mut windows);
+            on_tick(start, delta.unwrap_or(A::IDENTITY));
+        }
+        self.adapt_layout_maybe();
+
+        windows
+    }
+
     /// Advances the wheel by applying a set of partial aggregate deltas where each delta represents the lowest unit of time
     ///
     /// Note that deltas are processed in the order of the iterator. If you have the following deltas
@@ -695,6 +1868,91 @@ where
         windows
     }
 
+    /// Seeds the wheel of the given granularity with pre-aggregated partials that end at `watermark`
+    ///
+    /// Partials are ordered from oldest to newest and inserted directly into the slots of the granularity wheel,
+    /// bypassing the ticks of the lower granularities which are left empty. Full rotations are still rolled up
+    /// into the coarser wheels. Any existing state and deltas of the wheel are discarded.
+    ///
+    /// Note that `watermark` should be aligned to the given granularity. Returns an error and leaves the wheel
+    /// untouched if the partials would start before the epoch of the wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, WheelRange, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// // three minutes of partials ending at 00:03:00
+    /// haw.seed_granularity(Granularity::Minute, &[Some(10), None, Some(30)], 180000).unwrap();
+    ///
+    /// assert_eq!(haw.watermark(), 180000);
+    /// assert_eq!(haw.combine_range(WheelRange::new_unchecked(0, 180000)), Some(40));
+    /// assert_eq!(haw.combine_range(WheelRange::new_unchecked(60000, 61000)), None);
+    /// ```
+    pub fn seed_granularity(
+        &mut self,
+        granularity: Granularity,
+        partials: &[Option<A::PartialAggregate>],
+        watermark: u64,
+    ) -> Result<(), SeedError> {
+        let granularities = [
+            Granularity::Second,
+            Granularity::Minute,
+            Granularity::Hour,
+            Granularity::Day,
+            Granularity::Week,
+            Granularity::Year,
+        ];
+        let mut wheels = [
+            &mut self.seconds_wheel,
+            &mut self.minutes_wheel,
+            &mut self.hours_wheel,
+            &mut self.days_wheel,
+            &mut self.weeks_wheel,
+            &mut self.years_wheel,
+        ];
+        let seeded = granularity as usize;
+        let tick_size_ms = wheels[seeded].conf().tick_size_ms;
+        let start = (partials.len() as u64)
+            .checked_mul(tick_size_ms)
+            .and_then(|span| watermark.checked_sub(span))
+            .ok_or(SeedError::StartBeforeEpoch {
+                partials: partials.len(),
+                watermark,
+            })?;
+
+        // lower granularities start empty at the watermark while the seeded and coarser wheels tick up to it
+        for (level, wheel) in wheels.iter_mut().enumerate() {
+            let mut conf = *wheel.conf();
+            conf.set_watermark(if level < seeded { watermark } else { start });
+            **wheel = MaybeWheel::new(conf);
+        }
+        if let Some(millis) = self.millis_wheel.as_mut() {
+            millis.clear();
+            millis.set_watermark(watermark);
+        }
+        self.unfed_millis.clear();
+
+        for partial in partials {
+            let mut slot = WheelSlot::new(*partial);
+            for (level, wheel) in wheels.iter_mut().enumerate().skip(seeded) {
+                let wheel = wheel.get_or_insert();
+                wheel.insert_slot(slot);
+                match (wheel.tick(), granularities.get(level + 1)) {
+                    (Some(rot_data), Some(next)) => slot = rot_data.rollup(*next),
+                    _ => break,
+                }
+            }
+        }
+
+        self.watermark = watermark;
+        self.delta = DeltaState::new(watermark, Vec::new());
+        self.calendar_year = None;
+        self.cache.clear();
+        Ok(())
+    }
+
     /// Advance the watermark of the wheel by the given [Duration]
     #[inline(always)]
     pub fn advance(
@@ -702,30 +1960,172 @@ where
         duration: Duration,
         waw: &mut WriterWheel<A>,
     ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        self.advance_checked(duration, waw).unwrap_or_default()
+    }
+
+    /// Advances the time of the wheel aligned by the lowest unit (Second)
+    ///
+    /// Returns an error if the duration exceeds [Self::CYCLE_LENGTH] in which case all wheels have been cleared.
+    pub fn advance_checked(
+        &mut self,
+        duration: Duration,
+        waw: &mut WriterWheel<A>,
+    ) -> Result<Vec<WindowAggregate<A::PartialAggregate>>, CycleExceeded> {
         let ticks: usize = duration.whole_seconds() as usize;
         let mut windows = Vec::new();
 
         if ticks <= Self::CYCLE_LENGTH_SECS as usize {
             for _ in 0..ticks {
-                // tick the write wheel and freeze mutable aggregate
-                let delta = waw.tick().map(A::freeze);
+                self.advance_tick(waw, &mut windows);
+            }
+        } else {
+            // Exceeds full cycle length, clear all!
+            self.clear();
+            return Err(CycleExceeded {
+                ticks: ticks as u64,
+            });
+        }
 
-                // Store delta if configured to
-                if self.conf.generate_deltas {
-                    self.delta.push(delta);
-                }
+        self.adapt_layout_maybe();
 
-                // Tick the HAW
-                self.tick(delta);
+        Ok(windows)
+    }
 
-                // maybe handle window if there is any configured
-                self.handle_window_maybe(delta, &mut windows);
-            }
+    /// Advances the time of the wheel by the given duration lazily
+    ///
+    /// Returns an iterator that ticks the wheel on demand and yields window aggregates as they fire,
+    /// which avoids buffering all windows of large advances (e.g., backfills). Ticks are only performed
+    /// while the iterator is consumed, so dropping it early leaves the wheel at the last tick that was performed.
+    ///
+    /// Like [Self::advance], a duration exceeding [Self::CYCLE_LENGTH] clears all wheels and yields nothing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, Window, NumericalDuration, aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let mut waw: WriterWheel<U32SumAggregator> = WriterWheel::default();
+    /// haw.window(Window::tumbling(2.seconds()));
+    ///
+    /// assert_eq!(haw.advance_iter(10.seconds(), &mut waw).count(), 5);
+    /// assert_eq!(haw.watermark(), 10000);
+    /// ```
+    pub fn advance_iter<'a>(
+        &'a mut self,
+        duration: Duration,
+        waw: &'a mut WriterWheel<A>,
+    ) -> AdvanceIter<'a, A> {
+        let ticks = duration.whole_seconds() as u64;
+        let remaining = if ticks <= Self::CYCLE_LENGTH_SECS {
+            ticks as usize
         } else {
             // Exceeds full cycle length, clear all!
             self.clear();
+            0
+        };
+        AdvanceIter {
+            haw: self,
+            waw,
+            remaining,
+            pending: Vec::new(),
+        }
+    }
+
+    /// Advances the time of the wheel towards the given watermark by performing at most `steps` ticks
+    ///
+    /// Returns the window aggregates fired by the performed ticks together with the number of ticks
+    /// that remain until the watermark is reached. Enables callers to split large advances (e.g., replaying
+    /// years of data) into chunks and interleave them with other work or yield to an async runtime.
+    ///
+    /// Unlike [Self::advance], the wheels are never cleared as the ticks are always performed one by one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let mut waw: WriterWheel<U32SumAggregator> = WriterWheel::default();
+    ///
+    /// let (_windows, remaining) = haw.advance_steps(10000, 4, &mut waw);
+    /// assert_eq!(remaining, 6);
+    /// assert_eq!(haw.watermark(), 4000);
+    /// ```
+    pub fn advance_steps(
+        &mut self,
+        watermark: u64,
+        steps: usize,
+        waw: &mut WriterWheel<A>,
+    ) -> (Vec<WindowAggregate<A::PartialAggregate>>, usize) {
+        let ticks = (watermark.saturating_sub(self.watermark) / Self::SECOND_AS_MS) as usize;
+        let steps = cmp::min(ticks, steps);
+        let mut windows = Vec::new();
+
+        for _ in 0..steps {
+            self.advance_tick(waw, &mut windows);
+        }
+        self.adapt_layout_maybe();
+
+        (windows, ticks - steps)
+    }
+
+    // Performs a single tick of the wheel pushing any fired windows into `windows`
+    #[inline]
+    fn advance_tick(
+        &mut self,
+        waw: &mut WriterWheel<A>,
+        windows: &mut Vec<WindowAggregate<A::PartialAggregate>>,
+    ) -> Option<A::PartialAggregate> {
+        // tick the write wheel until the next second is sealed and freeze mutable aggregates
+        let feeds_millis = waw.tick_size_ms() == MILLISECOND_TICK_MS;
+        let mut delta = None;
+        for _ in 0..SECOND_TICK_MS / waw.tick_size_ms() {
+            let partial = waw.tick();
+            if let Some(millis) = self.millis_wheel.as_mut().filter(|_| feeds_millis) {
+                millis.insert_slot(WheelSlot::new(partial));
+                let _ = millis.tick();
+            }
+            if let Some(partial) = partial {
+                combine_or_insert::<A>(&mut delta, partial);
+            }
+        }
+
+        // Store delta if configured to
+        if self.conf.generate_deltas {
+            self.delta.push(delta);
+        }
+
+        // Tick the HAW
+        self.tick(delta);
+
+        // maybe handle window if there is any configured
+        self.handle_window_maybe(delta, windows);
+
+        delta
+    }
+
+    #[inline]
+    fn adapt_layout_maybe(&mut self) {
+        if let Some(layout) = self.conf.optimizer.adaptive_layout {
+            self.adapt_layout(layout);
         }
-        windows
+    }
+
+    /// Advances the time of the wheel by at most [Self::CYCLE_LENGTH]
+    ///
+    /// Unlike [Self::advance], a duration exceeding the cycle length saturates instead of clearing all wheels.
+    ///
+    /// Note that the wheel still ticks once per second so that windows, deltas and scheduled writes observe every tick.
+    /// A saturated advance thus performs a full cycle of ticks (over 3.4×10^8 with the default wheel sizes),
+    /// so prefer [Self::advance] when the retained data may simply be cleared.
+    pub fn advance_saturating(
+        &mut self,
+        duration: Duration,
+        waw: &mut WriterWheel<A>,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        let max = Duration::seconds(Self::CYCLE_LENGTH_SECS as i64);
+        self.advance(cmp::min(duration, max), waw)
     }
 
     // internal function to handle installed window queries
@@ -794,18 +2194,21 @@ where
             // if we have no more pairs to process
             if pairs_remaining == 0 {
                 // query the pair range
-                let from = Self::to_offset_date(self.watermark - current_pair_len as u64);
-                let to = Self::to_offset_date(self.watermark);
-                let pair = self.combine_range(WheelRange {
-                    start: from,
-                    end: to,
-                });
+                let from = self.to_offset_date(self.watermark - current_pair_len as u64);
+                let to = self.to_offset_date(self.watermark);
+                let pair = match (from, to) {
+                    (Ok(start), Ok(end)) => self.combine_range(WheelRange { start, end }),
+                    _ => None,
+                };
 
                 // SAFETY: safe to unwrap at this point
                 let manager = self.window_manager.as_mut().unwrap();
                 let (ref mut state, ref mut aggregator) = manager.aggregator.slicing_as_mut();
 
-                // insert pair into window aggregator
+                // evict the oldest pair if the buffer is full and insert the new pair into window aggregator
+                if aggregator.len() >= state.max_pairs {
+                    aggregator.pop();
+                }
                 aggregator.push(pair.unwrap_or(A::IDENTITY));
 
                 // Update pair metadata
@@ -832,16 +2235,49 @@ where
         }
     }
 
-    /// Clears the state of all wheels
+    /// Registers a hook that is invoked with every slot evicted by the retention policy of a wheel
     ///
-    /// Use with caution as this operation cannot be reversed.
-    pub fn clear(&mut self) {
-        self.seconds_wheel.clear();
-        self.minutes_wheel.clear();
-        self.hours_wheel.clear();
+    /// The hook receives the granularity of the wheel together with the start timestamp and partial aggregate
+    /// of the slot right before it is dropped, allowing evicted data to be archived elsewhere (e.g., cold storage).
+    /// Slots are only evicted by wheels configured with [RetentionPolicy::Drop] or [RetentionPolicy::KeepWithLimit].
+    /// Any previously registered hook is replaced.
+    ///
+    /// Note that the hook is not serialized together with the wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.on_evict(|granularity, ts, partial| println!("{granularity:?} {ts}: {partial}"));
+    /// haw.delta_advance(vec![Some(1); 120]);
+    /// ```
+    pub fn on_evict(&mut self, hook: impl EvictionFn<A::PartialAggregate>) {
+        self.eviction_hook = Some(Box::new(hook));
+    }
+
+    /// Removes a previously registered eviction hook
+    pub fn clear_evict_hook(&mut self) {
+        self.eviction_hook = None;
+    }
+
+    /// Clears the state of all wheels
+    ///
+    /// Use with caution as this operation cannot be reversed.
+    pub fn clear(&mut self) {
+        if let Some(millis) = self.millis_wheel.as_mut() {
+            millis.clear();
+        }
+        self.unfed_millis.clear();
+        self.seconds_wheel.clear();
+        self.minutes_wheel.clear();
+        self.hours_wheel.clear();
         self.days_wheel.clear();
         self.weeks_wheel.clear();
         self.years_wheel.clear();
+        self.calendar_year = None;
+        self.cache.clear();
     }
 
     /// Return the current watermark as milliseconds for this wheel
@@ -850,6 +2286,237 @@ where
         self.watermark
     }
 
+    /// Returns the timestamp in milliseconds of the oldest data point the wheel can still answer queries for
+    ///
+    /// Computed as the start of the oldest retained slot across all populated granularities, which respects
+    /// the retention policy of each wheel. Queries for ranges ending at or before this timestamp return `None`,
+    /// while [Self::combine_range] clamps ranges that start before it to only cover the data from this timestamp onwards.
+    /// Returns `None` if the wheel has not been advanced yet.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator, NumericalDuration};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::new(Default::default());
+    /// assert_eq!(haw.oldest_timestamp(), None);
+    ///
+    /// haw.delta_advance([Some(1), Some(2), Some(3)]);
+    /// assert_eq!(haw.oldest_timestamp(), Some(0));
+    /// ```
+    pub fn oldest_timestamp(&self) -> Option<u64> {
+        let oldest_year = self
+            .years_wheel
+            .as_ref()
+            .filter(|wheel| !wheel.is_empty())
+            .map(|wheel| {
+                if self.conf.calendar_years {
+                    // calendar years differ in length so the oldest slot starts on January 1
+                    let year = self
+                        .to_offset_date(wheel.watermark())
+                        .map_or(1970, |d| d.year());
+                    cmp::max(
+                        self.year_start(year - wheel.len() as i32),
+                        self.conf.watermark,
+                    )
+                } else {
+                    wheel.slot_timestamp(wheel.len() - 1)
+                }
+            });
+
+        [
+            &self.seconds_wheel,
+            &self.minutes_wheel,
+            &self.hours_wheel,
+            &self.days_wheel,
+            &self.weeks_wheel,
+        ]
+        .into_iter()
+        .filter_map(|wheel| wheel.as_ref())
+        .filter(|wheel| !wheel.is_empty())
+        .map(|wheel| wheel.slot_timestamp(wheel.len() - 1))
+        .chain(oldest_year)
+        .min()
+    }
+
+    /// Stitches an `older` wheel in front of this wheel to answer ranges spanning both
+    ///
+    /// The older wheel must cover the time up to the oldest data retained by this wheel
+    /// (see [Self::oldest_timestamp]), which holds when this wheel was started at the watermark
+    /// the older wheel was advanced to. Overlapping data is answered by this wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, HawConf, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut older: Haw<U32SumAggregator> = Haw::default();
+    /// older.delta_advance(vec![Some(1); 60]);
+    ///
+    /// let mut recent: Haw<U32SumAggregator> = Haw::new(HawConf::default().with_watermark(60000));
+    /// recent.delta_advance(vec![Some(2); 60]);
+    ///
+    /// let history = recent.concat_history(&older).unwrap();
+    /// let range = WheelRange::new_unchecked(50000, 70000);
+    /// assert_eq!(history.combine_range(range), Some(10 + 20));
+    /// ```
+    pub fn concat_history<'a>(
+        &'a self,
+        older: &'a Haw<A>,
+    ) -> Result<HistoryView<'a, A>, ConcatError> {
+        let oldest = self.oldest_timestamp().ok_or(ConcatError::Empty)?;
+        if older.watermark() < oldest {
+            return Err(ConcatError::Gap {
+                older_watermark: older.watermark(),
+                oldest,
+            });
+        }
+        Ok(HistoryView {
+            older,
+            recent: self,
+            boundary: oldest,
+        })
+    }
+
+    /// Returns the partial aggregates appended to this wheel since the given `base` snapshot
+    ///
+    /// The base must be a prior snapshot of this wheel (e.g., a replica that was last synced from it).
+    /// Only slots appended to each granularity since then are encoded, which allows keeping a replica
+    /// in sync without shipping a full snapshot. Windows and timers are not part of the diff.
+    ///
+    /// Returns an error if the base has been advanced beyond this wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let mut replica: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 90]);
+    ///
+    /// let diff = haw.diff(&replica).unwrap();
+    /// replica.apply_diff(&diff).unwrap();
+    ///
+    /// assert_eq!(replica.watermark(), haw.watermark());
+    /// assert_eq!(replica.landmark(), Some(90));
+    /// ```
+    pub fn diff(&self, base: &Haw<A>) -> Result<WheelDiff<A>, DiffError> {
+        if base.watermark > self.watermark {
+            return Err(DiffError::BaseAhead {
+                base: base.watermark,
+                watermark: self.watermark,
+            });
+        }
+        let levels = [
+            Granularity::Second,
+            Granularity::Minute,
+            Granularity::Hour,
+            Granularity::Day,
+            Granularity::Week,
+            Granularity::Year,
+        ]
+        .map(|granularity| {
+            let wheel = self.wheel(granularity)?;
+            let ticks = self.ticks_since(base, granularity);
+            (ticks > 0).then(|| wheel.diff(ticks))
+        });
+        let millis = self.millis_wheel.as_ref().and_then(|millis| {
+            let base_watermark = base
+                .millis_wheel
+                .as_ref()
+                .map_or(base.watermark, Wheel::watermark);
+            let ticks = millis.watermark().saturating_sub(base_watermark) as usize;
+            (ticks > 0).then(|| millis.diff(ticks))
+        });
+
+        Ok(WheelDiff {
+            base_watermark: base.watermark,
+            watermark: self.watermark,
+            levels,
+            millis,
+            unfed_millis: self.unfed_millis.clone(),
+            calendar_year: self.calendar_year.clone(),
+            deltas: self
+                .delta
+                .deltas
+                .iter()
+                .skip(base.delta.deltas.len())
+                .cloned()
+                .collect(),
+        })
+    }
+
+    /// Applies a diff taken through [Self::diff] against a snapshot at the watermark of this wheel
+    ///
+    /// After applying the diff, the wheel answers queries the same way as the wheel the diff was taken from.
+    ///
+    /// Returns an error if the diff was taken against a base at a different watermark.
+    pub fn apply_diff(&mut self, diff: &WheelDiff<A>) -> Result<(), DiffError> {
+        if diff.base_watermark != self.watermark {
+            return Err(DiffError::WatermarkMismatch {
+                expected: diff.base_watermark,
+                found: self.watermark,
+            });
+        }
+        for (level, wheel) in diff.levels.iter().zip([
+            &mut self.seconds_wheel,
+            &mut self.minutes_wheel,
+            &mut self.hours_wheel,
+            &mut self.days_wheel,
+            &mut self.weeks_wheel,
+            &mut self.years_wheel,
+        ]) {
+            if let Some(level) = level {
+                wheel.get_or_insert().apply_diff(level);
+            }
+        }
+        if let (Some(level), Some(millis)) = (diff.millis.as_ref(), self.millis_wheel.as_mut()) {
+            millis.apply_diff(level);
+        }
+        self.unfed_millis = diff.unfed_millis.clone();
+        self.watermark = diff.watermark;
+        self.calendar_year = diff.calendar_year.clone();
+        self.delta.deltas.extend(diff.deltas.iter().cloned());
+        self.cache.clear();
+        Ok(())
+    }
+
+    // Returns the number of ticks the wheel of the given granularity has advanced since the base snapshot
+    fn ticks_since(&self, base: &Haw<A>, granularity: Granularity) -> usize {
+        let Some(wheel) = self.wheel(granularity) else {
+            return 0;
+        };
+        let base_wheel = base.maybe_wheel(granularity);
+        let base_watermark = base_wheel
+            .as_ref()
+            .map_or(base_wheel.conf().watermark, Wheel::watermark);
+        if granularity == Granularity::Year && self.conf.calendar_years {
+            // calendar years differ in length so count the year boundaries instead
+            return match (
+                self.to_offset_date(wheel.watermark()),
+                self.to_offset_date(base_watermark),
+            ) {
+                (Ok(current), Ok(base)) => cmp::max(current.year() - base.year(), 0) as usize,
+                _ => 0,
+            };
+        }
+        let tick_size = base_wheel.conf().tick_size_ms;
+        (wheel.watermark().saturating_sub(base_watermark) / tick_size) as usize
+    }
+
+    // Returns the possibly uninitialized wheel of the given granularity
+    fn maybe_wheel(&self, granularity: Granularity) -> &MaybeWheel<A> {
+        match granularity {
+            Granularity::Second => &self.seconds_wheel,
+            Granularity::Minute => &self.minutes_wheel,
+            Granularity::Hour => &self.hours_wheel,
+            Granularity::Day => &self.days_wheel,
+            Granularity::Week => &self.weeks_wheel,
+            Granularity::Year => &self.years_wheel,
+        }
+    }
+
     /// Groups the data into aggregates based on the given range and interval
     ///
     /// Returns `None` if the range cannot be answered by the wheel
@@ -910,7 +2577,8 @@ where
         // Sanity check: return early with `None` if the range is invalid
         if start >= end
             || interval.whole_seconds() == 0
-            || Self::to_ms(start.unix_timestamp() as u64) > self.watermark
+            || self.to_ms(start.unix_timestamp() as u64) > self.watermark
+            || self.check_query_range(range).is_err()
         {
             return None;
         }
@@ -928,7 +2596,7 @@ where
                 end: next,
             };
             result.push((
-                Self::to_ms(step.unix_timestamp() as u64),
+                self.to_ms(step.unix_timestamp() as u64),
                 A::lower(self.combine_range(query_range).unwrap_or(A::IDENTITY)),
             ));
             step = next;
@@ -957,6 +2625,7 @@ where
     #[inline]
     pub fn range(&self, range: impl Into<WheelRange>) -> Option<Vec<(u64, A::PartialAggregate)>> {
         let range = range.into();
+        self.check_query_range(range).ok()?;
         let start = range.start;
         let end = range.end;
 
@@ -964,20 +2633,32 @@ where
             Granularity::Second => {
                 let seconds = (end - start).whole_seconds() as usize;
                 self.seconds_wheel
-                    .range(start, seconds, Granularity::Second)
+                    .range(self.wheel_date(start), seconds, Granularity::Second)
             }
             Granularity::Minute => {
                 let minutes = (end - start).whole_minutes() as usize;
                 self.minutes_wheel
-                    .range(start, minutes, Granularity::Minute)
+                    .range(self.wheel_date(start), minutes, Granularity::Minute)
             }
             Granularity::Hour => {
                 let hours = (end - start).whole_hours() as usize;
-                self.hours_wheel.range(start, hours, Granularity::Hour)
+                self.hours_wheel
+                    .range(self.wheel_date(start), hours, Granularity::Hour)
             }
             Granularity::Day => {
                 let days = (end - start).whole_days() as usize;
-                self.days_wheel.range(start, days, Granularity::Day)
+                self.days_wheel
+                    .range(self.wheel_date(start), days, Granularity::Day)
+            }
+            Granularity::Week => {
+                let weeks = (end - start).whole_weeks() as usize;
+                self.weeks_wheel
+                    .range(self.wheel_date(start), weeks, Granularity::Week)
+            }
+            Granularity::Year => {
+                let years = (end - start).whole_weeks() as usize / WEEKS;
+                self.years_wheel
+                    .range(self.wheel_date(start), years, Granularity::Year)
             }
         }
     }
@@ -1000,6 +2681,200 @@ where
         })
     }
 
+    /// Returns a dense series with an aggregate for every `bucket` of the given range without gaps
+    ///
+    /// Buckets start at the start of the range and the last bucket is cut off at its end. Unlike [Self::group_by]
+    /// and [Self::decimate], buckets without any inserted data (see [Self::combine_range_result]) as well as buckets
+    /// that cannot be answered by the wheel are filled with `fill` (e.g., [Aggregator::IDENTITY]), which keeps the
+    /// output aligned to timestamps for consumers such as models that expect evenly spaced samples.
+    ///
+    /// If a wheel whose slots evenly divide the bucket retains the whole range, its slots are read once and
+    /// combined per bucket. Otherwise, every bucket is answered through its own execution plan.
+    ///
+    /// Returns an empty series if the range is empty, exceeds the maximum query range or the bucket is not a positive
+    /// number of whole seconds (e.g., `1500ms`), since wheel slots are never shorter than a second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), None, None, Some(2)]);
+    ///
+    /// let series = haw.dense_series(WheelRange::new_unchecked(0, 4000), 1.seconds(), u32::MAX);
+    /// assert_eq!(series, vec![(0, 1), (1000, u32::MAX), (2000, u32::MAX), (3000, 2)]);
+    /// ```
+    pub fn dense_series(
+        &self,
+        range: impl Into<WheelRange>,
+        bucket: Duration,
+        fill: A::PartialAggregate,
+    ) -> Vec<(u64, A::Aggregate)> {
+        let range = range.into();
+        let start = self.to_ms(range.start.unix_timestamp() as u64);
+        let end = self.to_ms(range.end.unix_timestamp() as u64);
+        let bucket_ms = Self::secs_to_ms(bucket.whole_seconds().max(0) as u64);
+        if bucket_ms == 0
+            || bucket.subsec_nanoseconds() != 0
+            || start >= end
+            || self.check_query_range(range).is_err()
+        {
+            return Vec::new();
+        }
+
+        // prefer the coarsest wheel that retains the whole range in slots that evenly divide the bucket
+        let slots = [
+            Granularity::Day,
+            Granularity::Hour,
+            Granularity::Minute,
+            Granularity::Second,
+        ]
+        .into_iter()
+        .find_map(|granularity| {
+            let tick_size_ms = self.maybe_wheel(granularity).conf().tick_size_ms;
+            bucket_ms
+                .is_multiple_of(tick_size_ms)
+                .then(|| self.slots_within(granularity, start, end))
+                .flatten()
+                .map(|slots| (slots, (bucket_ms / tick_size_ms) as usize))
+        });
+
+        match slots {
+            Some((slots, slots_per_bucket)) => slots
+                .chunks(slots_per_bucket)
+                .zip((start..end).step_by(bucket_ms as usize))
+                .map(|(bucket, bucket_start)| {
+                    // buckets without any occupied slot are filled just like empty query results
+                    let partial = if bucket.iter().any(|(_, occupied)| *occupied) {
+                        bucket
+                            .iter()
+                            .fold(A::IDENTITY, |acc, (partial, _)| A::combine(acc, *partial))
+                    } else {
+                        fill
+                    };
+                    (bucket_start, A::lower(partial))
+                })
+                .collect(),
+            None => (start..end)
+                .step_by(bucket_ms as usize)
+                .map(|bucket_start| {
+                    let bucket_end = cmp::min(bucket_start + bucket_ms, end);
+                    let partial = self
+                        .occupied_range(WheelRange::new_unchecked(bucket_start, bucket_end))
+                        .unwrap_or(fill);
+                    (bucket_start, A::lower(partial))
+                })
+                .collect(),
+        }
+    }
+
+    // Returns the partial aggregate and occupancy of the slots of a granularity within [start, end) from oldest to newest
+    //
+    // Returns `None` unless the wheel retains every slot and the slots line up with both ends of the range.
+    fn slots_within(
+        &self,
+        granularity: Granularity,
+        start: u64,
+        end: u64,
+    ) -> Option<Vec<(A::PartialAggregate, bool)>> {
+        let wheel = self.wheel(granularity)?;
+        let tick_size_ms = self.maybe_wheel(granularity).conf().tick_size_ms;
+        let watermark = wheel.watermark();
+        if end > watermark
+            || !(watermark - start).is_multiple_of(tick_size_ms)
+            || !(end - start).is_multiple_of(tick_size_ms)
+        {
+            return None;
+        }
+        let newest = ((watermark - end) / tick_size_ms) as usize;
+        let oldest = ((watermark - start) / tick_size_ms) as usize;
+        if oldest > wheel.len() {
+            return None;
+        }
+        (newest..oldest)
+            .rev()
+            .map(|age| {
+                let partial = *wheel.at(age)?;
+                Some((partial, wheel.is_occupied(age).unwrap_or(false)))
+            })
+            .collect()
+    }
+
+    // Combines the range through a single forward execution plan and returns `None` if none of its slots are occupied
+    fn occupied_range(&self, range: WheelRange) -> Option<A::PartialAggregate> {
+        let plan = self.forward_exec_plan(range)?;
+        if self.contributing_slots(&plan) == 0 {
+            return None;
+        }
+        self.execute_plan(plan).0
+    }
+
+    /// Downsamples the slots of the given range into at most `points` buckets while preserving their extent
+    ///
+    /// Slots are read at the lowest granularity of the range (see [Self::range]) and split into buckets
+    /// of equal size from oldest to newest. Besides the combined aggregate, each bucket reports its smallest
+    /// and largest slot so that charts rendered from the buckets keep spikes that an average would smooth away.
+    ///
+    /// Returns `None` if `points` is `0` or the range cannot be answered by the wheel
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), Some(9), Some(2), Some(4)]);
+    ///
+    /// let points = haw.decimate(WheelRange::new_unchecked(0, 4000), 2).unwrap();
+    /// assert_eq!(points.len(), 2);
+    /// assert_eq!((points[0].aggregate, points[0].min, points[0].max), (10, 1, 9));
+    /// assert_eq!((points[1].aggregate, points[1].min, points[1].max), (6, 2, 4));
+    /// ```
+    pub fn decimate(
+        &self,
+        range: impl Into<WheelRange>,
+        points: usize,
+    ) -> Option<Vec<DecimatedPoint<A::PartialAggregate>>>
+    where
+        A::PartialAggregate: PartialOrd,
+    {
+        if points == 0 {
+            return None;
+        }
+        let slots = self.range(range)?;
+        let bucket_size = slots.len().div_ceil(points).max(1);
+
+        Some(
+            slots
+                .chunks(bucket_size)
+                .map(|bucket| {
+                    let (timestamp, first) = bucket[0];
+                    bucket.iter().skip(1).fold(
+                        DecimatedPoint {
+                            timestamp,
+                            slots: 1,
+                            aggregate: first,
+                            min: first,
+                            max: first,
+                        },
+                        |mut point, &(_, partial)| {
+                            point.slots += 1;
+                            point.aggregate = A::combine(point.aggregate, partial);
+                            if partial < point.min {
+                                point.min = partial;
+                            }
+                            if partial > point.max {
+                                point.max = partial;
+                            }
+                            point
+                        },
+                    )
+                })
+                .collect(),
+        )
+    }
+
     /// Returns the execution plan for a given combine range query
     ///
     /// # Example
@@ -1021,6 +2896,116 @@ where
         self.create_exec_plan(range.into())
     }
 
+    /// Returns every candidate execution plan for a given combine range query together with its cost
+    ///
+    /// Unlike [Self::explain_combine_range], the query optimizer does not stop early or pick a plan, which helps to
+    /// understand why a particular plan was chosen. Plans that are not applicable to the range are left out.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, wheels::read::PlanKind, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(10), None, Some(50), None]);
+    ///
+    /// let candidates = haw.plan_candidates(WheelRange::new_unchecked(0, 4000));
+    /// assert!(candidates.contains(&(PlanKind::LandmarkAggregation, 6)));
+    /// assert!(candidates.contains(&(PlanKind::WheelAggregation, 4)));
+    /// ```
+    pub fn plan_candidates(&self, range: impl Into<WheelRange>) -> Vec<(PlanKind, usize)> {
+        let mut range = range.into();
+        let mut candidates = Vec::new();
+        let wheel_start = self.wheel_start();
+        let Ok(start) = self.to_offset_date(wheel_start) else {
+            return candidates;
+        };
+        if range.start > range.end {
+            return candidates;
+        }
+        range.start = cmp::max(range.start, start);
+
+        let end_ms = self.to_ms(range.end.unix_timestamp() as u64);
+        let start_ms = self.to_ms(range.start.unix_timestamp() as u64);
+
+        let plans = [
+            (start_ms <= wheel_start && end_ms >= self.watermark())
+                .then_some(ExecutionPlan::LandmarkAggregation),
+            self.wheel_aggregation_plan(range)
+                .map(ExecutionPlan::WheelAggregation),
+            self.combined_plan(range, wheel_start)
+                .map(ExecutionPlan::CombinedAggregation),
+            A::exact_inverse()
+                .then(|| self.inverse_landmark_plan(range, wheel_start))
+                .flatten()
+                .map(ExecutionPlan::InverseLandmarkAggregation),
+        ];
+        candidates.extend(
+            plans
+                .into_iter()
+                .flatten()
+                .map(|plan| (plan.kind(), plan.cost())),
+        );
+        candidates
+    }
+
+    /// Splits the given date range [start, end) into the aligned sub-ranges a combined aggregation would use
+    ///
+    /// The sub-ranges are non-overlapping, ordered by time and tile the whole range, which allows them to be
+    /// executed separately (e.g., on different nodes) and combined afterwards. If the wheel cannot answer the
+    /// combined aggregation, the range is split along the calendar boundaries of each granularity.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 180]);
+    ///
+    /// let sub_ranges = haw.plan_sub_ranges(WheelRange::new_unchecked(30000, 150000));
+    /// assert_eq!(
+    ///     sub_ranges,
+    ///     vec![
+    ///         WheelRange::new_unchecked(30000, 60000),
+    ///         WheelRange::new_unchecked(60000, 120000),
+    ///         WheelRange::new_unchecked(120000, 150000),
+    ///     ]
+    /// );
+    /// ```
+    pub fn plan_sub_ranges(&self, range: impl Into<WheelRange>) -> Vec<WheelRange> {
+        let range = range.into();
+        match self.combined_plan(range, self.wheel_start()) {
+            Some(combined) => {
+                let mut ranges: Vec<WheelRange> =
+                    combined.aggregations.iter().map(|agg| agg.range).collect();
+                ranges.sort_unstable_by_key(|range| range.start);
+                ranges
+            }
+            None => Self::split_wheel_ranges(range).into_iter().collect(),
+        }
+    }
+
+    /// Returns `true` if the range can be answered in O(1) through a prefix-sum or landmark aggregation
+    ///
+    /// The execution plan is created but not executed. Returns `false` if the range cannot be answered by the wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(10), None, Some(50), None]);
+    ///
+    /// assert!(haw.is_fast_query(WheelRange::new_unchecked(0, 4000)));
+    /// ```
+    #[inline]
+    pub fn is_fast_query(&self, range: impl Into<WheelRange>) -> bool {
+        self.create_exec_plan(range.into())
+            .is_some_and(|plan| plan.is_prefix_or_landmark())
+    }
+
     /// Combines partial aggregates within the given date range and lowers it to a final aggregate
     ///
     /// Returns `None` if the range cannot be answered by the wheel
@@ -1062,731 +3047,4499 @@ where
         self.combine_range_inner(range).0
     }
 
-    /// Executes a combine range query and returns the result + cost (combine ops) of executing it
-    ///
-    /// Returns `None` if the range cannot be answered by the wheel
-    #[inline]
-    pub fn analyze_combine_range(
-        &self,
-        range: impl Into<WheelRange>,
-    ) -> (Option<A::PartialAggregate>, usize) {
-        self.combine_range_inner(range)
-    }
-
     /// Combines partial aggregates within the given date range [start, end) into a final partial aggregate
-    #[inline]
-    fn combine_range_inner(
+    ///
+    /// Unlike [Self::combine_range], returns an error if the range exceeds the maximum query range
+    /// (see [HawConf::with_max_query_range]) or if the watermark of the wheel cannot be represented as a date.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, HawConf, QueryError, WheelRange, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// let conf = HawConf::default().with_max_query_range(2.seconds());
+    /// let mut haw: Haw<U32SumAggregator> = Haw::new(conf);
+    /// haw.delta_advance([Some(1), Some(2), Some(3)]);
+    ///
+    /// assert_eq!(haw.try_combine_range(WheelRange::new_unchecked(1000, 3000)), Ok(Some(5)));
+    /// assert_eq!(
+    ///     haw.try_combine_range(WheelRange::new_unchecked(0, 3000)),
+    ///     Err(QueryError::RangeTooLarge { range_ms: 3000, max_ms: 2000 })
+    /// );
+    /// ```
+    pub fn try_combine_range(
         &self,
         range: impl Into<WheelRange>,
-    ) -> (Option<A::PartialAggregate>, usize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.combine_range);
-
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
         let range = range.into();
-        let WheelRange { start, end } = range;
+        self.now()?;
+        self.check_query_range(range)?;
+        Ok(self.combine_range(range))
+    }
 
-        // Return early if the range is invalid (Start exceeds End)
-        if start > end {
-            return (None, 0);
+    /// Combines the partial aggregates of the milliseconds within [start, end) given as unix timestamps in milliseconds
+    ///
+    /// Answered by the milliseconds wheel (see [HawConf::with_milliseconds]) which only retains the most recent
+    /// milliseconds. Unlike [Self::combine_range], the bounds are not rounded to whole seconds. Returns `None` if no
+    /// milliseconds wheel is configured, the range is invalid, it is not fully retained by the milliseconds wheel,
+    /// or it overlaps seconds that were sealed without a milliseconds writer (e.g., through [Self::delta_advance]).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Entry, Haw, HawConf, NumericalDuration, aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::new(HawConf::default().with_milliseconds(2000));
+    /// let mut waw: WriterWheel<U32SumAggregator> = WriterWheel::default().with_tick_size(1);
+    /// waw.insert(Entry::new(10, 100));
+    /// waw.insert(Entry::new(20, 900));
+    /// haw.advance(1.seconds(), &mut waw);
+    ///
+    /// assert_eq!(haw.combine_millis(100, 101), Some(10));
+    /// assert_eq!(haw.combine_millis(500, 1000), Some(20));
+    /// assert_eq!(haw.combine_millis(500, 1500), None);
+    /// ```
+    pub fn combine_millis(&self, start_ms: u64, end_ms: u64) -> Option<A::PartialAggregate> {
+        let millis = self.millis_wheel.as_ref()?;
+        let start = start_ms.checked_sub(self.conf.epoch_offset)?;
+        let end = end_ms.checked_sub(self.conf.epoch_offset)?;
+
+        // slots are ordered from the most recent millisecond up to the watermark of the milliseconds wheel
+        let watermark = millis.watermark();
+        if start >= end || end > watermark || watermark - start > millis.len() as u64 {
+            return None;
         }
-
-        // create the best possible execution plan and run it
-        match self.create_exec_plan(range) {
-            Some(ExecutionPlan::WheelAggregation(wheel_agg)) => {
-                (self.wheel_aggregation(wheel_agg), wheel_agg.cost())
-            }
-            Some(ExecutionPlan::CombinedAggregation(combined)) => {
-                self.combined_aggregation(combined)
-            }
-            Some(ExecutionPlan::LandmarkAggregation) => self.analyze_landmark(),
-            Some(ExecutionPlan::InverseLandmarkAggregation(wheel_aggs)) => {
-                let (result, cost) = self.inverse_landmark_aggregation(wheel_aggs);
-                (Some(result), cost)
-            }
-            None => (None, 0), // No execution plan possible
+        if self
+            .unfed_millis
+            .iter()
+            .any(|&(unfed_start, unfed_end)| unfed_start < end && start < unfed_end)
+        {
+            return None;
         }
+        millis.combine_range((watermark - end) as usize..(watermark - start) as usize)
     }
-    /// Returns the best possible execution plan for a given wheel range
-    ///
-    /// Returns `None` if no plan can be established.
-    #[inline]
-    fn create_exec_plan(&self, mut range: WheelRange) -> Option<ExecutionPlan> {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.exec_plan);
-
-        let mut best_plan: Option<ExecutionPlan> = None;
-
-        let wheel_start = self
-            .watermark()
-            .saturating_sub(self.current_time_in_cycle().whole_milliseconds() as u64);
 
-        // SAFETY: ensure start range is not lower than the start of the wheel time
-        range.start = cmp::max(range.start, Self::to_offset_date(wheel_start));
+    // Records that the milliseconds in [start, end) were filled with empty slots
+    fn mark_unfed_millis(&mut self, start: u64, end: u64) {
+        match self.unfed_millis.last_mut() {
+            Some(last) if last.1 == start => last.1 = end,
+            _ => self.unfed_millis.push((start, end)),
+        }
+        self.prune_unfed_millis();
+    }
 
-        let end_ms = Self::to_ms(range.end.unix_timestamp() as u64);
-        let start_ms = Self::to_ms(range.start.unix_timestamp() as u64);
+    // Drops unfed ranges that are no longer retained by the milliseconds wheel
+    fn prune_unfed_millis(&mut self) {
+        if let Some(millis) = self.millis_wheel.as_ref() {
+            let oldest = millis.watermark().saturating_sub(millis.len() as u64);
+            self.unfed_millis.retain(|&(_, end)| end > oldest);
+        }
+    }
 
-        // Landmark optimization: landmark covers the whole range
-        if start_ms <= wheel_start && end_ms >= self.watermark() {
-            return Some(ExecutionPlan::LandmarkAggregation);
+    // Returns an error if the range is longer than the configured maximum query range
+    #[inline]
+    fn check_query_range(&self, range: WheelRange) -> Result<(), QueryError> {
+        match self.conf.max_query_range {
+            Some(max) if range.duration() > max => Err(QueryError::RangeTooLarge {
+                range_ms: range.duration().whole_milliseconds() as u64,
+                max_ms: max.whole_milliseconds() as u64,
+            }),
+            _ => Ok(()),
         }
+    }
 
-        // Create a single-wheel aggregation plan using the lowest granularity
-        if let Some(plan) = self.wheel_aggregation_plan(range) {
-            // Check for possible early return: Going further may just have more overhead
-
-            // If the plan supports single-wheel prefix scan or if
-            // the cost is low and not quantizable to multiple wheels.
-            if plan.is_prefix() || plan.cost() < SECONDS {
-                return Some(ExecutionPlan::WheelAggregation(plan));
-            }
-            // otherwise, set the plan as the current best plan
-            best_plan = Some(ExecutionPlan::WheelAggregation(plan));
-        }
-
-        // Check whether it is worth to create a combined plan as it comes with some overhead.
-        let use_combined_aggregation = {
-            let single_wheel_cost = best_plan.as_ref().map(|p| p.cost()).unwrap_or(0);
-
-            if self.conf.optimizer.use_hints && A::simd_support() {
-                // With SIMD support check whether our hints tells us its worth to perform a
-                // single-wheel aggregation over a combined aggregation.
-                single_wheel_cost > self.conf.optimizer.heuristics.simd_threshold
-            } else {
-                // otherwise always generate a combined aggregation plan
-                true
-            }
+    /// Combines partial aggregates within the given date range [start, end) into a [QueryResult]
+    ///
+    /// Unlike [Self::combine_range], a range that no data was inserted into yields [QueryResult::Empty] rather than
+    /// the [Aggregator::IDENTITY] of its empty slots. Emptiness is derived from the slots visited by the execution plan
+    /// (see [Self::occupancy]). Ranges that cannot be answered by the wheel are also reported as empty.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, QueryResult, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(0), None, None, Some(2)]);
+    ///
+    /// assert_eq!(haw.combine_range_result(WheelRange::new_unchecked(0, 1000)), QueryResult::Value(0));
+    ///
+    /// let empty = haw.combine_range_result(WheelRange::new_unchecked(1000, 3000));
+    /// assert!(empty.is_empty());
+    /// assert_eq!(empty.unwrap_or_identity(), 0);
+    /// ```
+    pub fn combine_range_result(&self, range: impl Into<WheelRange>) -> QueryResult<A> {
+        let range = range.into();
+        let Some(result) = self.combine_range(range) else {
+            return QueryResult::Empty;
         };
 
-        if use_combined_aggregation {
-            // Generate a Combined Aggregation plan by splitting the range into multiple non-overlapping ranges.
-            // NOTE: could create multiple combinations of combined aggregations to check
-            Self::maybe_update_plan_or_insert(
-                self.combined_aggregation_plan(Self::split_wheel_ranges(range))
-                    .map(ExecutionPlan::CombinedAggregation),
-                &mut best_plan,
-            );
+        // inverse landmark plans visit slots outside of the range, so count the slots of a forward plan instead
+        match self.forward_exec_plan(range) {
+            Some(plan) if self.contributing_slots(&plan) == 0 => QueryResult::Empty,
+            _ => QueryResult::Value(result),
         }
-
-        best_plan
     }
 
-    // helper method for updating execution plans
-    #[inline]
-    fn maybe_update_plan_or_insert(
-        plan: Option<ExecutionPlan>,
-        current: &mut Option<ExecutionPlan>,
-    ) {
-        match (plan, current.take()) {
-            (Some(p), Some(c)) => *current = Some(cmp::min(c, p)),
-            (Some(p), None) => *current = Some(p),
-            (None, Some(c)) => *current = Some(c),
-            (None, None) => (),
-        }
+    // Returns an execution plan for the range that only visits slots within the range
+    //
+    // Inverse landmark plans are replaced by a combined or single-wheel aggregation plan.
+    fn forward_exec_plan(&self, range: WheelRange) -> Option<ExecutionPlan> {
+        self.create_exec_plan(range).and_then(|plan| match plan {
+            ExecutionPlan::InverseLandmarkAggregation(_) => {
+                let mut range = range;
+                let wheel_start = self.wheel_start();
+                range.start = cmp::max(range.start, self.to_offset_date(wheel_start).ok()?);
+                self.combined_plan(range, wheel_start)
+                    .map(ExecutionPlan::CombinedAggregation)
+                    .or_else(|| {
+                        self.wheel_aggregation_plan(range)
+                            .map(ExecutionPlan::WheelAggregation)
+                    })
+            }
+            plan => Some(plan),
+        })
     }
 
-    /// Given a [start, end) interval, returns the cost (number of ⊕ operations) for a given wheel aggregation
+    /// Folds the partial aggregates that make up the given date range [start, end) using a custom function
     ///
-    /// Returns `None` if the wheel aggregation cannot be executed because of uninitialized wheel or out of bounds aggregation
-    #[inline]
-    fn wheel_aggregation_plan(&self, range: WheelRange) -> Option<WheelAggregation> {
-        let start = range.start;
-        let end = range.end;
+    /// Instead of combining sub-results through [Aggregator::combine], each partial aggregate visited by the
+    /// execution plan (i.e., the result of each wheel aggregation or the total of each wheel for landmark plans)
+    /// is passed to `f` together with the accumulator. Partial aggregates are visited from the oldest to the
+    /// most recent one for non-commutative aggregators. No intermediate results are buffered, which allows folding
+    /// into a pre-allocated accumulator when [Aggregator::combine] is costly.
+    ///
+    /// Returns `None` if the range cannot be answered by the wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance((1..=90).map(Some));
+    ///
+    /// // collect the partial aggregates of the minute and the remaining seconds
+    /// let range = WheelRange::new_unchecked(0, 70000);
+    /// let parts = haw.combine_range_fold(range, Vec::new(), |mut parts, partial| {
+    ///     parts.push(partial);
+    ///     parts
+    /// });
+    /// assert_eq!(parts.map(|parts| parts.iter().sum()), haw.combine_range(range));
+    /// ```
+    pub fn combine_range_fold<B>(
+        &self,
+        range: impl Into<WheelRange>,
+        init: B,
+        mut f: impl FnMut(B, A::PartialAggregate) -> B,
+    ) -> Option<B> {
+        let range = range.into();
+        if range.start > range.end || self.check_query_range(range).is_err() {
+            return None;
+        }
 
-        match range.lowest_granularity() {
-            Granularity::Second => {
-                let seconds = (end - start).whole_seconds() as usize;
-                self.seconds_wheel
-                    .plan(start, seconds, range, Granularity::Second)
+        match self.forward_exec_plan(range) {
+            Some(ExecutionPlan::WheelAggregation(agg)) => {
+                fold_partials(self.wheel_aggregation(agg).into_iter(), init, f)
             }
-            Granularity::Minute => {
-                let minutes = (end - start).whole_minutes() as usize;
-                self.minutes_wheel
-                    .plan(start, minutes, range, Granularity::Minute)
+            Some(ExecutionPlan::CombinedAggregation(mut combined)) => {
+                // the aggregations may have been reordered for locality
+                if !A::commutative() {
+                    combined
+                        .aggregations
+                        .sort_unstable_by_key(|agg| agg.range.start);
+                }
+                let partials = combined
+                    .aggregations
+                    .into_iter()
+                    .filter_map(|agg| self.wheel_aggregation(agg));
+                fold_partials(partials, init, f)
             }
-            Granularity::Hour => {
-                let hours = (end - start).whole_hours() as usize;
-                self.hours_wheel
-                    .plan(start, hours, range, Granularity::Hour)
+            Some(ExecutionPlan::LandmarkAggregation) => {
+                let mut totals = self.landmark_totals();
+                if !A::commutative() {
+                    // fold from the oldest data (years) to the most recent (seconds)
+                    totals.reverse();
+                }
+                fold_partials(totals.into_iter().flatten(), init, f)
             }
-            Granularity::Day => {
-                let days = (end - start).whole_days() as usize;
-                self.days_wheel.plan(start, days, range, Granularity::Day)
+            // the range can only be answered through an inverse combine
+            Some(ExecutionPlan::InverseLandmarkAggregation(_)) | None => {
+                self.combine_range(range).map(|partial| f(init, partial))
             }
         }
     }
 
-    #[inline]
-    #[doc(hidden)]
-    pub fn split_wheel_ranges(range: WheelRange) -> WheelRanges {
-        let mut ranges = WheelRanges::default();
-        let WheelRange { start, end } = range;
-        let mut current_start = start;
+    /// Combines partial aggregates within the given date range [start, end) and reports its [Coverage]
+    ///
+    /// Unlike [Self::combine_range], the caller can tell whether the result covers the whole range or only
+    /// the part that is still retained, which distinguishes an empty range from data that has been evicted.
+    /// The coverage is derived from [Self::oldest_timestamp] and the current watermark. A partially covered
+    /// range is clamped to `[oldest_timestamp, watermark)` before it is aggregated.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator, wheels::read::Coverage};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(10), None, Some(50), None]);
+    ///
+    /// let range = WheelRange::new_unchecked(0, 4000);
+    /// assert_eq!(haw.combine_range_checked(range), (Some(60), Coverage::Full));
+    ///
+    /// let range = WheelRange::new_unchecked(5000, 8000);
+    /// assert_eq!(haw.combine_range_checked(range), (None, Coverage::None));
+    /// ```
+    pub fn combine_range_checked(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> (Option<A::PartialAggregate>, Coverage) {
+        let range = range.into();
+        let start_ms = self.to_ms(range.start.unix_timestamp() as u64);
+        let end_ms = self.to_ms(range.end.unix_timestamp() as u64);
 
-        while current_start < end {
-            let current_end = Self::new_curr_end(current_start, end);
-            let range = WheelRange {
-                start: current_start,
-                end: current_end,
-            };
-            ranges.push(range);
-            current_start = current_end;
+        let coverage = match self.oldest_timestamp() {
+            Some(oldest) if start_ms >= oldest && end_ms <= self.watermark => Coverage::Full,
+            Some(oldest) if start_ms < self.watermark && end_ms > oldest => Coverage::Partial,
+            _ => Coverage::None,
+        };
+
+        match (coverage, self.oldest_timestamp()) {
+            (Coverage::Partial, Some(oldest)) => {
+                let clamped = WheelRange::new_unchecked(
+                    cmp::max(start_ms, oldest),
+                    cmp::min(end_ms, self.watermark),
+                );
+                (self.combine_range(clamped), coverage)
+            }
+            (Coverage::None, _) => (None, coverage),
+            _ => (self.combine_range(range), coverage),
         }
-        ranges
     }
 
-    // helper fn to calculate the next aligned end date
-    #[inline]
-    fn new_curr_end(current_start: OffsetDateTime, end: OffsetDateTime) -> OffsetDateTime {
-        let second = current_start.second();
-        let minute = current_start.minute();
-        let hour = current_start.hour();
-        let day = current_start.day();
-
-        // based on the lowest granularity figure out the duration to next alignment point
-        let next = if second > 0 {
-            time::Duration::seconds(60 - second as i64)
-        } else if minute > 0 {
-            time::Duration::minutes(60 - minute as i64)
-        } else if hour > 0 {
-            time::Duration::hours(24 - hour as i64)
-        } else if day > 0 {
-            let year = current_start.year();
-            let month = current_start.month() as u8;
-
-            // Calculate days in the current month
-            let days_in_current_month = Self::days_in_month(year, month);
-            let remaining_days_in_month = days_in_current_month as i64 - day as i64;
-
-            // Determine if we should move to the next month
-            let days_to_add = if remaining_days_in_month > 0 {
-                remaining_days_in_month
-            } else {
-                1 // Move to the next day, which is the first day of the next month
-            };
+    /// Returns `true` if the partial aggregate of the given date range [start, end) exceeds `threshold`
+    ///
+    /// For [Aggregator::monotonic] aggregators, the slots of the range are combined one by one and the
+    /// scan stops as soon as the threshold is crossed. Other aggregators combine the full range first.
+    /// Returns `false` if the range cannot be answered by the wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(10), None, Some(50), None]);
+    ///
+    /// let range = WheelRange::new_unchecked(0, 4000);
+    /// assert!(haw.combine_range_exceeds(range, 50));
+    /// assert!(!haw.combine_range_exceeds(range, 60));
+    /// ```
+    pub fn combine_range_exceeds(
+        &self,
+        range: impl Into<WheelRange>,
+        threshold: A::PartialAggregate,
+    ) -> bool
+    where
+        A::PartialAggregate: PartialOrd,
+    {
+        let mut range = range.into();
+        if self.check_query_range(range).is_err() {
+            return false;
+        }
+        if !A::monotonic() || range.start > range.end {
+            return self.combine_range(range).is_some_and(|agg| agg > threshold);
+        }
 
-            time::Duration::days(days_to_add)
-        } else {
-            unimplemented!("Weeks and Years not supported yet");
+        let wheel_start = self.wheel_start();
+        let Ok(start) = self.to_offset_date(wheel_start) else {
+            return false;
         };
+        range.start = cmp::max(range.start, start);
 
-        // TODO(perf): time::checked_add is somewhat expensive.
-        let next_aligned = current_start + next;
-
-        if next_aligned > end {
-            // if jumping too far: check the remaining duration between current_start and end
-            let rem = end - current_start;
-            let rem_secs = rem.whole_seconds();
-            let rem_mins = rem.whole_minutes();
-            let rem_hours = rem.whole_hours();
-            let rem_days = rem.whole_days();
-
-            let remains = [
-                (rem_secs, time::Duration::seconds(rem_secs)),
-                (rem_mins, time::Duration::minutes(rem_mins)),
-                (rem_hours, time::Duration::hours(rem_hours)),
-                (rem_days, time::Duration::days(rem_days)),
-            ];
+        let Some(combined) = self.combined_plan(range, wheel_start) else {
+            return self.combine_range(range).is_some_and(|agg| agg > threshold);
+        };
 
-            // Jump with lowest remaining resolution
-            let idx = remains
-                .iter()
-                .rposition(|&(rem, _)| rem > 0)
-                .unwrap_or(remains.len() - 1);
+        let mut acc = A::IDENTITY;
+        for agg in combined.aggregations {
+            let (start, end) = agg.slots;
+            for age in start..end {
+                if let Some(partial) = self.combine_slot_range(agg.granularity, age, age + 1) {
+                    acc = A::combine(acc, partial);
+                }
+                if acc > threshold {
+                    self.record_access(agg.granularity, age + 1 - start);
+                    return true;
+                }
+            }
+            self.record_access(agg.granularity, end - start);
+        }
+        false
+    }
 
-            current_start + remains[idx].1
-        } else {
-            next_aligned
+    /// Combines partial aggregates within the given date range [start, end) using only the wheel of the given granularity
+    ///
+    /// Bypasses the query optimizer which gives explicit control over which wheel answers the query.
+    /// Returns an error if the range is not aligned to the slots of the granularity and `None`
+    /// if the wheel cannot answer the range (e.g., uninitialized wheel or data no longer retained).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, QueryError, WheelRange, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 120]);
+    ///
+    /// let range = WheelRange::new_unchecked(0, 120000);
+    /// assert_eq!(haw.combine_range_at(range, Granularity::Minute), Ok(Some(120)));
+    /// assert_eq!(
+    ///     haw.combine_range_at(range, Granularity::Hour),
+    ///     Err(QueryError::UnalignedRange { granularity: Granularity::Hour })
+    /// );
+    /// ```
+    pub fn combine_range_at(
+        &self,
+        range: impl Into<WheelRange>,
+        granularity: Granularity,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        let range = range.into();
+        self.check_query_range(range)?;
+        if !self.is_aligned_to(range, granularity) {
+            return Err(QueryError::UnalignedRange { granularity });
         }
+        Ok(self
+            .wheel_aggregation_plan_at(range, granularity)
+            .and_then(|plan| self.wheel_aggregation(plan)))
     }
 
-    // helper method to get the days of a month
+    /// Combines partial aggregates within the given date range [start, end) using only the seconds wheel
+    ///
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Second].
     #[inline]
-    fn days_in_month(year: i32, month: u8) -> u8 {
-        const DAYS_30: [u8; 4] = [4, 6, 9, 11];
-        const DAYS_31: [u8; 7] = [1, 3, 5, 7, 8, 10, 12];
-
-        if DAYS_31.contains(&month) {
-            31
-        } else if DAYS_30.contains(&month) {
-            30
-        } else {
-            // This must be February
-            if (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0) {
-                29
-            } else {
-                28
-            }
-        }
+    pub fn seconds_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Second)
     }
 
-    /// Takes a set of wheel ranges and creates a CombinedAggregation plan
-    #[doc(hidden)]
+    /// Combines partial aggregates within the given date range [start, end) using only the minutes wheel
+    ///
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Minute].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 180]);
+    ///
+    /// assert_eq!(haw.minutes_range(WheelRange::new_unchecked(60000, 180000)), Ok(Some(120)));
+    /// assert!(haw.minutes_range(WheelRange::new_unchecked(30000, 180000)).is_err());
+    /// ```
     #[inline]
-    pub fn combined_aggregation_plan(&self, ranges: WheelRanges) -> Option<CombinedAggregation> {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.combined_aggregation_plan);
+    pub fn minutes_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Minute)
+    }
 
-        let mut aggregations = WheelAggregations::default();
-
-        for range in ranges.into_iter() {
-            match self.wheel_aggregation_plan(range) {
-                Some(plan) => aggregations.push(plan),
-                None => return None, // early return if a single aggregation cannot be executed
-            }
-        }
-
-        // function that returns a score of the wheel range which is used during sorting
-        let granularity_score = |start: &OffsetDateTime, end: &OffsetDateTime| {
-            let (sh, sm, ss) = start.time().as_hms();
-            let (eh, em, es) = end.time().as_hms();
-            if ss > 0 || es > 0 {
-                // second rank
-                0
-            } else if sm > 0 || em > 0 {
-                // minute rank
-                1
-            } else if sh > 0 || eh > 0 {
-                // hour rank
-                2
-            } else {
-                // day rank (00:00:00 - 00:00:00)
-                3
-            }
-        };
-
-        // Sort ranges based on lowest granularity in order to execute ranges in order of granularity
-        // so that the execution visits wheels sequentially and not randomly.
-        aggregations.sort_unstable_by(|a, b| {
-            let a_score = granularity_score(&a.range.start, &a.range.end);
-            let b_score = granularity_score(&b.range.start, &b.range.end);
-            a_score.cmp(&b_score)
-        });
-
-        Some(CombinedAggregation::from(aggregations))
+    /// Combines partial aggregates within the given date range [start, end) using only the hours wheel
+    ///
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Hour].
+    #[inline]
+    pub fn hours_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Hour)
     }
 
-    // Performs a inverse landmark aggregation
-    fn inverse_landmark_aggregation(
+    /// Combines partial aggregates within the given date range [start, end) using only the days wheel
+    ///
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Day].
+    #[inline]
+    pub fn days_range(
         &self,
-        wheel_aggregations: WheelAggregations,
-    ) -> (A::PartialAggregate, usize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.inverse_landmark);
-
-        // get landmark partial
-        let (landmark, lcost) = self.analyze_landmark();
-        let combine_inverse = A::combine_inverse().unwrap(); // assumed to be safe as it has been verified by the plan generation
-
-        wheel_aggregations.into_iter().fold(
-            (landmark.unwrap_or(A::IDENTITY), lcost),
-            |mut acc, plan| {
-                let cost = plan.cost();
-                let agg = self.wheel_aggregation(plan).unwrap_or(A::IDENTITY);
-                acc.0 = combine_inverse(acc.0, agg);
-                acc.1 += cost;
-                acc
-            },
-        )
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Day)
     }
 
-    /// Performs a given Combined Aggregation and returns the partial aggregate and the cost of the operation
+    /// Combines partial aggregates within the given date range [start, end) using only the weeks wheel
+    ///
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Week].
     #[inline]
-    fn combined_aggregation(
+    pub fn weeks_range(
         &self,
-        combined: CombinedAggregation,
-    ) -> (Option<A::PartialAggregate>, usize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.combined_aggregation);
-
-        let cost = combined.cost();
-        let agg = combined
-            .aggregations
-            .into_iter()
-            .fold(None, |mut acc, wheel_agg| {
-                match self.wheel_aggregation(wheel_agg) {
-                    Some(agg) => {
-                        combine_or_insert::<A>(&mut acc, agg);
-                        acc
-                    }
-                    None => acc,
-                }
-            });
-        (agg, cost)
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Week)
     }
-    /// Combines partial aggregates within [start, end) using the lowest time granularity wheel
-    ///
-    /// Note that start date is inclusive while end date is exclusive.
-    /// For instance, the range [16:00:50, 16:01:00) refers to the 10 remaining seconds of the minute
+
+    /// Combines partial aggregates within the given date range [start, end) using only the years wheel
     ///
-    /// Returns `None` if the range cannot be answered by the wheel
-    fn wheel_aggregation(&self, agg: WheelAggregation) -> Option<A::PartialAggregate> {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.wheel_aggregation);
+    /// Shortcut for [Self::combine_range_at] with [Granularity::Year].
+    #[inline]
+    pub fn years_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.combine_range_at(range, Granularity::Year)
+    }
 
-        let (start, end) = agg.slots;
-        let slot_range = start..end;
-        match agg.granularity {
-            Granularity::Second => self.seconds_wheel.combine_range(slot_range),
-            Granularity::Minute => self.minutes_wheel.combine_range(slot_range),
-            Granularity::Hour => self.hours_wheel.combine_range(slot_range),
-            Granularity::Day => self.days_wheel.combine_range(slot_range),
-        }
+    // Returns true if both ends of the range fall on slot boundaries of the given granularity.
+    // Weeks and years are aligned relative to the start of the wheel cycle, or to January 1 for calendar years.
+    #[inline]
+    fn is_aligned_to(&self, range: WheelRange, granularity: Granularity) -> bool {
+        let start_ms = self.to_ms(range.start.unix_timestamp() as u64);
+        let end_ms = self.to_ms(range.end.unix_timestamp() as u64);
+
+        let (origin, unit_secs) = match granularity {
+            Granularity::Second => (0, 1),
+            Granularity::Minute => (0, Self::MINUTES_AS_SECS),
+            Granularity::Hour => (0, Self::HOURS_AS_SECS),
+            Granularity::Day => (0, Self::DAYS_AS_SECS),
+            Granularity::Week => (self.week_origin(), Self::WEEK_AS_SECS),
+            Granularity::Year if self.conf.calendar_years => {
+                return start_ms >= self.wheel_start()
+                    && self.is_year_start(start_ms)
+                    && self.is_year_start(end_ms);
+            }
+            Granularity::Year => (self.wheel_start(), Self::YEAR_AS_SECS),
+        };
+        let unit = Self::secs_to_ms(unit_secs);
+
+        start_ms >= origin
+            && (start_ms - origin).is_multiple_of(unit)
+            && (end_ms - origin).is_multiple_of(unit)
     }
 
-    /// Returns the partial aggregate in the given time interval [(watermark - `duration`), watermark)
+    /// Returns the difference between the partial aggregates of range `a` and range `b`
     ///
-    /// Internally the [Self::combine_range] function is used to produce the result
+    /// The difference is computed as `combine_inverse(agg(a), agg(b))` which is useful for comparisons
+    /// such as this week versus last week.
+    ///
+    /// Returns `None` if the aggregator is not invertible or if either range cannot be answered by the wheel
     ///
     /// # Example
     ///
     /// ```
-    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U64SumAggregator};
     ///
-    /// // Init a HAW with time 0
-    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
-    /// let deltas = vec![Some(10), None, Some(50), None];
-    /// haw.delta_advance(deltas);
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(10), Some(5), Some(25), Some(20)]);
     ///
-    /// assert_eq!(haw.interval(1.seconds()), Some(0));
-    /// assert_eq!(haw.interval(2.seconds()), Some(50));
-    /// assert_eq!(haw.interval(3.seconds()), Some(50));
-    /// assert_eq!(haw.interval(4.seconds()), Some(60));
+    /// let a = WheelRange::new_unchecked(2000, 4000);
+    /// let b = WheelRange::new_unchecked(0, 2000);
+    /// assert_eq!(haw.range_delta(a, b), Some(30));
     /// ```
-    pub fn interval(&self, dur: Duration) -> Option<A::PartialAggregate> {
-        self.interval_with_stats(dur).0
+    pub fn range_delta(
+        &self,
+        a: impl Into<WheelRange>,
+        b: impl Into<WheelRange>,
+    ) -> Option<A::PartialAggregate> {
+        let combine_inverse = A::combine_inverse()?;
+        let a = self.combine_range(a)?;
+        let b = self.combine_range(b)?;
+        Some(combine_inverse(a, b))
     }
 
-    /// Returns the partial aggregate in the given time interval and lowers the result
+    /// Replays the windows of length `window_range` and slide `window_slide` over the retained data in `range`
     ///
-    /// Internally the [Self::combine_range] function is used to produce the result
+    /// Windows start at the beginning of `range` and only windows that end within `range` are computed.
+    /// The wheel is not mutated and windows that cannot be answered by the wheel are skipped.
     ///
-    /// See [Self::interval] for example.
-    pub fn interval_and_lower(&self, dur: Duration) -> Option<A::Aggregate> {
-        self.interval(dur).map(|partial| A::lower(partial))
-    }
-
-    /// Returns the partial aggregate in the given time interval
-    #[inline]
-    pub fn interval_with_stats(&self, dur: Duration) -> (Option<A::PartialAggregate>, usize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.interval);
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, NumericalDuration, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(10), Some(20), Some(30), Some(40)]);
+    ///
+    /// let windows = haw.replay_windows(WheelRange::new_unchecked(0, 4000), 2.seconds(), 1.seconds());
+    /// let aggregates: Vec<u64> = windows.iter().map(|w| w.aggregate).collect();
+    /// assert_eq!(aggregates, vec![30, 50, 70]);
+    /// ```
+    pub fn replay_windows(
+        &self,
+        range: impl Into<WheelRange>,
+        window_range: Duration,
+        window_slide: Duration,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        let range = range.into();
+        let start = self.to_ms(range.start.unix_timestamp() as u64);
+        let end = self.to_ms(range.end.unix_timestamp() as u64);
+        let length = window_range.whole_milliseconds() as u64;
+        let slide = window_slide.whole_milliseconds() as u64;
 
-        let to = self.now();
-        let from = to.saturating_sub(time::Duration::seconds(dur.whole_seconds()));
+        let mut windows = Vec::new();
+        if length == 0 || slide == 0 {
+            return windows;
+        }
 
-        self.analyze_combine_range(WheelRange {
-            start: from,
-            end: to,
-        })
+        let mut window_start = start;
+        while window_start + length <= end {
+            let window_end = window_start + length;
+            if let Some(aggregate) =
+                self.combine_range(WheelRange::new_unchecked(window_start, window_end))
+            {
+                windows.push(WindowAggregate {
+                    window_start_ms: window_start,
+                    window_end_ms: window_end,
+                    aggregate,
+                });
+            }
+            window_start += slide;
+        }
+        windows
     }
 
-    /// Executes a Landmark Window that combines total partial aggregates across all wheels into a full-wheel result
+    /// Executes a combine range query and returns the result, cost (combine ops) and the number of contributing slots
+    ///
+    /// The cost counts the combine operations over all slots visited by the execution plan, while the
+    /// contributing slots only count visited slots that data was inserted into (see [Self::occupancy]).
+    /// A low ratio of contributing slots to cost indicates a sparse range.
+    ///
+    /// Returns `None` if the range cannot be answered by the wheel
     ///
     /// # Example
     ///
     /// ```
-    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
     ///
-    /// // Init a HAW with time 0
     /// let mut haw: Haw<U32SumAggregator> = Haw::default();
-    /// // Advance HAW with 1800 (total 30min) deltas with value 1
-    /// let deltas = (0..1800).map(Some).collect::<Vec<_>>();
-    /// haw.delta_advance(deltas);
+    /// haw.delta_advance([Some(1), None, None, Some(2)]);
     ///
-    /// // get the full result between time [0, watermark).
-    /// assert_eq!(haw.landmark(), Some(1619100));
+    /// let range = WheelRange::new_unchecked(0, 3000);
+    /// assert_eq!(haw.analyze_combine_range(range), (Some(1), 3, 1));
     /// ```
     #[inline]
-    pub fn landmark(&self) -> Option<A::PartialAggregate> {
-        self.analyze_landmark().0
+    pub fn analyze_combine_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> (Option<A::PartialAggregate>, usize, usize) {
+        let range = range.into();
+        let (result, cost) = self.combine_range_inner(range);
+        let contributing = self
+            .create_exec_plan(range)
+            .map(|plan| self.contributing_slots(&plan))
+            .unwrap_or(0);
+        (result, cost, contributing)
     }
 
-    /// Executes a Landmark Window that combines total partial aggregates across all wheels and lowers the result
+    /// Executes a Landmark Window and returns the result together with its cost (combine ops)
     ///
-    /// See [Self::landmark] for an example.
+    /// The cost counts the combine operations between the totals of the wheels, which is one less than
+    /// the number of wheels that hold data. Use this for query-cost logging in the same way as
+    /// [Self::analyze_combine_range], whereas [Self::landmark] only returns the result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 90]);
+    ///
+    /// // seconds and minutes wheels hold data
+    /// assert_eq!(haw.landmark_with_cost(), (Some(90), 1));
+    /// ```
     #[inline]
-    pub fn landmark_and_lower(&self) -> Option<A::Aggregate> {
-        self.landmark().map(|partial| A::lower(partial))
+    pub fn landmark_with_cost(&self) -> (Option<A::PartialAggregate>, usize) {
+        self.analyze_landmark()
     }
 
-    /// Executes a Landmark Window that combines total partial aggregates across all wheels and returns the aggregate cost
-    #[inline]
-    pub(crate) fn analyze_landmark(&self) -> (Option<A::PartialAggregate>, usize) {
-        #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.landmark);
+    // Returns the number of occupied slots visited by the given execution plan
+    fn contributing_slots(&self, plan: &ExecutionPlan) -> usize {
+        let occupied = |granularity: Granularity, (start, end): (usize, usize)| {
+            self.wheel(granularity).map_or(0, |wheel| {
+                (start..end)
+                    .filter(|&slot| wheel.is_occupied(slot).unwrap_or(false))
+                    .count()
+            })
+        };
+        let aggregations = |aggs: &[WheelAggregation]| {
+            aggs.iter()
+                .map(|agg| occupied(agg.granularity, agg.slots))
+                .sum::<usize>()
+        };
+        // the landmark visits the rotation total of every wheel
+        let landmark = || {
+            [
+                Granularity::Second,
+                Granularity::Minute,
+                Granularity::Hour,
+                Granularity::Day,
+                Granularity::Week,
+                Granularity::Year,
+            ]
+            .into_iter()
+            .filter_map(|granularity| {
+                self.wheel(granularity)
+                    .map(|wheel| occupied(granularity, (0, wheel.rotation_count())))
+            })
+            .filter(|&slots| slots > 0)
+            .count()
+        };
 
-        let wheels = [
-            self.seconds_wheel.total(),
-            self.minutes_wheel.total(),
-            self.hours_wheel.total(),
-            self.days_wheel.total(),
-            self.weeks_wheel.total(),
-            self.years_wheel.total(),
-        ];
-        Self::reduce(wheels)
+        match plan {
+            ExecutionPlan::WheelAggregation(agg) => occupied(agg.granularity, agg.slots),
+            ExecutionPlan::CombinedAggregation(combined) => aggregations(&combined.aggregations),
+            ExecutionPlan::LandmarkAggregation => landmark(),
+            ExecutionPlan::InverseLandmarkAggregation(aggs) => landmark() + aggregations(aggs),
+        }
     }
 
+    /// Combines partial aggregates within the given date range [start, end) into a final partial aggregate
     #[inline]
-    fn reduce(
-        partial_aggs: impl IntoIterator<Item = Option<A::PartialAggregate>>,
+    fn combine_range_inner(
+        &self,
+        range: impl Into<WheelRange>,
     ) -> (Option<A::PartialAggregate>, usize) {
-        let mut combines = 0;
-        let agg = partial_aggs
-            .into_iter()
-            .reduce(|acc, b| match (acc, b) {
-                (Some(curr), Some(agg)) => {
-                    combines += 1;
-                    Some(A::combine(curr, agg))
-                }
-                (None, Some(_)) => b,
-                _ => acc,
-            })
-            .flatten();
-        (agg, combines)
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.combine_range);
+
+        let range = range.into();
+        let WheelRange { start, end } = range;
+
+        // Return early if the range is invalid (Start exceeds End) or exceeds the maximum query range
+        if start > end || self.check_query_range(range).is_err() {
+            return (None, 0);
+        }
+
+        let cache_size = self.conf.query_cache_size;
+        if cache_size > 0 {
+            if let Some(result) = self.cache.get(range, self.watermark) {
+                return (result, 0);
+            }
+        }
+
+        // create the best possible execution plan and run it
+        let (result, cost) = match self.create_exec_plan(range) {
+            Some(plan) => self.execute_plan(plan),
+            None => (None, 0), // No execution plan possible
+        };
+
+        if cache_size > 0 {
+            self.cache.insert(range, self.watermark, result, cache_size);
+        }
+        (result, cost)
     }
-    /// Schedules a timer to fire once the HAW has reached the specified time.
+
+    /// Executes a combine range query and returns the result together with the plan that was executed
+    ///
+    /// Unlike [Self::explain_combine_range], the returned plan is the one that actually produced the result.
+    /// The plan is `None` if the range cannot be answered by the wheel.
     ///
     /// # Example
     ///
     /// ```
-    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    /// use uwheel::{Haw, WheelRange, wheels::read::ExecutionPlan, aggregator::sum::U32SumAggregator};
     ///
-    /// // Init a HAW with time 0
     /// let mut haw: Haw<U32SumAggregator> = Haw::default();
-    /// let _ = haw.schedule_once(5000, move |haw: &Haw<_>| {
-    ///    println!("{:?}", haw.interval(5.seconds()));
-    /// });
+    /// let deltas = vec![Some(10), None, Some(50), None];
+    /// haw.delta_advance(deltas);
     ///
+    /// let range = WheelRange::new_unchecked(0, 4000);
+    /// let (result, plan) = haw.combine_range_explained(range);
+    /// assert_eq!(result, Some(60));
+    /// assert_eq!(plan, Some(ExecutionPlan::LandmarkAggregation));
     /// ```
-    #[cfg(feature = "timer")]
-    pub fn schedule_once(
+    pub fn combine_range_explained(
         &self,
-        time: u64,
-        f: impl Fn(&Haw<A>) + 'static,
-    ) -> Result<(), TimerError<TimerAction<A>>> {
-        self.timer
-            .write()
-            .schedule_at(time, TimerAction::Oneshot(Box::new(f)))
+        range: impl Into<WheelRange>,
+    ) -> (Option<A::PartialAggregate>, Option<ExecutionPlan>) {
+        let range = range.into();
+        if range.start > range.end || self.check_query_range(range).is_err() {
+            return (None, None);
+        }
+        match self.create_exec_plan(range) {
+            Some(plan) => {
+                let (result, _) = self.execute_plan(plan.clone());
+                (result, Some(plan))
+            }
+            None => (None, None),
+        }
     }
-    /// Schedules a timer to fire repeatedly
+
+    /// Returns a human-readable dump of the execution plan for the given range including the partial aggregate of each step
+    ///
+    /// Partial aggregates are printed through [Aggregator::format_partial]. Returns `None` if the range cannot be answered by the wheel.
     ///
     /// # Example
     ///
     /// ```
-    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator};
     ///
-    /// // Init a HAW with time 0
     /// let mut haw: Haw<U32SumAggregator> = Haw::default();
-    /// let _ = haw.schedule_repeat(5000, 5.seconds(), move |haw: &Haw<_>| {
-    ///    println!("{:?}", haw.interval(5.seconds()));
-    /// });
+    /// haw.delta_advance([Some(10), None, Some(50), None]);
     ///
+    /// let dump = haw.dump_combine_range(WheelRange::new_unchecked(1000, 3000)).unwrap();
+    /// assert_eq!(dump, "WheelAggregation (cost 2)\n  Second [1, 3) => 50\nresult => 50\n");
     /// ```
-    #[cfg(feature = "timer")]
-    pub fn schedule_repeat(
-        &self,
-        at: u64,
-        interval: Duration,
-        f: impl Fn(&Haw<A>) + 'static,
-    ) -> Result<(), TimerError<TimerAction<A>>> {
-        self.timer
-            .write()
-            .schedule_at(at, TimerAction::Repeat((at, interval, Box::new(f))))
+    pub fn dump_combine_range(&self, range: impl Into<WheelRange>) -> Option<String> {
+        use core::fmt::Write;
+
+        let range = range.into();
+        if range.start > range.end || self.check_query_range(range).is_err() {
+            return None;
+        }
+        let plan = self.create_exec_plan(range)?;
+
+        let mut out = String::new();
+        let step = |prefix: &str, agg: &WheelAggregation| {
+            let (start, end) = agg.slots;
+            let partial = self
+                .wheel_aggregation(*agg)
+                .map_or_else(|| "None".into(), |p| A::format_partial(&p));
+            format!(
+                "  {prefix}{:?} [{start}, {end}) => {partial}\n",
+                agg.granularity
+            )
+        };
+        let _ = writeln!(out, "{:?} (cost {})", plan.kind(), plan.cost());
+        match &plan {
+            ExecutionPlan::WheelAggregation(agg) => out.push_str(&step("", agg)),
+            ExecutionPlan::CombinedAggregation(combined) => {
+                for agg in combined.aggregations() {
+                    out.push_str(&step("", agg));
+                }
+            }
+            ExecutionPlan::LandmarkAggregation => {}
+            ExecutionPlan::InverseLandmarkAggregation(aggs) => {
+                if let (Some(landmark), _) = self.analyze_landmark() {
+                    let _ = writeln!(out, "  landmark => {}", A::format_partial(&landmark));
+                }
+                for agg in aggs {
+                    out.push_str(&step("- ", agg));
+                }
+            }
+        }
+        let result = self
+            .execute_plan(plan)
+            .0
+            .map_or_else(|| "None".into(), |p| A::format_partial(&p));
+        let _ = writeln!(out, "result => {result}");
+        Some(out)
     }
 
-    /// Tick the wheel by a single unit (second)
+    // Executes the given plan and returns the result + cost (combine ops) of executing it
+    #[inline]
+    fn execute_plan(&self, plan: ExecutionPlan) -> (Option<A::PartialAggregate>, usize) {
+        match plan {
+            ExecutionPlan::WheelAggregation(wheel_agg) => {
+                (self.wheel_aggregation(wheel_agg), wheel_agg.cost())
+            }
+            ExecutionPlan::CombinedAggregation(combined) => self.combined_aggregation(combined),
+            ExecutionPlan::LandmarkAggregation => self.analyze_landmark(),
+            ExecutionPlan::InverseLandmarkAggregation(wheel_aggs) => {
+                let (result, cost) = self.inverse_landmark_aggregation(wheel_aggs);
+                (Some(result), cost)
+            }
+        }
+    }
+    /// Returns the best possible execution plan for a given wheel range
     ///
-    /// In the worst case, a tick may cause a rotation of all the wheels in the hierarchy.
+    /// Returns `None` if no plan can be established.
     #[inline]
-    fn tick(&mut self, partial_opt: Option<A::PartialAggregate>) {
+    fn create_exec_plan(&self, mut range: WheelRange) -> Option<ExecutionPlan> {
         #[cfg(feature = "profiler")]
-        profile_scope!(&self.stats.tick);
+        profile_scope!(&self.stats.exec_plan);
 
-        self.watermark += Self::SECOND_AS_MS;
+        let mut best_plan: Option<ExecutionPlan> = None;
 
-        // if 'None', insert the Identity value
-        let partial = partial_opt.unwrap_or(A::IDENTITY);
-        let seconds = self.seconds_wheel.get_or_insert();
+        let wheel_start = self.wheel_start();
 
-        seconds.insert_head(partial);
+        // SAFETY: ensure start range is not lower than the start of the wheel time nor the oldest retained slot
+        let retained_start = cmp::max(wheel_start, self.oldest_timestamp().unwrap_or(wheel_start));
+        range.start = cmp::max(range.start, self.to_offset_date(retained_start).ok()?);
 
-        // full rotation of seconds wheel
-        if let Some(rot_data) = seconds.tick() {
-            // insert 60 seconds worth of partial aggregates into minute wheel and then tick it
-            let minutes = self.minutes_wheel.get_or_insert();
+        // Ranges that end before the oldest retained slot cannot be answered
+        if range.start >= range.end {
+            return None;
+        }
 
-            minutes.insert_slot(rot_data);
+        let end_ms = self.to_ms(range.end.unix_timestamp() as u64);
+        let start_ms = self.to_ms(range.start.unix_timestamp() as u64);
 
-            // full rotation of minutes wheel
-            if let Some(rot_data) = minutes.tick() {
-                // insert 60 minutes worth of partial aggregates into hours wheel and then tick it
-                let hours = self.hours_wheel.get_or_insert();
+        // Landmark optimization: landmark covers the whole range
+        if start_ms <= wheel_start && end_ms >= self.watermark() {
+            return Some(ExecutionPlan::LandmarkAggregation);
+        }
 
-                hours.insert_slot(rot_data);
+        // Create a single-wheel aggregation plan using the lowest granularity
+        if let Some(plan) = self.wheel_aggregation_plan(range) {
+            // Check for possible early return: Going further may just have more overhead
 
-                // full rotation of hours wheel
-                if let Some(rot_data) = hours.tick() {
-                    // insert 24 hours worth of partial aggregates into days wheel and then tick it
-                    let days = self.days_wheel.get_or_insert();
-                    days.insert_slot(rot_data);
+            // If the plan supports single-wheel prefix scan or if
+            // the cost is low and not quantizable to multiple wheels.
+            if plan.is_prefix() || plan.cost() < SECONDS {
+                return Some(ExecutionPlan::WheelAggregation(plan));
+            }
+            // otherwise, set the plan as the current best plan
+            best_plan = Some(ExecutionPlan::WheelAggregation(plan));
+        }
 
-                    // full rotation of days wheel
-                    if let Some(rot_data) = days.tick() {
-                        // insert 7 days worth of partial aggregates into weeks wheel and then tick it
-                        let weeks = self.weeks_wheel.get_or_insert();
+        // Check whether it is worth to create a combined plan as it comes with some overhead.
+        let use_combined_aggregation = {
+            let single_wheel_cost = best_plan.as_ref().map(|p| p.cost()).unwrap_or(0);
+
+            if self.conf.optimizer.use_hints && A::simd_support() {
+                // With SIMD support check whether our hints tells us its worth to perform a
+                // single-wheel aggregation over a combined aggregation.
+                single_wheel_cost > self.conf.optimizer.heuristics.simd_threshold
+            } else {
+                // otherwise always generate a combined aggregation plan
+                true
+            }
+        };
 
-                        weeks.insert_slot(rot_data);
+        if use_combined_aggregation {
+            // NOTE: could create multiple combinations of combined aggregations to check
+            Self::maybe_update_plan_or_insert(
+                self.combined_plan(range, wheel_start)
+                    .map(ExecutionPlan::CombinedAggregation),
+                &mut best_plan,
+            );
+        }
 
-                        // full rotation of weeks wheel
-                        if let Some(rot_data) = weeks.tick() {
-                            // insert 1 years worth of partial aggregates into year wheel and then tick it
-                            let years = self.years_wheel.get_or_insert();
-                            years.insert_slot(rot_data);
+        // Exactly invertible aggregators may answer the range by removing the complement ranges from the landmark
+        if A::exact_inverse() {
+            Self::maybe_update_plan_or_insert(
+                self.inverse_landmark_plan(range, wheel_start)
+                    .map(ExecutionPlan::InverseLandmarkAggregation),
+                &mut best_plan,
+            );
+        }
 
-                            // tick but ignore full rotations as this is the last hierarchy
-                            let _ = years.tick();
-                        }
-                    }
-                }
+        best_plan
+    }
+
+    // Generates a Combined Aggregation plan by splitting the range into multiple non-overlapping ranges
+    fn combined_plan(&self, range: WheelRange, wheel_start: u64) -> Option<CombinedAggregation> {
+        self.combined_aggregation_plan(Self::split_wheel_ranges(range))
+            .or_else(|| {
+                // ranges spanning weeks or years need to be split along the slots of those wheels
+                self.aligned_ranges(range, wheel_start)
+                    .and_then(|ranges| self.combined_aggregation_plan(ranges))
+            })
+    }
+
+    // Creates the wheel aggregations of the complement ranges [wheel_start, start) and [end, now)
+    // that are inverse combined from the landmark aggregate.
+    fn inverse_landmark_plan(
+        &self,
+        range: WheelRange,
+        wheel_start: u64,
+    ) -> Option<WheelAggregations> {
+        let complements = [
+            WheelRange {
+                start: self.to_offset_date(wheel_start).ok()?,
+                end: range.start,
+            },
+            WheelRange {
+                start: range.end,
+                end: self.now().ok()?,
+            },
+        ];
+
+        let mut aggregations = WheelAggregations::default();
+        for complement in complements {
+            for range in self.aligned_ranges(complement, wheel_start)? {
+                aggregations.push(self.wheel_aggregation_plan(range)?);
+            }
+        }
+        Some(aggregations)
+    }
+
+    // helper method for updating execution plans
+    #[inline]
+    fn maybe_update_plan_or_insert(
+        plan: Option<ExecutionPlan>,
+        current: &mut Option<ExecutionPlan>,
+    ) {
+        match (plan, current.take()) {
+            (Some(p), Some(c)) => *current = Some(cmp::min(c, p)),
+            (Some(p), None) => *current = Some(p),
+            (None, Some(c)) => *current = Some(c),
+            (None, None) => (),
+        }
+    }
+
+    /// Given a [start, end) interval, returns the cost (number of ⊕ operations) for a given wheel aggregation
+    ///
+    /// Returns `None` if the wheel aggregation cannot be executed because of uninitialized wheel or out of bounds aggregation
+    #[inline]
+    fn wheel_aggregation_plan(&self, range: WheelRange) -> Option<WheelAggregation> {
+        let granularity = range.lowest_granularity();
+
+        // Day-aligned ranges covering whole weeks or years are answered by those wheels if possible
+        if granularity == Granularity::Day {
+            if let Some(plan) = self
+                .aligned_coarse_granularity(range)
+                .and_then(|coarse| self.wheel_aggregation_plan_at(range, coarse))
+            {
+                return Some(plan);
+            }
+        }
+
+        self.wheel_aggregation_plan_at(range, granularity)
+    }
+
+    // Returns the week or year granularity if the range is aligned to the slots of that wheel
+    #[inline]
+    fn aligned_coarse_granularity(&self, range: WheelRange) -> Option<Granularity> {
+        let wheel_start = self.wheel_start();
+        let start_ms = self.to_ms(range.start.unix_timestamp() as u64);
+        let end_ms = self.to_ms(range.end.unix_timestamp() as u64);
+
+        if start_ms < wheel_start || end_ms > self.watermark() || start_ms >= end_ms {
+            return None;
+        }
+
+        [Granularity::Year, Granularity::Week]
+            .into_iter()
+            .find(|&granularity| self.is_aligned_to(range, granularity))
+    }
+
+    // Creates a wheel aggregation plan for the range using the wheel of the given granularity
+    #[inline]
+    fn wheel_aggregation_plan_at(
+        &self,
+        range: WheelRange,
+        granularity: Granularity,
+    ) -> Option<WheelAggregation> {
+        let start = range.start;
+        let end = range.end;
+
+        match granularity {
+            Granularity::Second => {
+                let seconds = (end - start).whole_seconds() as usize;
+                self.seconds_wheel
+                    .plan(self.wheel_date(start), seconds, range, Granularity::Second)
+            }
+            Granularity::Minute => {
+                let minutes = (end - start).whole_minutes() as usize;
+                self.minutes_wheel
+                    .plan(self.wheel_date(start), minutes, range, Granularity::Minute)
             }
+            Granularity::Hour => {
+                let hours = (end - start).whole_hours() as usize;
+                self.hours_wheel
+                    .plan(self.wheel_date(start), hours, range, Granularity::Hour)
+            }
+            Granularity::Day => {
+                let days = (end - start).whole_days() as usize;
+                self.days_wheel
+                    .plan(self.wheel_date(start), days, range, Granularity::Day)
+            }
+            Granularity::Week => {
+                let weeks = (end - start).whole_weeks() as usize;
+                self.weeks_wheel
+                    .plan(self.wheel_date(start), weeks, range, Granularity::Week)
+            }
+            Granularity::Year => {
+                let years = (end - start).whole_weeks() as usize / WEEKS;
+                self.years_wheel
+                    .plan(self.wheel_date(start), years, range, Granularity::Year)
+            }
+        }
+    }
+
+    #[inline]
+    #[doc(hidden)]
+    pub fn split_wheel_ranges(range: WheelRange) -> WheelRanges {
+        let mut ranges = WheelRanges::default();
+        let WheelRange { start, end } = range;
+        let mut current_start = start;
+
+        while current_start < end {
+            let current_end = Self::new_curr_end(current_start, end);
+            let range = WheelRange {
+                start: current_start,
+                end: current_end,
+            };
+            ranges.push(range);
+            current_start = current_end;
+        }
+        ranges
+    }
+
+    // Splits the range into sub-ranges aligned to the slots of the wheels (including weeks and years)
+    // relative to the start of the wheel cycle.
+    //
+    // Returns `None` if the range cannot be aligned to the lowest granularity.
+    fn split_aligned_ranges(&self, range: WheelRange, wheel_start: u64) -> Option<WheelRanges> {
+        let units = [
+            1,
+            Self::MINUTES_AS_SECS,
+            Self::HOURS_AS_SECS,
+            Self::DAYS_AS_SECS,
+            Self::WEEK_AS_SECS,
+            Self::YEAR_AS_SECS,
+        ]
+        .map(Self::secs_to_ms);
+        self.split_aligned_ranges_by(range, wheel_start, &units)
+    }
+
+    // Splits the range into sub-ranges aligned to calendar years and to the slots of the lower wheels.
+    //
+    // Returns `None` if the range cannot be aligned to the lowest granularity.
+    fn split_calendar_ranges(&self, range: WheelRange) -> Option<WheelRanges> {
+        let units = [
+            1,
+            Self::MINUTES_AS_SECS,
+            Self::HOURS_AS_SECS,
+            Self::DAYS_AS_SECS,
+            Self::WEEK_AS_SECS,
+        ]
+        .map(Self::secs_to_ms);
+        let origin = self.week_origin();
+
+        let start = self.to_ms(range.start.unix_timestamp() as u64);
+        let end = self.to_ms(range.end.unix_timestamp() as u64);
+        let first_year = match self.is_year_start(start) {
+            true => start,
+            false => self.year_start(range.start.year() + 1),
+        };
+        let last_year = self.year_start(range.end.year());
+
+        if first_year >= last_year {
+            return self.split_aligned_ranges_by(range, origin, &units);
+        }
+
+        // whole calendar years are answered by the years wheel
+        let mut ranges = WheelRanges::default();
+        if start < first_year {
+            let head = WheelRange::new_unchecked(start, first_year);
+            ranges.extend(self.split_aligned_ranges_by(head, origin, &units)?);
+        }
+        ranges.push(WheelRange::new_unchecked(first_year, last_year));
+        if last_year < end {
+            let tail = WheelRange::new_unchecked(last_year, end);
+            ranges.extend(self.split_aligned_ranges_by(tail, origin, &units)?);
+        }
+        Some(ranges)
+    }
+
+    // Returns the aligned sub-ranges of the range based on the configured year length
+    #[inline]
+    fn aligned_ranges(&self, range: WheelRange, wheel_start: u64) -> Option<WheelRanges> {
+        if self.conf.calendar_years {
+            self.split_calendar_ranges(range)
+        } else {
+            self.split_aligned_ranges(range, wheel_start)
+        }
+    }
+
+    // Splits the range into sub-ranges aligned to the given units (in milliseconds) relative to the origin
+    fn split_aligned_ranges_by(
+        &self,
+        range: WheelRange,
+        wheel_start: u64,
+        units: &[u64],
+    ) -> Option<WheelRanges> {
+        let mut ranges = WheelRanges::default();
+        let end = self.to_ms(range.end.unix_timestamp() as u64);
+        let mut current = self.to_ms(range.start.unix_timestamp() as u64);
+
+        while current < end {
+            let offset = current.checked_sub(wheel_start)?;
+
+            // coarsest unit that the current position is aligned to and that fits within the range
+            let idx = units
+                .iter()
+                .rposition(|unit| offset % unit == 0 && current + unit <= end)?;
+            let unit = units[idx];
+
+            // avoid crossing the next alignment point of a coarser unit
+            let limit = match units.get(idx + 1) {
+                Some(coarser) if offset % coarser != 0 => {
+                    cmp::min(end, current + (coarser - offset % coarser))
+                }
+                _ => end,
+            };
+            let next = current + (limit - current) / unit * unit;
+
+            ranges.push(WheelRange {
+                start: self.to_offset_date(current).ok()?,
+                end: self.to_offset_date(next).ok()?,
+            });
+            current = next;
+        }
+
+        Some(ranges)
+    }
+
+    // helper fn to calculate the next aligned end date
+    #[inline]
+    fn new_curr_end(current_start: OffsetDateTime, end: OffsetDateTime) -> OffsetDateTime {
+        let second = current_start.second();
+        let minute = current_start.minute();
+        let hour = current_start.hour();
+        let day = current_start.day();
+
+        // based on the lowest granularity figure out the duration to next alignment point
+        let next = if second > 0 {
+            time::Duration::seconds(60 - second as i64)
+        } else if minute > 0 {
+            time::Duration::minutes(60 - minute as i64)
+        } else if hour > 0 {
+            time::Duration::hours(24 - hour as i64)
+        } else if day > 0 {
+            let year = current_start.year();
+            let month = current_start.month() as u8;
+
+            // Calculate days in the current month
+            let days_in_current_month = Self::days_in_month(year, month);
+            let remaining_days_in_month = days_in_current_month as i64 - day as i64;
+
+            // Determine if we should move to the next month
+            let days_to_add = if remaining_days_in_month > 0 {
+                remaining_days_in_month
+            } else {
+                1 // Move to the next day, which is the first day of the next month
+            };
+
+            time::Duration::days(days_to_add)
+        } else {
+            unimplemented!("Weeks and Years not supported yet");
+        };
+
+        // TODO(perf): time::checked_add is somewhat expensive.
+        let next_aligned = current_start + next;
+
+        if next_aligned > end {
+            // if jumping too far: check the remaining duration between current_start and end
+            let rem = end - current_start;
+            let rem_secs = rem.whole_seconds();
+            let rem_mins = rem.whole_minutes();
+            let rem_hours = rem.whole_hours();
+            let rem_days = rem.whole_days();
+
+            let remains = [
+                (rem_secs, time::Duration::seconds(rem_secs)),
+                (rem_mins, time::Duration::minutes(rem_mins)),
+                (rem_hours, time::Duration::hours(rem_hours)),
+                (rem_days, time::Duration::days(rem_days)),
+            ];
+
+            // Jump with lowest remaining resolution
+            let idx = remains
+                .iter()
+                .rposition(|&(rem, _)| rem > 0)
+                .unwrap_or(remains.len() - 1);
+
+            current_start + remains[idx].1
+        } else {
+            next_aligned
+        }
+    }
+
+    // helper method to get the days of a month
+    #[inline]
+    fn days_in_month(year: i32, month: u8) -> u8 {
+        const DAYS_30: [u8; 4] = [4, 6, 9, 11];
+        const DAYS_31: [u8; 7] = [1, 3, 5, 7, 8, 10, 12];
+
+        if DAYS_31.contains(&month) {
+            31
+        } else if DAYS_30.contains(&month) {
+            30
+        } else {
+            // This must be February
+            if (year % 4 == 0 && year % 100 != 0) || (year % 400 == 0) {
+                29
+            } else {
+                28
+            }
+        }
+    }
+
+    /// Takes a set of wheel ranges and creates a CombinedAggregation plan
+    #[doc(hidden)]
+    #[inline]
+    pub fn combined_aggregation_plan(&self, ranges: WheelRanges) -> Option<CombinedAggregation> {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.combined_aggregation_plan);
+
+        let mut aggregations = WheelAggregations::default();
+
+        for range in ranges.into_iter() {
+            match self.wheel_aggregation_plan(range) {
+                Some(plan) => aggregations.push(plan),
+                None => return None, // early return if a single aggregation cannot be executed
+            }
+        }
+
+        // function that returns a score of the wheel range which is used during sorting
+        let granularity_score = |start: &OffsetDateTime, end: &OffsetDateTime| {
+            let (sh, sm, ss) = start.time().as_hms();
+            let (eh, em, es) = end.time().as_hms();
+            if ss > 0 || es > 0 {
+                // second rank
+                0
+            } else if sm > 0 || em > 0 {
+                // minute rank
+                1
+            } else if sh > 0 || eh > 0 {
+                // hour rank
+                2
+            } else {
+                // day rank (00:00:00 - 00:00:00)
+                3
+            }
+        };
+
+        // Sort ranges based on lowest granularity in order to execute ranges in order of granularity
+        // so that the execution visits wheels sequentially and not randomly.
+        // Non-commutative aggregators must be combined in time order so the ranges are kept as is.
+        if A::commutative() {
+            aggregations.sort_unstable_by(|a, b| {
+                let a_score = granularity_score(&a.range.start, &a.range.end);
+                let b_score = granularity_score(&b.range.start, &b.range.end);
+                a_score.cmp(&b_score)
+            });
+        }
+
+        Some(CombinedAggregation::from(aggregations))
+    }
+
+    // Performs a inverse landmark aggregation
+    fn inverse_landmark_aggregation(
+        &self,
+        wheel_aggregations: WheelAggregations,
+    ) -> (A::PartialAggregate, usize) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.inverse_landmark);
+
+        // get landmark partial
+        let (landmark, lcost) = self.analyze_landmark();
+        let combine_inverse = A::combine_inverse().unwrap(); // assumed to be safe as it has been verified by the plan generation
+
+        wheel_aggregations.into_iter().fold(
+            (landmark.unwrap_or(A::IDENTITY), lcost),
+            |mut acc, plan| {
+                let cost = plan.cost();
+                let agg = self.wheel_aggregation(plan).unwrap_or(A::IDENTITY);
+                acc.0 = combine_inverse(acc.0, agg);
+                acc.1 += cost;
+                acc
+            },
+        )
+    }
+
+    /// Performs a given Combined Aggregation and returns the partial aggregate and the cost of the operation
+    #[inline]
+    fn combined_aggregation(
+        &self,
+        combined: CombinedAggregation,
+    ) -> (Option<A::PartialAggregate>, usize) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.combined_aggregation);
+
+        let cost = combined.cost();
+
+        // The aggregations may have been reordered for locality.
+        // Non-commutative aggregators execute them in plan order but combine the results in time order.
+        if !A::commutative() {
+            let mut partials: Vec<(OffsetDateTime, A::PartialAggregate)> = combined
+                .aggregations
+                .into_iter()
+                .filter_map(|wheel_agg| {
+                    let start = wheel_agg.range.start;
+                    self.wheel_aggregation(wheel_agg).map(|agg| (start, agg))
+                })
+                .collect();
+            partials.sort_unstable_by_key(|(start, _)| *start);
+
+            let agg = partials.into_iter().map(|(_, agg)| agg).reduce(A::combine);
+            return (agg, cost);
+        }
+
+        let agg = combined
+            .aggregations
+            .into_iter()
+            .fold(None, |mut acc, wheel_agg| {
+                match self.wheel_aggregation(wheel_agg) {
+                    Some(agg) => {
+                        combine_or_insert::<A>(&mut acc, agg);
+                        acc
+                    }
+                    None => acc,
+                }
+            });
+        (agg, cost)
+    }
+    /// Combines partial aggregates within [start, end) using the lowest time granularity wheel
+    ///
+    /// Note that start date is inclusive while end date is exclusive.
+    /// For instance, the range [16:00:50, 16:01:00) refers to the 10 remaining seconds of the minute
+    ///
+    /// Returns `None` if the range cannot be answered by the wheel
+    fn wheel_aggregation(&self, agg: WheelAggregation) -> Option<A::PartialAggregate> {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.wheel_aggregation);
+
+        self.record_access(agg.granularity, agg.cost());
+
+        let (start, end) = agg.slots;
+        self.combine_slot_range(agg.granularity, start, end)
+    }
+
+    /// Combines the slots of the given granularity wheel that are [start_age, end_age) slots old
+    ///
+    /// Age `0` refers to the most recent slot of the wheel. Unlike [Self::combine_range], no date math
+    /// is involved and the slots are folded directly using the current layout of the wheel (e.g., prefix-sum).
+    ///
+    /// Returns `None` if the wheel is not initialized or the slots are out of bounds.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), Some(2), Some(3)]);
+    ///
+    /// // the two most recent seconds
+    /// assert_eq!(haw.combine_slot_range(Granularity::Second, 0, 2), Some(5));
+    /// assert_eq!(haw.combine_slot_range(Granularity::Second, 1, 3), Some(3));
+    /// assert_eq!(haw.combine_slot_range(Granularity::Second, 2, 4), None);
+    /// ```
+    #[inline]
+    pub fn combine_slot_range(
+        &self,
+        granularity: Granularity,
+        start_age: usize,
+        end_age: usize,
+    ) -> Option<A::PartialAggregate> {
+        self.wheel(granularity)
+            .filter(|wheel| start_age <= end_age && end_age <= wheel.len())
+            .and_then(|wheel| wheel.combine_range(start_age..end_age))
+    }
+
+    /// Returns the partial aggregate in the given time interval [(watermark - `duration`), watermark)
+    ///
+    /// Internally the [Self::combine_range] function is used to produce the result
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// // Init a HAW with time 0
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let deltas = vec![Some(10), None, Some(50), None];
+    /// haw.delta_advance(deltas);
+    ///
+    /// assert_eq!(haw.interval(1.seconds()), Some(0));
+    /// assert_eq!(haw.interval(2.seconds()), Some(50));
+    /// assert_eq!(haw.interval(3.seconds()), Some(50));
+    /// assert_eq!(haw.interval(4.seconds()), Some(60));
+    /// ```
+    pub fn interval(&self, dur: Duration) -> Option<A::PartialAggregate> {
+        self.interval_with_stats(dur).0
+    }
+
+    /// Returns the partial aggregate in the given time interval [(watermark - `duration`), watermark)
+    ///
+    /// Unlike [Self::interval], returns an error if the watermark of the wheel cannot be represented as a date.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, HawConf, QueryError, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// let conf = HawConf::default().with_watermark(u64::MAX / 2);
+    /// let haw: Haw<U32SumAggregator> = Haw::new(conf);
+    /// assert_eq!(
+    ///     haw.try_interval(1.seconds()),
+    ///     Err(QueryError::InvalidTimestamp { ts_ms: u64::MAX / 2 })
+    /// );
+    /// ```
+    pub fn try_interval(&self, dur: Duration) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.now()?;
+        Ok(self.interval(dur))
+    }
+
+    /// Returns the partial aggregate of the `n` most recent complete slots of the given granularity
+    ///
+    /// Unlike [Self::interval], the slot that is still in progress at the watermark (e.g., the current minute)
+    /// is excluded, which avoids skewing rate calculations with an incomplete bucket. The result covers
+    /// `[(wheel watermark - n slots), wheel watermark)` where the wheel watermark is the end of the most
+    /// recently sealed slot of the granularity.
+    ///
+    /// Returns `None` if the wheel has fewer than `n` slots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// // 2 minutes and 30 seconds
+    /// haw.delta_advance(vec![Some(1); 150]);
+    ///
+    /// assert_eq!(haw.interval_complete(Granularity::Minute, 2), Some(120));
+    /// assert_eq!(haw.interval_complete(Granularity::Second, 10), Some(10));
+    /// assert_eq!(haw.interval_complete(Granularity::Minute, 3), None);
+    /// ```
+    #[inline]
+    pub fn interval_complete(
+        &self,
+        granularity: Granularity,
+        n: usize,
+    ) -> Option<A::PartialAggregate> {
+        self.wheel(granularity)
+            .and_then(|wheel| wheel.interval(n).0)
+    }
+
+    /// Returns the partial aggregate in the given time interval and lowers the result
+    ///
+    /// Internally the [Self::combine_range] function is used to produce the result
+    ///
+    /// See [Self::interval] for example.
+    pub fn interval_and_lower(&self, dur: Duration) -> Option<A::Aggregate> {
+        self.interval(dur).map(|partial| A::lower(partial))
+    }
+
+    /// Returns the partial aggregate in the given time interval
+    #[inline]
+    pub fn interval_with_stats(&self, dur: Duration) -> (Option<A::PartialAggregate>, usize) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.interval);
+
+        let Ok(to) = self.now() else {
+            return (None, 0);
+        };
+        let from = to.saturating_sub(time::Duration::seconds(dur.whole_seconds()));
+
+        self.combine_range_inner(WheelRange {
+            start: from,
+            end: to,
+        })
+    }
+
+    /// Returns the partial aggregates for multiple intervals ending at the current watermark
+    ///
+    /// Results are returned in the same order as the given durations. Since longer intervals contain
+    /// shorter ones, intervals are computed in ascending order and reuse the previous result whenever
+    /// aggregating the remaining sub-range is cheaper than executing the interval from scratch.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let deltas = (0..50).map(|_| Some(1)).collect::<Vec<_>>();
+    /// haw.delta_advance(deltas);
+    ///
+    /// let results = haw.interval_multi(&[40.seconds(), 10.seconds()]);
+    /// assert_eq!(results, vec![Some(40), Some(10)]);
+    /// ```
+    pub fn interval_multi(&self, durations: &[Duration]) -> Vec<Option<A::PartialAggregate>> {
+        self.interval_multi_with_stats(durations).0
+    }
+
+    /// Returns the partial aggregates for multiple intervals ending at the current watermark
+    ///
+    /// Unlike [Self::interval_multi], returns an error if the watermark of the wheel cannot be represented as a date.
+    pub fn try_interval_multi(
+        &self,
+        durations: &[Duration],
+    ) -> Result<Vec<Option<A::PartialAggregate>>, QueryError> {
+        self.now()?;
+        Ok(self.interval_multi(durations))
+    }
+
+    /// Returns the partial aggregates for multiple intervals together with the total cost of computing them
+    ///
+    /// See [Self::interval_multi] for more information.
+    pub fn interval_multi_with_stats(
+        &self,
+        durations: &[Duration],
+    ) -> (Vec<Option<A::PartialAggregate>>, usize) {
+        let (Ok(to), Ok(wheel_start)) = (self.now(), self.to_offset_date(self.wheel_start()))
+        else {
+            return (vec![None; durations.len()], 0);
+        };
+
+        let mut order: Vec<usize> = (0..durations.len()).collect();
+        order.sort_unstable_by_key(|&idx| durations[idx]);
+
+        let mut results = vec![None; durations.len()];
+        let mut total_cost = 0;
+        // the longest interval computed so far and its partial aggregate
+        let mut previous: Option<(OffsetDateTime, A::PartialAggregate)> = None;
+
+        for idx in order {
+            let from = to.saturating_sub(time::Duration::seconds(durations[idx].whole_seconds()));
+            let full = WheelRange {
+                start: from,
+                end: to,
+            };
+
+            let (result, cost) = match previous {
+                // the previous interval already covers the range
+                Some((prev_from, partial)) if prev_from <= from || prev_from <= wheel_start => {
+                    (Some(partial), 0)
+                }
+                Some((prev_from, partial)) => {
+                    let rest = WheelRange {
+                        start: from,
+                        end: prev_from,
+                    };
+                    let full_plan = self.create_exec_plan(full);
+                    let rest_plan = self.create_exec_plan(rest);
+                    match (full_plan, rest_plan) {
+                        (Some(full_plan), Some(rest_plan))
+                            if rest_plan.cost() + 1 < full_plan.cost() =>
+                        {
+                            // the rest [from, prev_from) precedes the previous interval in time
+                            let (rest, cost) = self.execute_plan(rest_plan);
+                            (rest.map(|rest| A::combine(rest, partial)), cost + 1)
+                        }
+                        (Some(full_plan), _) => self.execute_plan(full_plan),
+                        (None, _) => (None, 0),
+                    }
+                }
+                None => self.combine_range_inner(full),
+            };
+
+            total_cost += cost;
+            results[idx] = result;
+            if let Some(partial) = result {
+                previous = Some((from, partial));
+            }
+        }
+
+        (results, total_cost)
+    }
+
+    /// Executes a Landmark Window that combines total partial aggregates across all wheels into a full-wheel result
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// // Init a HAW with time 0
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// // Advance HAW with 1800 (total 30min) deltas with value 1
+    /// let deltas = (0..1800).map(Some).collect::<Vec<_>>();
+    /// haw.delta_advance(deltas);
+    ///
+    /// // get the full result between time [0, watermark).
+    /// assert_eq!(haw.landmark(), Some(1619100));
+    /// ```
+    #[inline]
+    pub fn landmark(&self) -> Option<A::PartialAggregate> {
+        self.analyze_landmark().0
+    }
+
+    /// Executes a Landmark Window that combines total partial aggregates across all wheels and lowers the result
+    ///
+    /// See [Self::landmark] for an example.
+    #[inline]
+    pub fn landmark_and_lower(&self) -> Option<A::Aggregate> {
+        self.landmark().map(|partial| A::lower(partial))
+    }
+
+    /// Returns the total partial aggregate of each wheel without combining them together
+    ///
+    /// The totals are ordered from the lowest granularity (seconds) to the highest (years), see [Granularity].
+    /// Wheels that have not been initialized yet return `None`. Useful for inspecting how data is distributed
+    /// across the wheels, whereas [Self::landmark] combines the totals into a single result.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, Granularity, aggregator::sum::U32SumAggregator};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 90]);
+    ///
+    /// let totals = haw.granularity_totals();
+    /// assert_eq!(totals[Granularity::Second as usize], Some(30));
+    /// assert_eq!(totals[Granularity::Minute as usize], Some(60));
+    /// assert_eq!(totals[Granularity::Hour as usize], None);
+    /// ```
+    pub fn granularity_totals(&self) -> [Option<A::PartialAggregate>; 6] {
+        [
+            self.seconds_wheel.total(),
+            self.minutes_wheel.total(),
+            self.hours_wheel.total(),
+            self.days_wheel.total(),
+            self.weeks_wheel.total(),
+            self.years_wheel.total(),
+        ]
+    }
+
+    /// Executes a Landmark Window that combines total partial aggregates across all wheels and returns the aggregate cost
+    #[inline]
+    pub(crate) fn analyze_landmark(&self) -> (Option<A::PartialAggregate>, usize) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.landmark);
+
+        let wheels = self.landmark_totals();
+        if A::commutative() {
+            Self::reduce(wheels)
+        } else {
+            // combine from the oldest data (years) to the most recent (seconds)
+            Self::reduce(wheels.into_iter().rev())
+        }
+    }
+
+    // Returns the wheel totals combined by a landmark window ordered from seconds to years
+    #[inline]
+    fn landmark_totals(&self) -> [Option<A::PartialAggregate>; 6] {
+        if self.conf.calendar_years {
+            // the days of the current calendar year replace the days and weeks wheels
+            [
+                self.seconds_wheel.total(),
+                self.minutes_wheel.total(),
+                self.hours_wheel.total(),
+                self.calendar_year.as_ref().map(|year| year.total),
+                None,
+                self.years_wheel.total(),
+            ]
+        } else {
+            self.granularity_totals()
+        }
+    }
+
+    #[inline]
+    fn reduce(
+        partial_aggs: impl IntoIterator<Item = Option<A::PartialAggregate>>,
+    ) -> (Option<A::PartialAggregate>, usize) {
+        let mut combines = 0;
+        let agg = partial_aggs
+            .into_iter()
+            .reduce(|acc, b| match (acc, b) {
+                (Some(curr), Some(agg)) => {
+                    combines += 1;
+                    Some(A::combine(curr, agg))
+                }
+                (None, Some(_)) => b,
+                _ => acc,
+            })
+            .flatten();
+        (agg, combines)
+    }
+    /// Schedules a timer to fire once the HAW has reached the specified time.
+    ///
+    /// Note that scheduled timers are not serialized together with the wheel.
+    /// Use [Self::pending_timers] to persist their times and re-schedule them after deserializing.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// // Init a HAW with time 0
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let _ = haw.schedule_once(5000, move |haw: &Haw<_>| {
+    ///    println!("{:?}", haw.interval(5.seconds()));
+    /// });
+    ///
+    /// ```
+    #[cfg(feature = "timer")]
+    pub fn schedule_once(
+        &self,
+        time: u64,
+        f: impl Fn(&Haw<A>) + 'static,
+    ) -> Result<(), TimerError<TimerAction<A>>> {
+        self.timer
+            .write()
+            .schedule_at(time, TimerAction::Oneshot((time, Box::new(f))))
+    }
+
+    /// Returns the times of all pending one-shot timers in ascending order
+    ///
+    /// Timers are skipped during serialization as their closures cannot be serialized,
+    /// so the returned times can be persisted alongside the wheel and re-scheduled after load.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, aggregator::sum::U32SumAggregator};
+    ///
+    /// let haw: Haw<U32SumAggregator> = Haw::default();
+    /// let _ = haw.schedule_once(5000, |_| {});
+    /// let _ = haw.schedule_once(2000, |_| {});
+    /// assert_eq!(haw.pending_timers(), vec![2000, 5000]);
+    /// ```
+    #[cfg(feature = "timer")]
+    pub fn pending_timers(&self) -> Vec<u64> {
+        let mut times: Vec<u64> = self
+            .timer
+            .read()
+            .entries()
+            .filter(|action| matches!(action, TimerAction::Oneshot(_)))
+            .map(TimerAction::at)
+            .collect();
+        times.sort_unstable();
+        times
+    }
+    /// Schedules a timer to fire repeatedly
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, NumericalDuration, aggregator::sum::U32SumAggregator};
+    ///
+    /// // Init a HAW with time 0
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// let _ = haw.schedule_repeat(5000, 5.seconds(), move |haw: &Haw<_>| {
+    ///    println!("{:?}", haw.interval(5.seconds()));
+    /// });
+    ///
+    /// ```
+    #[cfg(feature = "timer")]
+    pub fn schedule_repeat(
+        &self,
+        at: u64,
+        interval: Duration,
+        f: impl Fn(&Haw<A>) + 'static,
+    ) -> Result<(), TimerError<TimerAction<A>>> {
+        self.timer
+            .write()
+            .schedule_at(at, TimerAction::Repeat((at, interval, Box::new(f))))
+    }
+
+    // Returns true if the timestamp is the start of a calendar year (UTC)
+    #[inline]
+    fn is_year_start(&self, ts: u64) -> bool {
+        (ts + self.conf.epoch_offset).is_multiple_of(DAY_TICK_MS)
+            && self
+                .to_offset_date(ts)
+                .is_ok_and(|date| date.ordinal() == 1)
+    }
+
+    // Returns the timestamp in milliseconds of January 1 (UTC) of the given year
+    #[inline]
+    fn year_start(&self, year: i32) -> u64 {
+        time::Date::from_calendar_date(year, time::Month::January, 1)
+            .map(|date| date.midnight().assume_utc().unix_timestamp())
+            .map_or(0, |secs| self.to_ms(cmp::max(secs, 0) as u64))
+    }
+
+    // Rolls up the days of the completed calendar year into the years wheel
+    fn rollover_calendar_year(&mut self) {
+        let year = self.calendar_year.take().unwrap_or(WheelSlot::new(None));
+        let years = self.years_wheel.get_or_insert();
+        years.insert_slot(year.rollup(Granularity::Year));
+
+        // the wheel derives eviction timestamps from a fixed tick size so only its partial aggregate is used
+        let evicted = match self.eviction_hook {
+            Some(_) => years
+                .next_eviction()
+                .map(|(_, partial)| (years.len(), partial)),
+            None => None,
+        };
+
+        // tick but ignore full rotations as this is the last hierarchy
+        let _ = years.tick();
+
+        // calendar years differ in length so the watermark does not advance by a fixed tick size
+        years.set_watermark(self.watermark);
+
+        if let Some((len, partial)) = evicted {
+            // the evicted slot is the oldest retained calendar year (see Self::oldest_timestamp)
+            let year = self
+                .to_offset_date(self.watermark)
+                .map_or(1970, |date| date.year());
+            let timestamp = cmp::max(self.year_start(year - len as i32), self.conf.watermark);
+            if let Some(hook) = self.eviction_hook.as_mut() {
+                hook(Granularity::Year, timestamp, partial);
+            }
+        }
+    }
+
+    // Ticks a single wheel and passes the slot it evicts (if any) to the eviction hook
+    #[inline]
+    fn tick_level(
+        wheel: &mut Wheel<A>,
+        granularity: Granularity,
+        hook: &mut Option<EvictionHook<A>>,
+    ) -> Option<WheelSlot<A>> {
+        if let Some(hook) = hook.as_mut() {
+            if let Some((timestamp, partial)) = wheel.next_eviction() {
+                hook(granularity, timestamp, partial);
+            }
+        }
+        wheel.tick()
+    }
+
+    /// Tick the wheel by a single unit (second)
+    ///
+    /// In the worst case, a tick may cause a rotation of all the wheels in the hierarchy.
+    #[inline]
+    fn tick(&mut self, partial_opt: Option<A::PartialAggregate>) {
+        #[cfg(feature = "profiler")]
+        profile_scope!(&self.stats.tick);
+
+        self.watermark += Self::SECOND_AS_MS;
+        self.cache.clear();
+
+        // milliseconds of seconds sealed without a milliseconds writer are left empty
+        if let Some(millis) = self.millis_wheel.as_mut() {
+            let unfed = millis.watermark();
+            while millis.watermark() < self.watermark {
+                millis.insert_slot(WheelSlot::new(None));
+                let _ = millis.tick();
+            }
+            if unfed < self.watermark {
+                self.mark_unfed_millis(unfed, self.watermark);
+            }
+        }
+
+        let suppress_identity = self.conf.suppress_identity_fill && A::identity_is_noop();
+        let seconds = self.seconds_wheel.get_or_insert();
+
+        // if 'None', insert the Identity value and mark the slot as unoccupied
+        let rotation = match partial_opt {
+            // evicted slots are only observable through the eviction hook
+            None if suppress_identity
+                && self.eviction_hook.is_none()
+                && seconds.skips_empty_slot() =>
+            {
+                seconds.tick_empty()
+            }
+            None if suppress_identity => {
+                seconds.insert_empty_slot();
+                Self::tick_level(seconds, Granularity::Second, &mut self.eviction_hook)
+            }
+            _ => {
+                seconds.insert_slot(WheelSlot::new(partial_opt));
+                Self::tick_level(seconds, Granularity::Second, &mut self.eviction_hook)
+            }
+        };
+
+        // full rotation of seconds wheel
+        if let Some(rot_data) = rotation {
+            // insert 60 seconds worth of partial aggregates into minute wheel and then tick it
+            let minutes = self.minutes_wheel.get_or_insert();
+
+            minutes.insert_slot(rot_data.rollup(Granularity::Minute));
+
+            // full rotation of minutes wheel
+            if let Some(rot_data) =
+                Self::tick_level(minutes, Granularity::Minute, &mut self.eviction_hook)
+            {
+                // insert 60 minutes worth of partial aggregates into hours wheel and then tick it
+                let hours = self.hours_wheel.get_or_insert();
+
+                hours.insert_slot(rot_data.rollup(Granularity::Hour));
+
+                // full rotation of hours wheel
+                if let Some(rot_data) =
+                    Self::tick_level(hours, Granularity::Hour, &mut self.eviction_hook)
+                {
+                    // insert 24 hours worth of partial aggregates into days wheel and then tick it
+                    let days = self.days_wheel.get_or_insert();
+                    let day = rot_data.rollup(Granularity::Day);
+
+                    // calendar years are rolled up from the completed days instead of the weeks wheel
+                    if self.conf.calendar_years {
+                        let year = self.calendar_year.get_or_insert(WheelSlot {
+                            total: A::IDENTITY,
+                            occupied: false,
+                        });
+                        year.total = A::combine(year.total, day.total);
+                        year.occupied |= day.occupied;
+                    }
+                    days.insert_slot(day);
+
+                    // full rotation of days wheel
+                    if let Some(rot_data) =
+                        Self::tick_level(days, Granularity::Day, &mut self.eviction_hook)
+                    {
+                        // insert 7 days worth of partial aggregates into weeks wheel and then tick it
+                        let weeks = self.weeks_wheel.get_or_insert();
+
+                        weeks.insert_slot(rot_data.rollup(Granularity::Week));
+
+                        // full rotation of weeks wheel (discarded with calendar years)
+                        let rotation =
+                            Self::tick_level(weeks, Granularity::Week, &mut self.eviction_hook)
+                                .filter(|_| !self.conf.calendar_years);
+                        if let Some(rot_data) = rotation {
+                            // insert 1 years worth of partial aggregates into year wheel and then tick it
+                            let years = self.years_wheel.get_or_insert();
+                            years.insert_slot(rot_data.rollup(Granularity::Year));
+
+                            // tick but ignore full rotations as this is the last hierarchy
+                            let _ =
+                                Self::tick_level(years, Granularity::Year, &mut self.eviction_hook);
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.conf.calendar_years && self.is_year_start(self.watermark) {
+            self.rollover_calendar_year();
+        }
+
+        // Fire any outgoing timers
+        #[cfg(feature = "timer")]
+        {
+            let mut timer = self.timer.write();
+
+            for action in timer.advance_to(self.watermark) {
+                match action {
+                    TimerAction::Oneshot((_, udf)) => {
+                        udf(self);
+                    }
+                    TimerAction::Repeat((at, interval, udf)) => {
+                        udf(self);
+                        let new_at = at + interval.whole_milliseconds() as u64;
+                        let _ =
+                            timer.schedule_at(new_at, TimerAction::Repeat((new_at, interval, udf)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns a reference to the wheel of the given granularity
+    pub fn wheel(&self, granularity: Granularity) -> Option<&Wheel<A>> {
+        match granularity {
+            Granularity::Second => self.seconds_wheel.as_ref(),
+            Granularity::Minute => self.minutes_wheel.as_ref(),
+            Granularity::Hour => self.hours_wheel.as_ref(),
+            Granularity::Day => self.days_wheel.as_ref(),
+            Granularity::Week => self.weeks_wheel.as_ref(),
+            Granularity::Year => self.years_wheel.as_ref(),
+        }
+    }
+
+    /// Returns the start timestamps in milliseconds of the slots of the given granularity wheel
+    ///
+    /// Timestamps are returned from the most recent slot to the oldest one.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), Some(2), Some(3)]);
+    ///
+    /// let timestamps: Vec<u64> = haw.granularity_timestamps(Granularity::Second).collect();
+    /// assert_eq!(timestamps, vec![2000, 1000, 0]);
+    /// ```
+    pub fn granularity_timestamps(
+        &self,
+        granularity: Granularity,
+    ) -> impl Iterator<Item = u64> + '_ {
+        self.wheel(granularity)
+            .into_iter()
+            .flat_map(|wheel| (0..wheel.len()).map(|age| wheel.slot_timestamp(age)))
+    }
+
+    /// Returns the start timestamp and partial aggregate of the largest slot within the last `lookback` slots of a granularity
+    ///
+    /// Answers queries such as "which minute in the last hour had the highest sum".
+    /// Ties are resolved in favour of the most recent slot. Returns `None` if the wheel is not initialized or has no slots.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), Some(5), Some(3)]);
+    ///
+    /// assert_eq!(haw.peak_slot(Granularity::Second, 3), Some((1000, 5)));
+    /// ```
+    pub fn peak_slot(
+        &self,
+        granularity: Granularity,
+        lookback: usize,
+    ) -> Option<(u64, A::PartialAggregate)>
+    where
+        A::PartialAggregate: PartialOrd,
+    {
+        self.peak_slot_by(granularity, lookback, |a, b| {
+            a.partial_cmp(b).unwrap_or(cmp::Ordering::Equal)
+        })
+    }
+
+    /// Returns the start timestamp and partial aggregate of the largest slot according to `compare`
+    ///
+    /// See [Self::peak_slot] for more information.
+    pub fn peak_slot_by<F>(
+        &self,
+        granularity: Granularity,
+        lookback: usize,
+        mut compare: F,
+    ) -> Option<(u64, A::PartialAggregate)>
+    where
+        F: FnMut(&A::PartialAggregate, &A::PartialAggregate) -> cmp::Ordering,
+    {
+        let wheel = self.wheel(granularity)?;
+        let mut peak: Option<(u64, A::PartialAggregate)> = None;
+
+        for age in 0..cmp::min(lookback, wheel.len()) {
+            let Some(partial) = wheel.at(age) else {
+                continue;
+            };
+            if peak
+                .as_ref()
+                .is_none_or(|(_, max)| compare(partial, max) == cmp::Ordering::Greater)
+            {
+                peak = Some((wheel.slot_timestamp(age), *partial));
+            }
+        }
+        peak
+    }
+
+    /// Returns for each of the last `lookback` slots of a granularity whether data was inserted into it
+    ///
+    /// Slots are ordered from most recent to oldest. Seconds without any insertions are stored as
+    /// [Aggregator::IDENTITY] and are reported as `false`, while coarser slots are occupied if any of the
+    /// slots they were rolled up from were. Returns an empty vec if the wheel is not initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(1), None, Some(0)]);
+    ///
+    /// assert_eq!(haw.occupancy(Granularity::Second, 3), vec![true, false, true]);
+    /// ```
+    pub fn occupancy(&self, granularity: Granularity, lookback: usize) -> Vec<bool> {
+        self.wheel(granularity)
+            .map(|wheel| {
+                (0..cmp::min(lookback, wheel.len()))
+                    .map(|age| wheel.is_occupied(age).unwrap_or(false))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the change between adjacent slots of the given granularity within the last `lookback` slots
+    ///
+    /// Each element is the [Aggregator::combine_inverse] of a slot against the slot preceding it in time,
+    /// ordered from the most recent slot to the oldest one. An element is `None` if there is no preceding slot.
+    ///
+    /// Returns an empty vector if the aggregator does not support `combine_inverse` or the wheel is not initialized.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Granularity, Haw, aggregator::sum::U64SumAggregator};
+    ///
+    /// let mut haw: Haw<U64SumAggregator> = Haw::default();
+    /// haw.delta_advance([Some(10), Some(15), Some(30)]);
+    ///
+    /// assert_eq!(haw.deltas(Granularity::Second, 3), vec![Some(15), Some(5), None]);
+    /// ```
+    pub fn deltas(
+        &self,
+        granularity: Granularity,
+        lookback: usize,
+    ) -> Vec<Option<A::PartialAggregate>> {
+        match (A::combine_inverse(), self.wheel(granularity)) {
+            (Some(inverse), Some(wheel)) => (0..cmp::min(lookback, wheel.len()))
+                .map(|age| match (wheel.at(age), wheel.at(age + 1)) {
+                    (Some(current), Some(previous)) => Some(inverse(*current, *previous)),
+                    _ => None,
+                })
+                .collect(),
+            _ => Vec::new(),
+        }
+    }
+
+    /// Returns a reference to the milliseconds wheel if configured (see [HawConf::with_milliseconds])
+    pub fn milliseconds(&self) -> Option<&Wheel<A>> {
+        self.millis_wheel.as_ref()
+    }
+
+    /// Returns a reference to the seconds wheel
+    pub fn seconds(&self) -> Option<&Wheel<A>> {
+        self.seconds_wheel.as_ref()
+    }
+    /// Returns an unchecked reference to the seconds wheel
+    pub fn seconds_unchecked(&self) -> &Wheel<A> {
+        self.seconds_wheel.as_ref().unwrap()
+    }
+
+    /// Returns a reference to the minutes wheel
+    pub fn minutes(&self) -> Option<&Wheel<A>> {
+        self.minutes_wheel.as_ref()
+    }
+    /// Returns an unchecked reference to the minutes wheel
+    pub fn minutes_unchecked(&self) -> &Wheel<A> {
+        self.minutes_wheel.as_ref().unwrap()
+    }
+    /// Returns a reference to the hours wheel
+    pub fn hours(&self) -> Option<&Wheel<A>> {
+        self.hours_wheel.as_ref()
+    }
+    /// Returns an unchecked reference to the hours wheel
+    pub fn hours_unchecked(&self) -> &Wheel<A> {
+        self.hours_wheel.as_ref().unwrap()
+    }
+    /// Returns a reference to the days wheel
+    pub fn days(&self) -> Option<&Wheel<A>> {
+        self.days_wheel.as_ref()
+    }
+    /// Returns an unchecked reference to the days wheel
+    pub fn days_unchecked(&self) -> &Wheel<A> {
+        self.days_wheel.as_ref().unwrap()
+    }
+
+    /// Returns a reference to the weeks wheel
+    pub fn weeks(&self) -> Option<&Wheel<A>> {
+        self.weeks_wheel.as_ref()
+    }
+
+    /// Returns an unchecked reference to the weeks wheel
+    pub fn weeks_unchecked(&self) -> &Wheel<A> {
+        self.weeks_wheel.as_ref().unwrap()
+    }
+
+    /// Returns a reference to the years wheel
+    pub fn years(&self) -> Option<&Wheel<A>> {
+        self.years_wheel.as_ref()
+    }
+    /// Returns a reference to the years wheel
+    pub fn years_unchecked(&self) -> &Wheel<A> {
+        self.years_wheel.as_ref().unwrap()
+    }
+
+    /// Merges two wheels
+    ///
+    /// Note that the time in `other` may be advanced and thus change state
+    ///
+    /// The wheels may use different retention policies and data layouts as long as the tick sizes
+    /// of each granularity match. Prefix-enabled wheels are normalized to regular slots before combining.
+    /// Returns an error without modifying either wheel if the configurations are incompatible.
+    pub(crate) fn merge(&mut self, other: &mut Self) -> Result<(), MergeError> {
+        self.check_merge_compatible(other)?;
+
+        let other_watermark = other.watermark();
+
+        // make sure both wheels are aligned by time
+        if self.watermark() > other_watermark {
+            other.advance_to(self.watermark(), &mut WriterWheel::default());
+        } else {
+            self.advance_to(other_watermark, &mut WriterWheel::default());
+        }
+
+        self.cache.clear();
+
+        // merge all aggregation wheels
+        self.seconds_wheel.merge(&other.seconds_wheel);
+        self.minutes_wheel.merge(&other.minutes_wheel);
+        self.hours_wheel.merge(&other.hours_wheel);
+        self.days_wheel.merge(&other.days_wheel);
+        self.weeks_wheel.merge(&other.weeks_wheel);
+        self.years_wheel.merge(&other.years_wheel);
+        if let (Some(millis), Some(other_millis)) =
+            (self.millis_wheel.as_mut(), other.millis_wheel.as_ref())
+        {
+            millis.merge(other_millis);
+        }
+        // milliseconds left unfed by either wheel are incomplete in the merged wheel
+        self.unfed_millis.extend(other.unfed_millis.iter().copied());
+        self.unfed_millis.sort_unstable();
+        let mut merged: Vec<(u64, u64)> = Vec::with_capacity(self.unfed_millis.len());
+        for (start, end) in self.unfed_millis.drain(..) {
+            match merged.last_mut() {
+                Some(last) if start <= last.1 => last.1 = cmp::max(last.1, end),
+                _ => merged.push((start, end)),
+            }
+        }
+        self.unfed_millis = merged;
+        self.prune_unfed_millis();
+
+        if let Some(other_year) = other.calendar_year.as_ref() {
+            let year = self.calendar_year.get_or_insert(WheelSlot::new(None));
+            year.total = A::combine(year.total, other_year.total);
+            year.occupied |= other_year.occupied;
+        }
+        Ok(())
+    }
+
+    fn check_merge_compatible(&self, other: &Self) -> Result<(), MergeError> {
+        if self.conf.calendar_years != other.conf.calendar_years {
+            return Err(MergeError::IncompatibleYearLength);
+        }
+        if self.conf.epoch_offset != other.conf.epoch_offset {
+            return Err(MergeError::IncompatibleEpoch {
+                expected: self.conf.epoch_offset,
+                found: other.conf.epoch_offset,
+            });
+        }
+        if self.millis_wheel.is_some() != other.millis_wheel.is_some() {
+            return Err(MergeError::IncompatibleMilliseconds);
+        }
+
+        let pairs = [
+            (
+                Granularity::Second,
+                &self.seconds_wheel,
+                &other.seconds_wheel,
+            ),
+            (
+                Granularity::Minute,
+                &self.minutes_wheel,
+                &other.minutes_wheel,
+            ),
+            (Granularity::Hour, &self.hours_wheel, &other.hours_wheel),
+            (Granularity::Day, &self.days_wheel, &other.days_wheel),
+            (Granularity::Week, &self.weeks_wheel, &other.weeks_wheel),
+            (Granularity::Year, &self.years_wheel, &other.years_wheel),
+        ];
+
+        for (granularity, wheel, other_wheel) in pairs {
+            let (expected, found) = (wheel.conf().tick_size_ms, other_wheel.conf().tick_size_ms);
+            if expected != found {
+                return Err(MergeError::IncompatibleTickSize {
+                    granularity,
+                    expected,
+                    found,
+                });
+            }
+            let compressed = [wheel, other_wheel].iter().any(|w| {
+                w.as_ref()
+                    .is_some_and(|w| matches!(w.data_layout(), DataLayout::Compressed(_)))
+            });
+            if compressed {
+                return Err(MergeError::CompressedLayout { granularity });
+            }
+        }
+        Ok(())
+    }
+    /// Returns per-granularity access statistics of executed wheel aggregations
+    ///
+    /// Granularities whose scan volume deviates significantly from the others are flagged as outliers.
+    /// Statistics are only recorded if enabled through [HawConf::with_access_stats] or an adaptive layout.
+    pub fn access_stats(&self) -> AccessStats {
+        self.access.snapshot()
+    }
+
+    // Records a wheel aggregation if access statistics are enabled
+    #[inline]
+    fn record_access(&self, granularity: Granularity, scans: usize) {
+        if self.conf.access_stats || self.conf.optimizer.adaptive_layout.is_some() {
+            self.access.record(granularity, scans);
+        }
+    }
+
+    /// Resets the access statistics of the wheel
+    pub fn reset_access_stats(&self) {
+        self.access.reset();
+    }
+
+    /// Converts granularities selected by the given policy to prefix-enabled wheels
+    ///
+    /// Does nothing if the [Aggregator] lacks an exact inverse, as prefix ranges would otherwise be inexact.
+    pub fn adapt_layout(&mut self, layout: AdaptiveLayout) {
+        if !A::exact_inverse() {
+            return;
+        }
+        let stats = self.access_stats();
+        let wheels = [
+            (stats.seconds, &mut self.seconds_wheel),
+            (stats.minutes, &mut self.minutes_wheel),
+            (stats.hours, &mut self.hours_wheel),
+            (stats.days, &mut self.days_wheel),
+            (stats.weeks, &mut self.weeks_wheel),
+            (stats.years, &mut self.years_wheel),
+        ];
+        for (access, wheel) in wheels {
+            if layout.selects(&access) {
+                if let Some(wheel) = wheel.as_mut() {
+                    wheel.to_prefix();
+                }
+            }
+        }
+    }
+
+    #[cfg(feature = "profiler")]
+    /// Returns a reference to the stats of the [Haw]
+    pub fn stats(&self) -> &Stats {
+        &self.stats
+    }
+}
+
+// Folds the partial aggregates and returns `None` if there are none
+#[inline]
+fn fold_partials<P, B>(
+    partials: impl Iterator<Item = P>,
+    init: B,
+    f: impl FnMut(B, P) -> B,
+) -> Option<B> {
+    let mut partials = partials.peekable();
+    partials.peek()?;
+    Some(partials.fold(init, f))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        aggregator::sum::{F64SumAggregator, I64SumAggregator, U32SumAggregator, U64SumAggregator},
+        duration::NumericalDuration,
+        wheels::read::plan::{Aggregation, ComplexityClass},
+        Entry,
+    };
+    use proptest::prelude::*;
+    use time::macros::datetime;
+
+    use super::*;
+
+    #[test]
+    fn delta_advance_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // oldest to newest
+        let deltas = vec![Some(10), None, Some(50), None];
+
+        // Visualization:
+        // 10
+        // 0, 10
+        // 50, 0, 10
+        // 0, 50, 0, 10
+        haw.delta_advance(deltas);
+
+        assert_eq!(haw.interval(1.seconds()), Some(0));
+        assert_eq!(haw.interval(2.seconds()), Some(50));
+        assert_eq!(haw.interval(3.seconds()), Some(50));
+        assert_eq!(haw.interval(4.seconds()), Some(60));
+    }
+
+    #[test]
+    fn scan_estimation_weeks_and_years_test() {
+        let haw: Haw<U64SumAggregator> = Haw::default();
+
+        let start = datetime!(2023 - 11 - 06 00:00:00 UTC);
+        let range = WheelRange::from(start, start + time::Duration::weeks(3));
+        assert_eq!(range.estimation_granularity(), Granularity::Week);
+        assert_eq!(range.scan_estimation(), 3);
+        assert_eq!(
+            haw.weeks_wheel.aggregate_plan(&range, Granularity::Week),
+            Aggregation::Scan(range.scan_estimation() as usize)
+        );
+
+        let range = WheelRange::from(start, start + time::Duration::weeks(2 * WEEKS as i64));
+        assert_eq!(range.estimation_granularity(), Granularity::Year);
+        assert_eq!(range.scan_estimation(), 2);
+        assert_eq!(
+            haw.years_wheel.aggregate_plan(&range, Granularity::Year),
+            Aggregation::Scan(range.scan_estimation() as usize)
+        );
+
+        // day-aligned ranges that are not whole weeks are still estimated in days
+        let range = WheelRange::from(start, start + time::Duration::days(10));
+        assert_eq!(range.scan_estimation(), 10);
+    }
+
+    // keeps the most recent (value, present) pair which makes combine non-commutative
+    #[derive(Clone, Debug, Default)]
+    struct LastAggregator;
+
+    impl Aggregator for LastAggregator {
+        const IDENTITY: Self::PartialAggregate = (0, 0);
+        const TAG: &'static str = "LastAggregator";
+        type Input = u64;
+        type MutablePartialAggregate = (u64, u64);
+        type PartialAggregate = (u64, u64);
+        type Aggregate = u64;
+
+        fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+            (input, 1)
+        }
+        fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+            *a = (input, 1);
+        }
+        fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+            a
+        }
+        fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+            if b.1 > 0 {
+                b
+            } else {
+                a
+            }
+        }
+        fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+            a.0
+        }
+        fn commutative() -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn non_commutative_order_test() {
+        let mut haw: Haw<LastAggregator> = Haw::default();
+        haw.delta_advance((1..=340).map(|value| Some((value, 1))));
+
+        // minutes [00:01, 00:05) followed by seconds [00:05:00, 00:05:30)
+        let range = WheelRange::new_unchecked(60000, 330000);
+        assert!(matches!(
+            haw.explain_combine_range(range),
+            Some(ExecutionPlan::CombinedAggregation(_))
+        ));
+        assert_eq!(haw.combine_range_and_lower(range), Some(330));
+
+        // single wheel scans and the landmark are combined in time order as well
+        let range = WheelRange::new_unchecked(60000, 240000);
+        assert_eq!(haw.combine_range_and_lower(range), Some(240));
+        let range = WheelRange::new_unchecked(305000, 320000);
+        assert_eq!(haw.combine_range_and_lower(range), Some(320));
+        assert_eq!(haw.landmark().map(LastAggregator::lower), Some(340));
+    }
+
+    // concatenates decimal digits as (digits, length), a copyable stand-in for string concatenation
+    #[derive(Clone, Debug, Default)]
+    struct DigitConcatAggregator;
+
+    impl Aggregator for DigitConcatAggregator {
+        const IDENTITY: Self::PartialAggregate = (0, 0);
+        const TAG: &'static str = "DigitConcatAggregator";
+        type Input = u8;
+        type MutablePartialAggregate = (u64, u32);
+        type PartialAggregate = (u64, u32);
+        type Aggregate = u64;
+
+        fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+            (input as u64, 1)
+        }
+        fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+            *a = Self::combine(*a, Self::lift(input));
+        }
+        fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+            a
+        }
+        fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+            (a.0 * 10u64.pow(b.1) + b.0, a.1 + b.1)
+        }
+        fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+            a.0
+        }
+        fn commutative() -> bool {
+            false
+        }
+    }
+
+    #[test]
+    fn combined_aggregation_concat_test() {
+        let mut haw: Haw<DigitConcatAggregator> = Haw::default();
+        let digits = [(70, 1), (130, 2), (190, 3), (250, 4), (305, 5), (315, 6)];
+        haw.delta_advance((0..340).map(|second| {
+            digits
+                .iter()
+                .find(|(at, _)| *at == second)
+                .map(|(_, digit)| DigitConcatAggregator::lift(*digit))
+        }));
+
+        // minutes [00:01, 00:05) followed by seconds [00:05:00, 00:05:30)
+        let range = WheelRange::new_unchecked(60000, 330000);
+        assert_eq!(haw.combine_range_and_lower(range), Some(123456));
+
+        // executing the sub-ranges in any order combines them in time order
+        let Some(ExecutionPlan::CombinedAggregation(mut combined)) =
+            haw.explain_combine_range(range)
+        else {
+            panic!("expected a combined aggregation");
+        };
+        combined.aggregations.reverse();
+        let (result, _) = haw.combined_aggregation(combined);
+        assert_eq!(result.map(DigitConcatAggregator::lower), Some(123456));
+    }
+
+    #[derive(Clone, Debug, Default)]
+    struct CoarseYearSum;
+
+    impl Aggregator for CoarseYearSum {
+        const IDENTITY: Self::PartialAggregate = 0;
+        const TAG: &'static str = "CoarseYearSum";
+        type Input = u64;
+        type MutablePartialAggregate = u64;
+        type PartialAggregate = u64;
+        type Aggregate = u64;
+
+        fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+            input
+        }
+        fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+            *a += input;
+        }
+        fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+            a
+        }
+        fn combine(a: Self::PartialAggregate, b: Self::PartialAggregate) -> Self::PartialAggregate {
+            a + b
+        }
+        fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+            a
+        }
+        // years only keep the sum in thousands
+        fn project_on_rollup(
+            a: Self::PartialAggregate,
+            granularity: Granularity,
+        ) -> Self::PartialAggregate {
+            match granularity {
+                Granularity::Year => a / 1000 * 1000,
+                _ => a,
+            }
+        }
+    }
+
+    #[test]
+    fn project_on_rollup_test() {
+        let mut haw: Haw<CoarseYearSum> = Haw::default();
+        let mut waw = WriterWheel::<CoarseYearSum>::with_watermark(haw.watermark());
+
+        let day_ms = time::Duration::DAY.whole_milliseconds() as u64;
+        let year_ms = time::Duration::WEEK.whole_milliseconds() as u64 * WEEKS as u64;
+        haw.advance_to(year_ms - 2 * day_ms, &mut waw);
+        waw.insert(Entry::new(1500, year_ms - 2 * day_ms));
+
+        // lower granularities are left untouched
+        haw.advance_to(year_ms - day_ms, &mut waw);
+        let range = WheelRange::new_unchecked(year_ms - 2 * day_ms, year_ms - day_ms);
+        assert_eq!(haw.combine_range(range), Some(1500));
+
+        // the years wheel stores the projected aggregate
+        haw.advance_to(year_ms, &mut waw);
+        assert_eq!(haw.years_unchecked().total(), Some(1000));
+        let range = WheelRange::new_unchecked(0, year_ms);
+        assert_eq!(range.estimation_granularity(), Granularity::Year);
+        assert_eq!(haw.combine_range(range), Some(1000));
+    }
+
+    #[test]
+    fn millisecond_wheel_alignment_test() {
+        let conf = HawConf::default().with_milliseconds(1500);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(10_000));
+        assert!(haw.size_breakdown().milliseconds.total() > 0);
+        assert_eq!(haw.size_bytes(), haw.size_breakdown().total());
+
+        // seconds sealed without milliseconds leave the milliseconds wheel empty but aligned
+        haw.delta_advance([Some(5)]);
+        assert_eq!(
+            haw.milliseconds().map(|wheel| wheel.watermark()),
+            Some(11_000)
+        );
+        // seconds that were never fed at millisecond granularity cannot be answered
+        assert_eq!(haw.combine_millis(10_000, 11_000), None);
+        assert_eq!(haw.combine_millis(10_500, 10_501), None);
+
+        let mut waw = WriterWheel::with_watermark(haw.watermark()).with_tick_size(1);
+        waw.insert(Entry::new(7, 11_400));
+        haw.advance(1.seconds(), &mut waw);
+        assert_eq!(haw.combine_millis(11_400, 11_401), Some(7));
+        assert_eq!(haw.combine_millis(11_000, 11_400), Some(0));
+        assert_eq!(haw.combine_millis(11_000, 12_000), Some(7));
+        assert_eq!(haw.combine_millis(10_600, 12_000), None);
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(10_000, 12_000)),
+            Some(12)
+        );
+
+        // only the most recent milliseconds are retained
+        assert_eq!(haw.combine_millis(10_000, 12_000), None);
+
+        // a writer ticking per second cannot feed the milliseconds wheel
+        let mut waw = WriterWheel::with_watermark(haw.watermark());
+        waw.insert(Entry::new(3, 12_000));
+        haw.advance(1.seconds(), &mut waw);
+        assert_eq!(haw.combine_millis(12_000, 13_000), None);
+        assert_eq!(haw.combine_millis(11_600, 12_000), Some(0));
+        assert_eq!(haw.interval(1.seconds()), Some(3));
+    }
+
+    #[test]
+    fn millisecond_wheel_diff_merge_test() {
+        let conf = HawConf::default().with_milliseconds(2000);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let mut replica: Haw<U64SumAggregator> = Haw::new(conf);
+
+        let mut waw = WriterWheel::with_watermark(0).with_tick_size(1);
+        waw.insert(Entry::new(7, 400));
+        haw.advance(1.seconds(), &mut waw);
+        haw.delta_advance([Some(2)]);
+
+        // the replica mirrors both the fed and the unfed milliseconds
+        let diff = haw.diff(&replica).unwrap();
+        assert_eq!(diff.len(), 2 + haw.milliseconds().unwrap().len());
+        replica.apply_diff(&diff).unwrap();
+        assert_eq!(replica.combine_millis(400, 1000), Some(7));
+        assert_eq!(replica.combine_millis(1000, 2000), None);
+
+        let mut waw = WriterWheel::with_watermark(2000).with_tick_size(1);
+        waw.insert(Entry::new(5, 2100));
+        haw.advance(1.seconds(), &mut waw);
+        let diff = haw.diff(&replica).unwrap();
+        replica.apply_diff(&diff).unwrap();
+        assert_eq!(replica.combine_millis(2000, 3000), Some(5));
+        assert_eq!(replica.combine_millis(1000, 2000), None);
+        assert_eq!(
+            replica.combine_millis(2000, 3000),
+            haw.combine_millis(2000, 3000)
+        );
+
+        // merged milliseconds are incomplete wherever either wheel was not fed
+        let mut other: Haw<U64SumAggregator> = Haw::new(conf);
+        let mut waw = WriterWheel::with_watermark(0).with_tick_size(1);
+        waw.insert(Entry::new(1, 2500));
+        other.advance(3.seconds(), &mut waw);
+        haw.merge(&mut other).unwrap();
+        assert_eq!(haw.combine_millis(2000, 3000), Some(6));
+        assert_eq!(haw.combine_millis(1000, 2000), None);
+
+        // wheels without a milliseconds wheel cannot be merged into one with and vice versa
+        let mut seconds: Haw<U64SumAggregator> = Haw::default();
+        assert_eq!(
+            haw.merge(&mut seconds),
+            Err(MergeError::IncompatibleMilliseconds)
+        );
+        assert_eq!(
+            seconds.merge(&mut haw),
+            Err(MergeError::IncompatibleMilliseconds)
+        );
+    }
+
+    #[test]
+    fn advance_past_cycle_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        let mut waw = WriterWheel::<U64SumAggregator>::with_watermark(haw.watermark());
+        waw.insert(Entry::new(10, 0));
+        assert!(haw.advance_checked(2.seconds(), &mut waw).is_ok());
+        assert_eq!(haw.interval(2.seconds()), Some(10));
+
+        let jump = Haw::<U64SumAggregator>::CYCLE_LENGTH + time::Duration::SECOND;
+        let result = haw.advance_checked(Duration::seconds(jump.whole_seconds()), &mut waw);
+        assert_eq!(
+            result.unwrap_err(),
+            CycleExceeded {
+                ticks: jump.whole_seconds() as u64
+            }
+        );
+        assert_eq!(haw.interval(2.seconds()), None);
+
+        // a lazy advance past the cycle also clears all wheels and yields nothing
+        waw.insert(Entry::new(10, haw.watermark()));
+        haw.advance(1.seconds(), &mut waw);
+        assert_eq!(haw.interval(1.seconds()), Some(10));
+        let jump = Duration::seconds(jump.whole_seconds());
+        assert_eq!(haw.advance_iter(jump, &mut waw).count(), 0);
+        assert_eq!(haw.interval(1.seconds()), None);
+    }
+
+    #[test]
+    fn granularity_timestamps_test() {
+        let watermark = 1699488000000;
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_watermark(watermark));
+        haw.delta_advance((0..10).map(Some));
+
+        let timestamps: Vec<u64> = haw.granularity_timestamps(Granularity::Second).collect();
+        assert_eq!(timestamps.len(), 10);
+        assert_eq!(timestamps[0], haw.watermark() - 1000);
+        assert!(timestamps.windows(2).all(|w| w[0] - w[1] == 1000));
+        assert_eq!(timestamps[9], watermark);
+        assert_eq!(haw.seconds_unchecked().slot_timestamp(3), timestamps[3]);
+
+        // uninitialized wheels have no slots
+        assert_eq!(haw.granularity_timestamps(Granularity::Minute).count(), 0);
+    }
+
+    #[test]
+    fn range_delta_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((1..=20).map(Some));
+
+        let this_week = WheelRange::new_unchecked(10000, 20000);
+        let last_week = WheelRange::new_unchecked(0, 10000);
+        let manual = haw.combine_range(this_week).unwrap() - haw.combine_range(last_week).unwrap();
+        assert_eq!(haw.range_delta(this_week, last_week), Some(manual));
+        assert_eq!(manual, 100);
+
+        // non-invertible aggregators have no delta
+        let mut haw: Haw<crate::aggregator::max::U64MaxAggregator> = Haw::default();
+        haw.delta_advance((1..=20).map(Some));
+        assert_eq!(haw.range_delta(this_week, last_week), None);
+    }
+
+    #[test]
+    fn window_pairs_bounded_test() {
+        let buffered = |haw: &mut Haw<U64SumAggregator>| {
+            let manager = haw.window_manager.as_mut().unwrap();
+            let (state, aggregator) = manager.aggregator.slicing_as_mut();
+            (aggregator.len(), state.max_pairs)
+        };
+
+        // (range, slide, expected pairs)
+        for (range, slide, pairs) in [(600, 2, 300), (600, 7, 172)] {
+            let mut haw: Haw<U64SumAggregator> = Haw::default();
+            haw.window(Window::sliding(range.seconds(), slide.seconds()));
+            assert_eq!(buffered(&mut haw).1, pairs);
+
+            let mut windows = Vec::new();
+            for _ in 0..3600 {
+                windows.extend(haw.delta_advance([Some(1)]));
+                let (len, max_pairs) = buffered(&mut haw);
+                assert!(len <= max_pairs);
+            }
+            assert!(!windows.is_empty());
+            assert!(windows.iter().all(|w| w.aggregate == range as u64));
+        }
+    }
+
+    #[test]
+    fn advance_iter_test() {
+        let new_wheels = || {
+            let mut haw: Haw<U64SumAggregator> = Haw::default();
+            haw.window(Window::sliding(30.seconds(), 10.seconds()));
+            let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::default();
+            for second in 0..600u64 {
+                waw.insert(Entry::new(second % 13, second * 1000));
+            }
+            (haw, waw)
+        };
+
+        let (mut eager, mut eager_waw) = new_wheels();
+        let expected = eager.advance(10.minutes(), &mut eager_waw);
+        assert!(!expected.is_empty());
+
+        let (mut lazy, mut lazy_waw) = new_wheels();
+        let mut iter = lazy.advance_iter(10.minutes(), &mut lazy_waw);
+
+        // ticks are only performed while consuming the iterator
+        let first = iter.next().unwrap();
+        assert_eq!(first, expected[0]);
+        assert_eq!(iter.remaining_ticks(), 600 - 30);
+
+        let mut windows = vec![first];
+        windows.extend(iter);
+        assert_eq!(windows, expected);
+        assert_eq!(lazy.watermark(), eager.watermark());
+        assert_eq!(lazy.landmark(), eager.landmark());
+    }
+
+    #[test]
+    fn advance_steps_test() {
+        let new_wheels = || {
+            let mut haw: Haw<U64SumAggregator> = Haw::default();
+            haw.window(Window::sliding(30.seconds(), 10.seconds()));
+            let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::default();
+            for second in 0..600u64 {
+                waw.insert(Entry::new(second % 13, second * 1000));
+            }
+            (haw, waw)
+        };
+
+        let (mut eager, mut eager_waw) = new_wheels();
+        let expected = eager.advance(10.minutes(), &mut eager_waw);
+        assert!(!expected.is_empty());
+
+        let (mut chunked, mut chunked_waw) = new_wheels();
+        let target = 10.minutes().whole_milliseconds() as u64;
+        let mut windows = Vec::new();
+        let mut chunks = 0;
+        loop {
+            let (fired, remaining) = chunked.advance_steps(target, 7, &mut chunked_waw);
+            windows.extend(fired);
+            chunks += 1;
+            if remaining == 0 {
+                break;
+            }
+            assert_eq!(remaining as u64, (target - chunked.watermark()) / 1000);
+        }
+
+        assert_eq!(chunks, 600usize.div_ceil(7));
+        assert_eq!(windows, expected);
+        assert_eq!(chunked.watermark(), eager.watermark());
+        assert_eq!(chunked.landmark(), eager.landmark());
+
+        // nothing left to advance
+        let (fired, remaining) = chunked.advance_steps(target, 7, &mut chunked_waw);
+        assert!(fired.is_empty());
+        assert_eq!(remaining, 0);
+        assert_eq!(chunked.watermark(), target);
+    }
+
+    #[test]
+    fn fractional_second_range_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        let deltas: Vec<Option<u64>> = (1..=10).map(Some).collect();
+        haw.delta_advance(deltas);
+
+        // start is rounded down and end is rounded up
+        let range = WheelRange::new_unchecked(1500, 3200);
+        assert_eq!(range, WheelRange::new_unchecked(1000, 4000));
+        assert_eq!(range.duration(), 3.seconds());
+        assert_eq!(range.lowest_granularity(), Granularity::Second);
+        assert_eq!(haw.combine_range(range), Some(2 + 3 + 4));
+
+        // whole seconds are left untouched
+        assert_eq!(
+            WheelRange::new_unchecked(2000, 3000).duration(),
+            1.seconds()
+        );
+
+        let start = OffsetDateTime::UNIX_EPOCH + time::Duration::milliseconds(4999);
+        let end = OffsetDateTime::UNIX_EPOCH + time::Duration::milliseconds(5001);
+        let range = WheelRange::from(start, end);
+        assert_eq!(range, WheelRange::new_unchecked(4000, 6000));
+        assert_eq!(haw.combine_range(range), Some(5 + 6));
+    }
+
+    #[test]
+    fn replay_windows_test() {
+        use crate::{Conf, RwWheel};
+
+        // 2023-11-09 00:00:00
+        let start = 1699488000000;
+        let haw_conf = HawConf::default()
+            .with_watermark(start)
+            .with_retention_policy(RetentionPolicy::Keep);
+        let mut wheel: RwWheel<U64SumAggregator> =
+            RwWheel::with_conf(Conf::default().with_haw_conf(haw_conf).with_write_ahead(64));
+        wheel.window(Window::sliding(10.minutes(), 5.minutes()));
+
+        let day_ms = time::Duration::DAY.whole_milliseconds() as u64;
+        let mut emitted = Vec::new();
+        for minute in 0..24 * 60 {
+            let ts = start + minute * 60000;
+            wheel.insert(Entry::new(minute % 7, ts));
+            wheel.insert(Entry::new(1, ts + 30000));
+            emitted.extend(wheel.advance_to(ts + 60000));
+        }
+        assert_eq!(wheel.watermark(), start + day_ms);
+        assert!(!emitted.is_empty());
+
+        let replayed = wheel.read().replay_windows(
+            WheelRange::new_unchecked(start, start + day_ms),
+            10.minutes(),
+            5.minutes(),
+        );
+        assert_eq!(replayed, emitted);
+    }
+
+    #[test]
+    fn is_fast_query_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((0..120).map(Some));
+
+        // the full wheel range is answered through the landmark
+        assert!(haw.is_fast_query(WheelRange::new_unchecked(0, 120000)));
+
+        // arbitrary second-aligned ranges require scans without prefix layout
+        let range = WheelRange::new_unchecked(65000, 110000);
+        assert!(!haw.is_fast_query(range));
+
+        // ranges that cannot be answered are not fast
+        assert!(!haw.is_fast_query(WheelRange::new_unchecked(5000, 30000)));
+
+        haw.to_prefix_wheels();
+        assert!(haw.is_fast_query(range));
+    }
+
+    #[test]
+    fn combine_range_at_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((0..3600).map(Some));
+
+        // forcing minute granularity matches the auto-planned result
+        let range = WheelRange::new_unchecked(10 * 60000, 40 * 60000);
+        let minutes = haw.combine_range_at(range, Granularity::Minute).unwrap();
+        assert!(minutes.is_some());
+        assert_eq!(minutes, haw.combine_range(range));
+
+        // the completed hour has been rolled up into the hours wheel
+        let hour = WheelRange::new_unchecked(0, 3600000);
+        assert_eq!(
+            haw.combine_range_at(hour, Granularity::Hour),
+            Ok(Some((0..3600).sum()))
+        );
+
+        // a range with second precision cannot be expressed in minutes
+        let range = WheelRange::new_unchecked(10 * 60000 + 30000, 40 * 60000);
+        assert_eq!(
+            haw.combine_range_at(range, Granularity::Minute),
+            Err(QueryError::UnalignedRange {
+                granularity: Granularity::Minute
+            })
+        );
+
+        // seconds older than the seconds wheel are no longer retained
+        let range = WheelRange::new_unchecked(10 * 60000, 11 * 60000);
+        assert_eq!(haw.combine_range_at(range, Granularity::Second), Ok(None));
+    }
+
+    #[test]
+    fn granularity_range_shortcuts_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((0..2 * 3600).map(Some));
+
+        let range = WheelRange::new_unchecked(65 * 60000, 90 * 60000);
+        let minutes = haw.minutes_range(range).unwrap();
+        assert!(minutes.is_some());
+        assert_eq!(minutes, haw.combine_range(range));
+
+        let range = WheelRange::new_unchecked(0, 2 * 3600000);
+        assert_eq!(haw.hours_range(range), Ok(haw.combine_range(range)));
+
+        // not aligned to minutes
+        let range = WheelRange::new_unchecked(65 * 60000 + 1000, 90 * 60000);
+        assert_eq!(
+            haw.minutes_range(range),
+            Err(QueryError::UnalignedRange {
+                granularity: Granularity::Minute
+            })
+        );
+        assert!(haw.days_range(range).is_err());
+    }
+
+    #[test]
+    fn concat_history_test() {
+        // 2023-11-09 00:00:00
+        let start = 1699488000000;
+        let split = start + 2 * HOUR_TICK_MS;
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let deltas: Vec<Option<u64>> = (0..4 * 3600).map(|i| Some(i % 7)).collect();
+
+        let mut baseline: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(start));
+        baseline.delta_advance(deltas.clone());
+
+        let mut older: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(start));
+        older.delta_advance(deltas[..2 * 3600].to_vec());
+        let mut recent: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(split));
+        recent.delta_advance(deltas[2 * 3600..].to_vec());
+
+        let history = recent.concat_history(&older).unwrap();
+        assert_eq!(history.boundary(), split);
+        assert_eq!(history.watermark(), baseline.watermark());
+
+        let ranges = [
+            // crosses the boundary
+            (start + HOUR_TICK_MS + 30000, split + HOUR_TICK_MS + 15000),
+            (start, start + 4 * HOUR_TICK_MS),
+            // within a single wheel
+            (start + MINUTE_TICK_MS, split),
+            (split, split + 10000),
+        ];
+        for (start_ms, end_ms) in ranges {
+            let range = WheelRange::new_unchecked(start_ms, end_ms);
+            let expected = baseline.combine_range(range);
+            assert!(expected.is_some());
+            assert_eq!(history.combine_range(range), expected);
+        }
+
+        // the older wheel must reach the oldest data of the recent wheel
+        let mut gap: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(start));
+        gap.delta_advance(deltas[..3600].to_vec());
+        assert_eq!(
+            recent.concat_history(&gap).err(),
+            Some(ConcatError::Gap {
+                older_watermark: start + HOUR_TICK_MS,
+                oldest: split
+            })
+        );
+        let empty: Haw<U64SumAggregator> = Haw::new(conf.with_watermark(split));
+        assert_eq!(empty.concat_history(&older).err(), Some(ConcatError::Empty));
+    }
+
+    #[test]
+    fn oldest_timestamp_test() {
+        // 2023-11-09 00:00:00
+        let start = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(start)
+            .with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::with_watermark(start);
+        assert_eq!(haw.oldest_timestamp(), None);
+
+        // only the seconds wheel is populated
+        haw.advance(30.seconds(), &mut waw);
+        assert_eq!(haw.oldest_timestamp(), Some(start));
+
+        let retained = (2 * DAY_TICK_MS) + (3 * HOUR_TICK_MS) + (30 * MINUTE_TICK_MS) + 10_000;
+        haw.advance_to(start + retained, &mut waw);
+        assert_eq!(haw.oldest_timestamp(), Some(haw.watermark() - retained));
+
+        // the retained span covers all data of the wheel
+        let oldest = haw.oldest_timestamp().unwrap();
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(oldest, haw.watermark())),
+            haw.landmark()
+        );
+    }
+
+    #[test]
+    fn oldest_timestamp_drop_retention_test() {
+        let days = WheelConf::new(DAY_TICK_MS, 7).with_retention_policy(RetentionPolicy::Drop);
+        let conf = HawConf::default().with_days(days);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        // partially fill the days wheel
+        haw.seed_granularity(Granularity::Day, &[Some(1); 5], 12 * DAY_TICK_MS)
+            .unwrap();
+        assert_eq!(
+            haw.oldest_timestamp(),
+            Some(haw.watermark() - 5 * DAY_TICK_MS)
+        );
+
+        let oldest = haw.oldest_timestamp().unwrap();
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(oldest, haw.watermark())),
+            Some(5)
+        );
+        // queries before the oldest retained timestamp cannot be answered
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(oldest - DAY_TICK_MS, oldest)),
+            None
+        );
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(0, DAY_TICK_MS)),
+            None
+        );
+
+        // the days wheel only retains the 6 latest days but the first week was rolled up before being dropped
+        haw.seed_granularity(Granularity::Day, &[Some(1); 10], 12 * DAY_TICK_MS)
+            .unwrap();
+        assert_eq!(
+            haw.wheel(Granularity::Day).map(|wheel| wheel.len()),
+            Some(6)
+        );
+        assert_eq!(
+            haw.oldest_timestamp(),
+            Some(haw.watermark() - 10 * DAY_TICK_MS)
+        );
+
+        let oldest = haw.oldest_timestamp().unwrap();
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(oldest, haw.watermark())),
+            Some(10)
+        );
+        assert_eq!(
+            haw.combine_range(WheelRange::new_unchecked(0, oldest)),
+            None
+        );
+    }
+
+    #[test]
+    fn combine_range_checked_test() {
+        // 2023-11-09 00:00:00
+        let start = 1699488000000;
+        let conf = HawConf::default().with_watermark(start);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let (result, coverage) =
+            haw.combine_range_checked(WheelRange::new_unchecked(start, start + 1000));
+        assert_eq!((result, coverage), (None, Coverage::None));
+
+        let deltas: Vec<Option<u64>> = (0..7200).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+        let watermark = haw.watermark();
+
+        let full = WheelRange::new_unchecked(start + HOUR_TICK_MS, watermark);
+        assert_eq!(
+            haw.combine_range_checked(full),
+            (Some(3600), Coverage::Full)
+        );
+
+        // starts past the retention horizon: only the retained part is aggregated
+        let evicted = WheelRange::new_unchecked(start - HOUR_TICK_MS, start + HOUR_TICK_MS);
+        assert_eq!(
+            haw.combine_range_checked(evicted),
+            (Some(3600), Coverage::Partial)
+        );
+        assert_eq!(haw.combine_range(evicted), Some(3600));
+
+        // ends ahead of the watermark
+        let ahead = WheelRange::new_unchecked(watermark - 10000, watermark + 10000);
+        assert_eq!(
+            haw.combine_range_checked(ahead),
+            (Some(10), Coverage::Partial)
+        );
+
+        // entirely before the oldest retained data point
+        let before = WheelRange::new_unchecked(start - HOUR_TICK_MS, start);
+        assert_eq!(haw.combine_range_checked(before), (None, Coverage::None));
+    }
+
+    #[test]
+    fn combine_range_result_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance(vec![None; 120]);
+        haw.delta_advance([Some(5), Some(0)]);
+        haw.delta_advance(vec![None; 31]);
+        haw.delta_advance([Some(3)]);
+
+        // a range without data is empty rather than the identity of its slots
+        let empty = WheelRange::new_unchecked(0, 120000);
+        assert_eq!(haw.combine_range(empty), Some(0));
+        assert_eq!(haw.combine_range_result(empty), QueryResult::Empty);
+        assert_ne!(
+            haw.combine_range_result(empty),
+            QueryResult::Value(U64SumAggregator::IDENTITY)
+        );
+        assert!(haw.combine_range_result(empty).is_empty());
+        assert_eq!(haw.combine_range_result(empty).unwrap_or_identity(), 0);
+
+        // inserted zeros are values
+        let zero = WheelRange::new_unchecked(121000, 122000);
+        assert_eq!(haw.combine_range_result(zero), QueryResult::Value(0));
+
+        let all = WheelRange::new_unchecked(0, haw.watermark());
+        assert_eq!(haw.combine_range_result(all), QueryResult::Value(8));
+        let latest = WheelRange::new_unchecked(150000, haw.watermark());
+        assert_eq!(haw.combine_range_result(latest).value(), Some(3));
+
+        // ranges the wheel cannot answer
+        let evicted = WheelRange::new_unchecked(10000, 20000);
+        assert_eq!(haw.combine_range(evicted), None);
+        assert!(haw.combine_range_result(evicted).is_empty());
+    }
+
+    #[test]
+    fn eviction_hook_test() {
+        use std::sync::{Arc, Mutex};
+
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let inner = evicted.clone();
+        haw.on_evict(move |granularity, ts, partial| {
+            inner.lock().unwrap().push((granularity, ts, partial))
+        });
+
+        // the seconds wheel keeps 59 seconds once full
+        haw.delta_advance((1..=59).map(Some));
+        assert!(evicted.lock().unwrap().is_empty());
+        haw.delta_advance((60..=62).map(Some));
+        assert_eq!(
+            *evicted.lock().unwrap(),
+            vec![
+                (Granularity::Second, 0, 1),
+                (Granularity::Second, 1000, 2),
+                (Granularity::Second, 2000, 3)
+            ]
+        );
+        assert_eq!(haw.oldest_timestamp(), Some(0));
+
+        // the first minute is evicted once the minutes wheel is full
+        haw.delta_advance(vec![Some(1); 3600 - 62]);
+        let minutes: Vec<_> = evicted
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(granularity, ..)| *granularity == Granularity::Minute)
+            .map(|(_, ts, partial)| (*ts, *partial))
+            .collect();
+        assert_eq!(minutes, vec![(0, (1..=60).sum())]);
+
+        haw.clear_evict_hook();
+        let count = evicted.lock().unwrap().len();
+        haw.delta_advance([Some(1)]);
+        assert_eq!(evicted.lock().unwrap().len(), count);
+    }
+
+    #[test]
+    fn eviction_hook_calendar_years_test() {
+        use std::sync::{Arc, Mutex};
+
+        let conf = HawConf::default().with_calendar_years().with_years(
+            WheelConf::new(YEAR_TICK_MS, 3).with_retention_policy(RetentionPolicy::Drop),
+        );
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let evicted = Arc::new(Mutex::new(Vec::new()));
+        let inner = evicted.clone();
+        haw.on_evict(move |granularity, ts, partial| {
+            if granularity == Granularity::Year {
+                inner.lock().unwrap().push((ts, partial))
+            }
+        });
+
+        // the years wheel retains the completed years 2019 and 2020 while 2021 is still in progress
+        let dec_31 = haw.year_start(2022) - DAY_TICK_MS;
+        haw.seed_granularity(Granularity::Year, &[Some(1), Some(2)], dec_31)
+            .unwrap();
+        let oldest = haw.oldest_timestamp();
+        assert_eq!(oldest, Some(haw.year_start(2019)));
+
+        // crossing January 1 evicts the oldest calendar year starting on its January 1
+        haw.delta_advance(vec![Some(1); 86400]);
+        assert_eq!(*evicted.lock().unwrap(), vec![(oldest.unwrap(), 1)]);
+        assert_eq!(haw.oldest_timestamp(), Some(haw.year_start(2020)));
+    }
+
+    // timer actions and profiler stats are not Send
+    #[cfg(not(any(feature = "timer", feature = "profiler")))]
+    #[test]
+    fn haw_send_sync_test() {
+        fn assert_send_sync<T: Send + Sync>(_: &T) {}
+
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.on_evict(|_, _, _| {});
+        assert_send_sync(&haw);
+    }
+
+    #[test]
+    fn granularity_totals_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // advance to 02:30:45 with a 1 per second
+        haw.delta_advance(vec![Some(1); 2 * 3600 + 30 * 60 + 45]);
+
+        let [seconds, minutes, hours, days, weeks, years] = haw.granularity_totals();
+        assert_eq!(seconds, Some(45));
+        assert_eq!(minutes, Some(30 * 60));
+        assert_eq!(hours, Some(2 * 3600));
+        assert_eq!((days, weeks, years), (None, None, None));
+
+        // the totals are combined by the landmark window
+        assert_eq!(haw.landmark(), Some(2 * 3600 + 30 * 60 + 45));
+    }
+
+    #[test]
+    fn calendar_years_test() {
+        // 2019-12-30T00:00:00Z
+        let start = 1577664000000;
+        let conf = HawConf::default()
+            .with_watermark(start)
+            .with_calendar_years()
+            .with_identity_fill_suppression();
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        // a single 1 per day until 2021-01-03
+        let mut day = vec![None; 86400];
+        day[0] = Some(1);
+        for _ in 0..370 {
+            haw.delta_advance(day.clone());
+        }
+
+        let jan_1 = |year: i32| haw.year_start(year);
+        assert_eq!(jan_1(2020), start + 2 * DAY_TICK_MS);
+        assert_eq!(haw.years_unchecked().rotation_count(), 2);
+        assert_eq!(haw.years_unchecked().watermark(), jan_1(2021));
+
+        // the first year only covers the days since the start of the wheel
+        let first_year = WheelRange::new_unchecked(start, jan_1(2020));
+        assert_eq!(haw.combine_range(first_year), Some(2));
+        // 2020 is a leap year
+        let leap_year = WheelRange::new_unchecked(jan_1(2020), jan_1(2021));
+        assert_eq!(haw.combine_range(leap_year), Some(366));
+        let both = WheelRange::new_unchecked(start, jan_1(2021));
+        assert_eq!(haw.combine_range(both), Some(368));
+
+        // recent days across the start of a year
+        let days = WheelRange::new_unchecked(jan_1(2021) - 2 * DAY_TICK_MS, haw.watermark());
+        assert_eq!(haw.combine_range(days), Some(4));
+
+        // older days are only retained as part of their calendar year
+        let mixed = WheelRange::new_unchecked(jan_1(2020) + DAY_TICK_MS, jan_1(2021));
+        assert_eq!(haw.combine_range(mixed), None);
+        assert_eq!(haw.landmark(), Some(370));
+    }
+
+    #[test]
+    fn calendar_years_epoch_offset_test() {
+        // a watermark of 1s relative to an epoch 1s before 2020-01-01T00:00:00Z is aligned to a UTC day
+        let jan_1 = 1577836800000;
+        let conf = HawConf::default()
+            .with_epoch_offset(jan_1 - 1000)
+            .with_watermark(1000)
+            .with_calendar_years();
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        // 2021-01-01T00:00:00Z relative to the epoch of the wheel
+        assert_eq!(haw.year_start(2021), 1000 + 366 * DAY_TICK_MS);
+
+        haw.delta_advance(vec![Some(1); 60]);
+        assert_eq!(haw.landmark(), Some(60));
+    }
+
+    #[test]
+    #[should_panic(expected = "Calendar years require a watermark aligned to a UTC day")]
+    fn calendar_years_unaligned_test() {
+        // aligned relative to the epoch of the wheel but not to a UTC day
+        let conf = HawConf::default()
+            .with_epoch_offset(1000)
+            .with_calendar_years();
+        let _haw: Haw<U64SumAggregator> = Haw::new(conf);
+    }
+
+    #[test]
+    fn combine_range_before_oldest_test() {
+        let conf = HawConf::default().with_watermark(HOUR_TICK_MS);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        haw.delta_advance(vec![Some(1); 1800]);
+        let oldest = haw.oldest_timestamp().unwrap();
+        assert_eq!(oldest, HOUR_TICK_MS);
+
+        // a lookback that exceeds the retained data is clamped to the oldest retained slot
+        let end = haw.watermark() - 10000;
+        let lookback = WheelRange::new_unchecked(0, end);
+        let retained = WheelRange::new_unchecked(oldest, end);
+        assert_eq!(haw.combine_range(retained), Some(1790));
+        assert_eq!(haw.combine_range(lookback), Some(1790));
+        assert_eq!(
+            haw.combine_range_checked(lookback),
+            (Some(1790), Coverage::Partial)
+        );
+    }
+
+    #[test]
+    fn wheel_conf_builder_test() {
+        let conf = HawConf::default()
+            .with_seconds(
+                WheelConf::new(SECOND_TICK_MS, SECONDS)
+                    .with_prefix()
+                    .with_retention_policy(RetentionPolicy::Keep),
+            )
+            .with_minutes(
+                WheelConf::new(MINUTE_TICK_MS, MINUTES)
+                    .with_retention_policy(RetentionPolicy::KeepWithLimit(30)),
+            )
+            .with_hours(WheelConf::new(HOUR_TICK_MS, HOURS).with_prefix());
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        haw.reserve();
+
+        let seconds = haw.seconds_wheel.as_ref().unwrap();
+        assert!(matches!(seconds.data_layout(), DataLayout::Prefix));
+        assert!(matches!(
+            haw.seconds_wheel.conf().retention,
+            RetentionPolicy::Keep
+        ));
+
+        let minutes = haw.minutes_wheel.as_ref().unwrap();
+        assert!(matches!(minutes.data_layout(), DataLayout::Normal));
+        assert!(matches!(
+            haw.minutes_wheel.conf().retention,
+            RetentionPolicy::KeepWithLimit(30)
+        ));
+
+        let hours = haw.hours_wheel.as_ref().unwrap();
+        assert!(matches!(hours.data_layout(), DataLayout::Prefix));
+        assert!(matches!(
+            haw.hours_wheel.conf().retention,
+            RetentionPolicy::Drop
+        ));
+
+        let days = haw.days_wheel.as_ref().unwrap();
+        assert!(matches!(days.data_layout(), DataLayout::Normal));
+
+        let compressed = WheelConf::new(DAY_TICK_MS, DAYS).with_compression(8);
+        assert!(matches!(compressed.data_layout, DataLayout::Compressed(8)));
+    }
+
+    #[test]
+    fn execution_plan_ordering_test() {
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        haw.delta_advance(vec![Some(1); 3 * 3600 + 100]);
+
+        // the whole wheel can be answered by the landmark or by combining every wheel
+        let range = WheelRange::new_unchecked(0, haw.watermark());
+        let landmark = haw.explain_combine_range(range).unwrap();
+        assert_eq!(landmark, ExecutionPlan::LandmarkAggregation);
+
+        let combined = haw
+            .combined_aggregation_plan(Haw::<U64SumAggregator>::split_wheel_ranges(range))
+            .map(ExecutionPlan::CombinedAggregation)
+            .unwrap();
+        assert!(combined.cost() > landmark.cost());
+        assert!(landmark < combined);
+        assert_eq!(cmp::min(combined.clone(), landmark.clone()), landmark);
+
+        // ties are broken in favor of the landmark
+        let agg = WheelAggregation::new(range, Aggregation::Scan(6), (0, 6), Granularity::Second);
+        let scan = ExecutionPlan::WheelAggregation(agg);
+        assert_eq!(scan.cost(), landmark.cost());
+        assert!(landmark < scan);
+
+        // distinct plans of the same kind and cost are only equal if their wheel aggregations are
+        let shifted = WheelAggregation::new(
+            WheelRange::new_unchecked(1000, haw.watermark()),
+            Aggregation::Scan(6),
+            (1, 7),
+            Granularity::Second,
+        );
+        let shifted = ExecutionPlan::WheelAggregation(shifted);
+        assert_eq!(shifted.cost(), scan.cost());
+        assert_ne!(shifted, scan);
+        assert_eq!(scan.cmp(&shifted), cmp::Ordering::Less);
+        assert_eq!(shifted.cmp(&scan), cmp::Ordering::Greater);
+        assert_eq!(scan.cmp(&scan.clone()), cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn complexity_class_test() {
+        let conf = HawConf::default().with_prefix_sum();
+        let mut prefix: Haw<U64SumAggregator> = Haw::new(conf);
+        let mut scan: Haw<U64SumAggregator> = Haw::default();
+        prefix.delta_advance(vec![Some(1); 3 * 3600 + 100]);
+        scan.delta_advance(vec![Some(1); 3 * 3600 + 100]);
+
+        // 01:00:00 - 03:00:00
+        let wide = WheelRange::new_unchecked(HOUR_TICK_MS, 3 * HOUR_TICK_MS);
+        let plan = prefix.explain_combine_range(wide).unwrap();
+        assert!(plan.is_prefix_or_landmark());
+        assert_eq!(plan.complexity_class(), ComplexityClass::Constant);
+
+        // 02:10:00 - 02:40:00
+        let sub_range =
+            WheelRange::new_unchecked(2 * HOUR_TICK_MS + 10 * 60000, 2 * HOUR_TICK_MS + 40 * 60000);
+        let plan = scan.explain_combine_range(sub_range).unwrap();
+        assert_eq!(plan.complexity_class(), ComplexityClass::Linear);
+
+        let landmark = WheelRange::new_unchecked(0, scan.watermark());
+        let plan = scan.explain_combine_range(landmark).unwrap();
+        assert_eq!(plan.complexity_class(), ComplexityClass::Constant);
+    }
+
+    #[test]
+    fn plan_candidates_test() {
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let deltas: Vec<Option<u64>> = (0..3 * 3600 + 100).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+
+        // 00:00:30 - 02:00:10
+        let range = WheelRange::new_unchecked(30000, 2 * HOUR_TICK_MS + 10000);
+        let candidates = haw.plan_candidates(range);
+        let kinds: Vec<PlanKind> = candidates.iter().map(|(kind, _)| *kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                PlanKind::WheelAggregation,
+                PlanKind::CombinedAggregation,
+                PlanKind::InverseLandmarkAggregation
+            ]
+        );
+        assert_eq!(candidates[0].1, 7180);
+
+        // the chosen plan is the cheapest candidate
+        let chosen = haw.explain_combine_range(range).unwrap();
+        let cheapest = candidates.iter().min_by_key(|(_, cost)| *cost).unwrap();
+        assert_eq!((chosen.kind(), chosen.cost()), *cheapest);
+        assert_eq!(haw.combine_range(range), Some(7180));
+
+        // the landmark is a candidate when the range covers the whole wheel
+        let range = WheelRange::new_unchecked(0, haw.watermark());
+        assert!(haw
+            .plan_candidates(range)
+            .contains(&(PlanKind::LandmarkAggregation, 6)));
+    }
+
+    #[test]
+    fn occupancy_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        assert!(haw.occupancy(Granularity::Second, 60).is_empty());
+
+        // data within seconds 5, 7 and 70 only, where second 7 only holds the identity value
+        let data_seconds = [5, 7, 70];
+        haw.delta_advance((0..180).map(|sec| {
+            if data_seconds.contains(&sec) {
+                Some(if sec == 7 { 0 } else { 1 })
+            } else {
+                None
+            }
+        }));
+
+        // most recent minute first
+        assert_eq!(
+            haw.occupancy(Granularity::Minute, 60),
+            vec![false, true, true]
+        );
+
+        // the seconds wheel only covers the last (empty) minute
+        let seconds = haw.occupancy(Granularity::Second, 180);
+        assert!(!seconds.is_empty());
+        assert!(seconds.iter().all(|occupied| !occupied));
+        assert_eq!(haw.occupancy(Granularity::Minute, 1), vec![false]);
+
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((0..60).map(|sec| data_seconds.contains(&sec).then_some(1)));
+        let seconds = haw.occupancy(Granularity::Second, 60);
+        let occupied: Vec<usize> = (0..seconds.len()).filter(|&age| seconds[age]).collect();
+        // ages of seconds 5 and 7 relative to the watermark at 60s
+        assert_eq!(occupied, vec![52, 54]);
+        assert_eq!(haw.occupancy(Granularity::Hour, 1), Vec::<bool>::new());
+    }
+
+    #[test]
+    fn identity_fill_suppression_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        let mut suppressed: Haw<U64SumAggregator> =
+            Haw::new(HawConf::default().with_identity_fill_suppression());
+
+        // a mostly empty stream
+        let deltas: Vec<Option<u64>> = (0..2 * 3600)
+            .map(|sec| (sec % 600 == 0).then_some(sec))
+            .collect();
+        haw.delta_advance(deltas.clone());
+        suppressed.delta_advance(deltas);
+
+        for dur in [
+            1.seconds(),
+            30.seconds(),
+            10.minutes(),
+            1.hours(),
+            2.hours(),
+        ] {
+            assert_eq!(suppressed.interval(dur), haw.interval(dur));
+        }
+        let range = WheelRange::new_unchecked(5 * 60000, 95 * 60000);
+        assert_eq!(suppressed.combine_range(range), haw.combine_range(range));
+        assert_eq!(suppressed.landmark(), haw.landmark());
+        assert_eq!(
+            suppressed.occupancy(Granularity::Minute, 60),
+            haw.occupancy(Granularity::Minute, 60)
+        );
+
+        // empty seconds are only inserted until the seconds wheel retains nothing but empty seconds
+        let inserts = haw.seconds_unchecked().total_inserts;
+        let suppressed_inserts = suppressed.seconds_unchecked().total_inserts;
+        assert_eq!(inserts, 2 * 3600);
+        assert!(suppressed_inserts <= 12 * 60);
+        assert_eq!(
+            suppressed.seconds_unchecked().total_ticks,
+            haw.seconds_unchecked().total_ticks
+        );
+
+        // an empty stream skips almost every insert
+        let mut empty: Haw<U64SumAggregator> =
+            Haw::new(HawConf::default().with_identity_fill_suppression());
+        empty.delta_advance(vec![None; 3600]);
+        assert!(empty.seconds_unchecked().total_inserts < 60);
+        assert_eq!(empty.interval(1.hours()), Some(0));
+        assert_eq!(empty.interval(30.seconds()), Some(0));
+
+        // suppression is opt-in even for aggregators where the identity is a no-op
+        let mut default: Haw<U64SumAggregator> = Haw::default();
+        default.delta_advance(vec![None; 120]);
+        assert_eq!(default.seconds_unchecked().total_inserts, 120);
+    }
+
+    #[test]
+    fn contributing_slots_test() {
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        // data within every tenth second only
+        haw.delta_advance((0..3 * 3600).map(|sec| (sec % 10 == 0).then_some(1)));
+
+        // every minute holds data
+        let range = WheelRange::new_unchecked(2 * HOUR_TICK_MS, 2 * HOUR_TICK_MS + 30 * 60000);
+        let (result, cost, contributing) = haw.analyze_combine_range(range);
+        assert_eq!(result, Some(180));
+        assert_eq!((cost, contributing), (30, 30));
+
+        // the recent seconds are sparse
+        let watermark = haw.watermark();
+        let range = WheelRange::new_unchecked(watermark - 50000, watermark);
+        let (result, cost, contributing) = haw.analyze_combine_range(range);
+        assert_eq!(result, Some(5));
+        assert_eq!(cost, 50);
+        assert_eq!(contributing, 5);
+        assert!(contributing < cost);
+    }
+
+    #[test]
+    fn dump_combine_range_test() {
+        #[derive(Default, Debug, Clone)]
+        struct PrettySum;
+
+        impl Aggregator for PrettySum {
+            const IDENTITY: Self::PartialAggregate = 0;
+            const TAG: &'static str = "PrettySum";
+            type Input = u64;
+            type MutablePartialAggregate = u64;
+            type PartialAggregate = u64;
+            type Aggregate = u64;
+
+            fn lift(input: Self::Input) -> Self::MutablePartialAggregate {
+                input
+            }
+            fn combine_mutable(a: &mut Self::MutablePartialAggregate, input: Self::Input) {
+                *a += input
+            }
+            fn freeze(a: Self::MutablePartialAggregate) -> Self::PartialAggregate {
+                a
+            }
+            fn combine(
+                a: Self::PartialAggregate,
+                b: Self::PartialAggregate,
+            ) -> Self::PartialAggregate {
+                a + b
+            }
+            fn lower(a: Self::PartialAggregate) -> Self::Aggregate {
+                a
+            }
+            fn format_partial(partial: &Self::PartialAggregate) -> String {
+                format!("sum={partial}")
+            }
+        }
+
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<PrettySum> = Haw::new(conf);
+        haw.delta_advance((0..2 * 3600).map(|_| Some(1)));
+
+        // 00:59:30 - 01:02:00
+        let range = WheelRange::new_unchecked(59 * 60000 + 30000, 62 * 60000);
+        let dump = haw.dump_combine_range(range).unwrap();
+        let plan = haw.explain_combine_range(range).unwrap();
+        let lines: Vec<&str> = dump.lines().collect();
+        assert_eq!(
+            lines[0],
+            format!("{:?} (cost {})", plan.kind(), plan.cost())
+        );
+        assert_eq!(lines.last(), Some(&"result => sum=150"));
+        // every intermediate partial is printed through the custom formatter
+        assert!(lines[1..].iter().all(|line| line.contains("=> sum=")));
+        assert!(lines.len() > 2);
+
+        assert_eq!(
+            haw.dump_combine_range(WheelRange::new_unchecked(2000, 1000)),
+            None
+        );
+    }
+
+    #[test]
+    fn combine_slot_range_test() {
+        let mut haw: Haw<U64SumAggregator> =
+            Haw::new(HawConf::default().with_retention_policy(RetentionPolicy::Keep));
+        haw.delta_advance((0..3600u64).map(|sec| Some(sec % 17)));
+
+        let watermark = haw.watermark();
+        let check = |haw: &Haw<U64SumAggregator>, granularity: Granularity, unit_ms: u64| {
+            for (start_age, end_age) in [(0, 1), (3, 9), (10, 30)] {
+                let range = WheelRange::new_unchecked(
+                    watermark - end_age * unit_ms,
+                    watermark - start_age * unit_ms,
+                );
+                let plan = haw.wheel_aggregation_plan_at(range, granularity).unwrap();
+                assert_eq!(plan.slots, (start_age as usize, end_age as usize));
+                assert_eq!(
+                    haw.combine_slot_range(granularity, start_age as usize, end_age as usize),
+                    haw.wheel_aggregation(plan)
+                );
+            }
+        };
+        check(&haw, Granularity::Second, 1000);
+        check(&haw, Granularity::Minute, 60_000);
+
+        // prefix layout yields the same results
+        haw.seconds_wheel.as_mut().unwrap().to_prefix();
+        haw.minutes_wheel.as_mut().unwrap().to_prefix();
+        check(&haw, Granularity::Second, 1000);
+        check(&haw, Granularity::Minute, 60_000);
+
+        assert_eq!(haw.combine_slot_range(Granularity::Second, 5, 4), None);
+        assert_eq!(haw.combine_slot_range(Granularity::Minute, 0, 61), None);
+        assert_eq!(haw.combine_slot_range(Granularity::Day, 0, 1), None);
+    }
+
+    #[test]
+    fn deltas_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        assert!(haw.deltas(Granularity::Second, 10).is_empty());
+
+        // a monotonically increasing counter sampled every second
+        let increments = [3u64, 1, 4, 1, 5, 9, 2, 6];
+        let readings: Vec<u64> = increments
+            .iter()
+            .scan(100, |counter, inc| {
+                *counter += inc;
+                Some(*counter)
+            })
+            .collect();
+        haw.delta_advance(readings.iter().copied().map(Some));
+
+        let deltas = haw.deltas(Granularity::Second, 8);
+        assert_eq!(deltas.len(), 8);
+        // newest first, where the oldest reading has no predecessor
+        let expected: Vec<Option<u64>> = increments[1..]
+            .iter()
+            .rev()
+            .copied()
+            .map(Some)
+            .chain([None])
+            .collect();
+        assert_eq!(deltas, expected);
+        assert_eq!(haw.deltas(Granularity::Second, 2), vec![Some(6), Some(2)]);
+
+        // non-invertible aggregators produce no deltas
+        let mut haw: Haw<crate::aggregator::max::U64MaxAggregator> = Haw::default();
+        haw.delta_advance([Some(1), Some(2)]);
+        assert!(haw.deltas(Granularity::Second, 2).is_empty());
+    }
+
+    #[test]
+    fn combine_range_across_test() {
+        let mut a: Haw<U64SumAggregator> = Haw::default();
+        let mut b: Haw<U64SumAggregator> = Haw::default();
+        a.delta_advance((1..=3600 + 90).map(Some));
+        b.delta_advance((0..3600 + 90).map(|i| if i % 3 == 0 { Some(i) } else { None }));
+
+        let ranges = [
+            WheelRange::new_unchecked(0, HOUR_TICK_MS),
+            WheelRange::new_unchecked(HOUR_TICK_MS, HOUR_TICK_MS + 60000),
+            WheelRange::new_unchecked(HOUR_TICK_MS + 60000, HOUR_TICK_MS + 80000),
+        ];
+        let across: Vec<_> = ranges
+            .iter()
+            .map(|range| combine_range_across(&[&a, &b], *range))
+            .collect();
+
+        // equals merging the wheels and then querying
+        a.merge(&mut b).unwrap();
+        let merged: Vec<_> = ranges.iter().map(|range| a.combine_range(*range)).collect();
+        assert!(across.iter().all(Option::is_some));
+        assert_eq!(across, merged);
+
+        // wheels must be aligned by time
+        let mut behind: Haw<U64SumAggregator> = Haw::default();
+        behind.delta_advance(vec![Some(1); 3600]);
+        assert_eq!(combine_range_across(&[&a, &behind], ranges[0]), None);
+        assert_eq!(
+            combine_range_across::<U64SumAggregator>(&[], ranges[0]),
+            None
+        );
+    }
+
+    #[test]
+    fn landmark_with_cost_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        assert_eq!(haw.landmark_with_cost(), (None, 0));
+
+        // seconds only
+        haw.delta_advance(vec![Some(1); 30]);
+        assert_eq!(haw.landmark_with_cost(), (Some(30), 0));
+
+        // grow into the days wheel
+        for _ in 0..25 * 60 {
+            haw.delta_advance(vec![Some(1); 60]);
+        }
+        haw.delta_advance(vec![Some(1); 75]);
+
+        let non_empty = haw.granularity_totals().iter().flatten().count();
+        assert_eq!(non_empty, 4);
+        let (landmark, combines) = haw.landmark_with_cost();
+        assert_eq!(landmark, haw.landmark());
+        assert_eq!(combines, non_empty - 1);
+    }
+
+    #[test]
+    fn max_query_range_test() {
+        let conf = HawConf::default().with_max_query_range(Duration::days(90));
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        haw.delta_advance(vec![Some(1); 120]);
+
+        let within = WheelRange::new_unchecked(0, 120000);
+        assert_eq!(haw.try_combine_range(within), Ok(Some(120)));
+        assert_eq!(haw.combine_range(within), Some(120));
+        assert_eq!(
+            haw.combine_range_at(within, Granularity::Minute),
+            Ok(Some(120))
+        );
+
+        // exactly the maximum is allowed while longer ranges are rejected before planning
+        let max_ms = Duration::days(90).whole_milliseconds() as u64;
+        assert!(haw
+            .try_combine_range(WheelRange::new_unchecked(0, max_ms))
+            .is_ok());
+        let oversized = WheelRange::new_unchecked(0, max_ms + DAY_TICK_MS);
+        let error = QueryError::RangeTooLarge {
+            range_ms: max_ms + DAY_TICK_MS,
+            max_ms,
+        };
+        assert_eq!(haw.try_combine_range(oversized), Err(error));
+        assert_eq!(
+            haw.combine_range_at(oversized, Granularity::Day),
+            Err(error)
+        );
+        assert_eq!(haw.combine_range(oversized), None);
+
+        // every other range entry point is guarded as well
+        assert_eq!(haw.combine_range_fold(oversized, 0, |acc, p| acc + p), None);
+        assert_eq!(
+            haw.combine_range_fold(within, 0, |acc, p| acc + p),
+            Some(120)
+        );
+        assert!(!haw.combine_range_exceeds(oversized, 0));
+        assert!(haw.combine_range_exceeds(within, 0));
+        assert_eq!(haw.range(oversized), None);
+        assert!(haw.range(within).is_some());
+        assert_eq!(haw.range_and_lower(oversized), None);
+        assert_eq!(haw.decimate(oversized, 10), None);
+        assert_eq!(haw.group_by(oversized, Duration::days(1)), None);
+        assert!(haw.dense_series(oversized, Duration::days(1), 0).is_empty());
+        assert_eq!(haw.combine_range_explained(oversized), (None, None));
+        assert_eq!(haw.dump_combine_range(oversized), None);
+        assert!(haw.combine_range_result(oversized).is_empty());
+
+        // unlimited by default
+        let mut unlimited: Haw<U64SumAggregator> = Haw::default();
+        unlimited.delta_advance(vec![Some(1); 120]);
+        assert!(unlimited.try_combine_range(oversized).is_ok());
+    }
+
+    #[test]
+    fn epoch_offset_test() {
+        // 2000-01-01T00:00:00Z
+        let epoch = 946684800000;
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_epoch_offset(epoch));
+        // the same data inserted relative to the unix epoch
+        let mut unix: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_watermark(epoch));
+
+        let deltas: Vec<_> = (0..2 * 3600 + 90).map(|i| Some(i % 7)).collect();
+        haw.delta_advance(deltas.clone());
+        unix.delta_advance(deltas);
+        assert_eq!(haw.watermark() + epoch, unix.watermark());
+
+        // date ranges resolve to the same absolute instants
+        let at = |offset_ms: u64| epoch + offset_ms;
+        let ranges = [
+            WheelRange::new_unchecked(at(0), at(HOUR_TICK_MS)),
+            WheelRange::new_unchecked(at(HOUR_TICK_MS), at(2 * HOUR_TICK_MS + 60000)),
+            WheelRange::new_unchecked(at(HOUR_TICK_MS), at(2 * HOUR_TICK_MS + 80000)),
+            WheelRange::new_unchecked(at(2 * HOUR_TICK_MS + 70000), at(2 * HOUR_TICK_MS + 90000)),
+        ];
+        for range in ranges {
+            assert!(haw.combine_range(range).is_some());
+            assert_eq!(haw.combine_range(range), unix.combine_range(range));
+        }
+        assert_eq!(haw.interval(90.minutes()), unix.interval(90.minutes()));
+        assert_eq!(
+            haw.combine_range_at(ranges[0], Granularity::Hour),
+            unix.combine_range_at(ranges[0], Granularity::Hour)
+        );
+
+        // ranges before the epoch are not covered by the wheel
+        let before = WheelRange::new_unchecked(epoch - HOUR_TICK_MS, epoch);
+        assert_eq!(haw.combine_range(before), None);
+
+        // timestamps of individual slots are relative to the epoch of the wheel
+        let start = 2 * HOUR_TICK_MS + 70000;
+        let slots = haw
+            .range(WheelRange::new_unchecked(at(start), at(start + 2000)))
+            .unwrap();
+        assert_eq!(slots, vec![(start, 4), (start + 1000, 5)]);
+
+        let mut other: Haw<U64SumAggregator> = Haw::default();
+        assert_eq!(
+            haw.merge(&mut other),
+            Err(MergeError::IncompatibleEpoch {
+                expected: epoch,
+                found: 0
+            })
+        );
+    }
+
+    #[test]
+    fn inclusive_range_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance((1..=120).map(Some));
+
+        let half_open = WheelRange::new_unchecked(60000, 65000);
+        let inclusive = WheelRange::inclusive(60000, 65000).unwrap();
+        assert_eq!(
+            inclusive.duration().whole_seconds(),
+            half_open.duration().whole_seconds() + 1
+        );
+        assert_eq!(haw.combine_range(half_open), Some((61..=65).sum()));
+        assert_eq!(haw.combine_range(inclusive), Some((61..=66).sum()));
+
+        // the second containing a fractional end is included once
+        assert_eq!(
+            WheelRange::inclusive(60000, 65500).unwrap(),
+            WheelRange::new_unchecked(60000, 66000)
+        );
+        assert_eq!(
+            WheelRange::inclusive(0, 1500).unwrap(),
+            WheelRange::new_unchecked(0, 2000)
+        );
+        assert_eq!(
+            WheelRange::inclusive(0, 500).unwrap(),
+            WheelRange::new_unchecked(0, 1000)
+        );
+        assert_eq!(
+            haw.combine_range(WheelRange::inclusive(60000, 65500).unwrap()),
+            Some((61..=66).sum())
+        );
+
+        // aligned bounds extend by one unit of their lowest granularity
+        assert_eq!(
+            WheelRange::inclusive(0, 60000).unwrap(),
+            WheelRange::new_unchecked(0, 120000)
+        );
+        assert_eq!(
+            WheelRange::inclusive(0, 3600000).unwrap(),
+            WheelRange::new_unchecked(0, 7200000)
+        );
+        assert_eq!(
+            WheelRange::inclusive(0, 86400000).unwrap(),
+            WheelRange::new_unchecked(0, 2 * 86400000)
+        );
+        // a fractional end is not aligned to any coarser granularity
+        assert_eq!(
+            WheelRange::inclusive(0, 60500).unwrap(),
+            WheelRange::new_unchecked(0, 61000)
+        );
+        assert_eq!(
+            WheelRange::inclusive(0, 3600500).unwrap(),
+            WheelRange::new_unchecked(0, 3601000)
+        );
+        // as is a fractional start
+        assert_eq!(
+            WheelRange::inclusive(500, 60000).unwrap(),
+            WheelRange::new_unchecked(500, 61000)
+        );
+
+        // ends that cannot be extended are rejected rather than overflowing
+        assert!(matches!(
+            WheelRange::inclusive(0, u64::MAX),
+            Err(RangeError::InvalidEnd { end_ms: u64::MAX })
+        ));
+        let aligned_max = u64::MAX - u64::MAX % 1000;
+        assert!(matches!(
+            WheelRange::inclusive(0, aligned_max),
+            Err(RangeError::InvalidEnd { end_ms }) if end_ms == aligned_max
+        ));
+    }
+
+    #[test]
+    fn interval_complete_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // 10 minutes and 30 seconds where each second holds its minute
+        haw.delta_advance((0..630).map(|second| Some(second / 60)));
+        assert_eq!(haw.watermark(), 630000);
+
+        // the minutes [5, 10) while the partial minute 10 is excluded
+        let complete = haw.interval_complete(Granularity::Minute, 5);
+        assert_eq!(complete, Some((5..10).sum::<u64>() * 60));
+        assert_eq!(
+            complete,
+            haw.combine_range(WheelRange::new_unchecked(300000, 600000))
+        );
+        assert_ne!(complete, haw.interval(5.minutes()));
+
+        // sealing the current minute moves the window forward
+        haw.delta_advance(vec![Some(10); 30]);
+        assert_eq!(
+            haw.interval_complete(Granularity::Minute, 5),
+            Some((6..11).sum::<u64>() * 60)
+        );
+
+        assert_eq!(haw.interval_complete(Granularity::Minute, 12), None);
+        assert_eq!(haw.interval_complete(Granularity::Hour, 1), None);
+    }
+
+    #[test]
+    fn diff_test() {
+        let conf = HawConf::default().with_deltas();
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        let mut replica: Haw<U64SumAggregator> = Haw::new(conf);
+
+        let deltas: Vec<_> = (0..5 * 3600 + 130u64)
+            .map(|i| (i % 7 != 0).then_some(i % 11))
+            .collect();
+        let (synced, pending) = deltas.split_at(2 * 3600 + 30);
+        haw.delta_advance(synced.to_vec());
+        replica.delta_advance(synced.to_vec());
+
+        // a replica at the same watermark receives an empty diff
+        let diff = haw.diff(&replica).unwrap();
+        assert!(diff.is_empty());
+        assert_eq!(diff.watermark(), haw.watermark());
+
+        haw.delta_advance(pending.to_vec());
+        let diff = haw.diff(&replica).unwrap();
+        assert_eq!(diff.base_watermark(), replica.watermark());
+        assert_eq!(
+            replica.diff(&haw).err(),
+            Some(DiffError::BaseAhead {
+                base: haw.watermark(),
+                watermark: replica.watermark(),
+            })
+        );
+
+        replica.apply_diff(&diff).unwrap();
+        assert_eq!(replica.watermark(), haw.watermark());
+        assert_eq!(replica.landmark(), haw.landmark());
+        assert_eq!(replica.delta.deltas, haw.delta.deltas);
+        for granularity in [
+            Granularity::Second,
+            Granularity::Minute,
+            Granularity::Hour,
+            Granularity::Day,
+        ] {
+            let state = |haw: &Haw<U64SumAggregator>| {
+                haw.wheel(granularity).map(|wheel| {
+                    (
+                        wheel.len(),
+                        wheel.watermark(),
+                        wheel.rotation_count(),
+                        wheel.total(),
+                    )
+                })
+            };
+            assert_eq!(state(&replica), state(&haw));
+        }
+
+        let watermark = haw.watermark();
+        for range in [
+            WheelRange::new_unchecked(0, 5 * 3600 * 1000),
+            WheelRange::new_unchecked(3600 * 1000, watermark),
+            WheelRange::new_unchecked(watermark - 50000, watermark - 10000),
+        ] {
+            assert!(haw.combine_range(range).is_some());
+            assert_eq!(replica.combine_range(range), haw.combine_range(range));
+        }
+
+        // the diff cannot be applied twice
+        assert_eq!(
+            replica.apply_diff(&diff),
+            Err(DiffError::WatermarkMismatch {
+                expected: diff.base_watermark(),
+                found: watermark,
+            })
+        );
+
+        // frequent syncs only ship the few slots appended in between
+        haw.delta_advance(vec![Some(3); 30]);
+        let diff = haw.diff(&replica).unwrap();
+        assert!(diff.len() <= 31);
+        replica.apply_diff(&diff).unwrap();
+        assert_eq!(replica.landmark(), haw.landmark());
+        let range = WheelRange::new_unchecked(watermark - 30000, watermark + 30000);
+        assert_eq!(replica.combine_range(range), haw.combine_range(range));
+    }
+
+    #[test]
+    fn dense_series_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // data in [0, 10) and [20, 30) seconds with gaps in between
+        haw.delta_advance(vec![Some(1); 10]);
+        haw.delta_advance(vec![None; 10]);
+        haw.delta_advance(vec![Some(2); 10]);
+        haw.delta_advance(vec![None; 10]);
+
+        let range = WheelRange::new_unchecked(0, 40000);
+        let series = haw.dense_series(range, 5.seconds(), 99);
+        assert_eq!(series.len(), 8);
+        let timestamps: Vec<u64> = series.iter().map(|(ts, _)| *ts).collect();
+        assert_eq!(timestamps, (0..40000).step_by(5000).collect::<Vec<_>>());
+        let values: Vec<u64> = series.iter().map(|(_, agg)| *agg).collect();
+        assert_eq!(values, vec![5, 5, 99, 99, 10, 10, 99, 99]);
+
+        // filling with the identity matches group_by
+        let identity = haw.dense_series(range, 5.seconds(), U64SumAggregator::IDENTITY);
+        assert_eq!(Some(identity), haw.group_by(range, 5.seconds()));
+
+        // the last bucket is cut off at the end of the range and buckets beyond the watermark are filled
+        let series = haw.dense_series(WheelRange::new_unchecked(22000, 43000), 10.seconds(), 99);
+        assert_eq!(series, vec![(22000, 16), (32000, 99), (42000, 99)]);
+
+        assert!(haw.dense_series(range, 0.seconds(), 99).is_empty());
+
+        // sub-second buckets are rejected rather than truncated to whole seconds
+        assert!(haw
+            .dense_series(range, Duration::milliseconds(1500), 99)
+            .is_empty());
+        assert!(haw
+            .dense_series(range, Duration::milliseconds(500), 99)
+            .is_empty());
+
+        // slots that are read once per series match the result of querying every bucket
+        let expected: Vec<(u64, u64)> = (0..40000)
+            .step_by(5000)
+            .map(|ts| {
+                let bucket = WheelRange::new_unchecked(ts, ts + 5000);
+                (ts, haw.combine_range_result(bucket).value().unwrap_or(99))
+            })
+            .collect();
+        assert_eq!(haw.dense_series(range, 5.seconds(), 99), expected);
+
+        // minute buckets beyond the retention of the seconds wheel are read from the minutes wheel
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance(vec![Some(1); 60]);
+        haw.delta_advance(vec![None; 60]);
+        haw.delta_advance(vec![Some(2); 60]);
+        let range = WheelRange::new_unchecked(0, 180000);
+        let series = haw.dense_series(range, 1.minutes(), 99);
+        assert_eq!(series, vec![(0, 60), (60000, 99), (120000, 120)]);
+        let series = haw.dense_series(range, 2.minutes(), 99);
+        assert_eq!(series, vec![(0, 60), (120000, 120)]);
+    }
+
+    #[test]
+    fn decimate_test() {
+        let conf = HawConf::default().with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+        // a steady signal of 1 per second with a single spike at second 1234
+        haw.delta_advance((0..3601u64).map(|sec| Some(if sec == 1234 { 1000 } else { 1 })));
+
+        // an hour of seconds starting at second 1
+        let range = WheelRange::new_unchecked(1000, 3601 * 1000);
+        let points = haw.decimate(range, 60).unwrap();
+        assert_eq!(points.len(), 60);
+        assert!(points.iter().all(|point| point.slots == 60));
+        assert_eq!(points[1].timestamp, 61000);
+
+        let spike_idx = (1234 - 1) / 60;
+        let spike = &points[spike_idx];
+        assert_eq!(spike.aggregate, 59 + 1000);
+        assert_eq!((spike.min, spike.max), (1, 1000));
+
+        // averaging the bucket smooths away the spike while the max extent keeps it
+        let average = spike.aggregate / spike.slots as u64;
+        assert!(average < 20);
+        assert_eq!(points.iter().map(|point| point.max).max(), Some(1000));
+        assert!(points
+            .iter()
+            .enumerate()
+            .all(|(i, point)| i == spike_idx || point.max == 1));
+
+        // uneven buckets and more points than slots
+        let range = WheelRange::new_unchecked(3591 * 1000, 3601 * 1000);
+        let points = haw.decimate(range, 3).unwrap();
+        assert_eq!(
+            points.iter().map(|p| p.slots).collect::<Vec<_>>(),
+            vec![4, 4, 2]
+        );
+        assert_eq!(haw.decimate(range, 100).unwrap().len(), 10);
+        assert_eq!(haw.decimate(range, 0), None);
+    }
+
+    #[test]
+    fn size_breakdown_test() {
+        let deltas = || (0..3 * 3600u64).map(Some);
+
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        assert_eq!(haw.size_breakdown().total(), 0);
+        haw.delta_advance(deltas());
+
+        let breakdown = haw.size_breakdown();
+        assert_eq!(breakdown.total(), haw.size_bytes());
+        assert!(breakdown.days.layout.is_none());
+        for granularity in [Granularity::Second, Granularity::Minute, Granularity::Hour] {
+            let level = breakdown.level(granularity);
+            assert!(matches!(level.layout, Some(DataLayout::Normal)));
+            assert!(level.slot_bytes > 0);
+            assert_eq!(level.prefix_bytes, 0);
         }
 
-        // Fire any outgoing timers
-        #[cfg(feature = "timer")]
-        {
-            let mut timer = self.timer.write();
+        // the seconds wheel retains 59 slots of 8 bytes each plus an occupancy flag per slot
+        let slots = haw.seconds_unchecked().len();
+        assert_eq!(slots, SECONDS - 1);
+        assert_eq!(breakdown.seconds.slot_bytes, slots * mem::size_of::<u64>());
+        assert_eq!(breakdown.seconds.occupancy_bytes, slots);
+
+        // a prefix-enabled seconds wheel additionally holds a prefix-sum entry per slot
+        let prefix_seconds =
+            WheelConf::new(SECOND_TICK_MS, SECONDS).with_data_layout(DataLayout::Prefix);
+        let mut prefix_haw: Haw<U64SumAggregator> =
+            Haw::new(HawConf::default().with_seconds(prefix_seconds));
+        prefix_haw.delta_advance(deltas());
+
+        let prefix_breakdown = prefix_haw.size_breakdown();
+        assert_eq!(prefix_breakdown.total(), prefix_haw.size_bytes());
+        let seconds = prefix_breakdown.seconds;
+        assert!(matches!(seconds.layout, Some(DataLayout::Prefix)));
+        assert_eq!(seconds.slot_bytes, breakdown.seconds.slot_bytes);
+        assert_eq!(seconds.prefix_bytes, slots * mem::size_of::<u64>());
+        assert_eq!(seconds.occupancy_bytes, breakdown.seconds.occupancy_bytes);
+        assert!(seconds.total() > breakdown.seconds.total());
+        assert_eq!(prefix_breakdown.minutes.total(), breakdown.minutes.total());
+    }
 
-            for action in timer.advance_to(self.watermark) {
-                match action {
-                    TimerAction::Oneshot(udf) => {
-                        udf(self);
-                    }
-                    TimerAction::Repeat((at, interval, udf)) => {
-                        udf(self);
-                        let new_at = at + interval.whole_milliseconds() as u64;
-                        let _ =
-                            timer.schedule_at(new_at, TimerAction::Repeat((new_at, interval, udf)));
-                    }
+    #[test]
+    fn const_size_estimation_test() {
+        const SLOTS: usize = total_slots(&HawConf::DEFAULT);
+        const BYTES: usize = estimated_bytes::<U64SumAggregator>(&HawConf::DEFAULT);
+        assert_eq!(SLOTS, Haw::<U64SumAggregator>::TOTAL_WHEEL_SLOTS);
+
+        // fills every granularity up to its capacity
+        let full = |conf: HawConf| {
+            let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+            for wheel in [
+                &mut haw.seconds_wheel,
+                &mut haw.minutes_wheel,
+                &mut haw.hours_wheel,
+                &mut haw.days_wheel,
+                &mut haw.weeks_wheel,
+                &mut haw.years_wheel,
+            ] {
+                let capacity = wheel.conf().capacity;
+                let wheel = wheel.get_or_insert();
+                for _ in 0..capacity {
+                    wheel.insert_empty_slot();
                 }
             }
-        }
-    }
+            haw
+        };
 
-    /// Returns a reference to the seconds wheel
-    pub fn seconds(&self) -> Option<&Wheel<A>> {
-        self.seconds_wheel.as_ref()
-    }
-    /// Returns an unchecked reference to the seconds wheel
-    pub fn seconds_unchecked(&self) -> &Wheel<A> {
-        self.seconds_wheel.as_ref().unwrap()
-    }
+        let haw = full(HawConf::DEFAULT);
+        assert_eq!(haw.len(), SLOTS);
+        assert_eq!(haw.size_bytes(), BYTES);
 
-    /// Returns a reference to the minutes wheel
-    pub fn minutes(&self) -> Option<&Wheel<A>> {
-        self.minutes_wheel.as_ref()
-    }
-    /// Returns an unchecked reference to the minutes wheel
-    pub fn minutes_unchecked(&self) -> &Wheel<A> {
-        self.minutes_wheel.as_ref().unwrap()
-    }
-    /// Returns a reference to the hours wheel
-    pub fn hours(&self) -> Option<&Wheel<A>> {
-        self.hours_wheel.as_ref()
-    }
-    /// Returns an unchecked reference to the hours wheel
-    pub fn hours_unchecked(&self) -> &Wheel<A> {
-        self.hours_wheel.as_ref().unwrap()
-    }
-    /// Returns a reference to the days wheel
-    pub fn days(&self) -> Option<&Wheel<A>> {
-        self.days_wheel.as_ref()
-    }
-    /// Returns an unchecked reference to the days wheel
-    pub fn days_unchecked(&self) -> &Wheel<A> {
-        self.days_wheel.as_ref().unwrap()
+        let conf = HawConf::default()
+            .with_prefix_sum()
+            .with_seconds(WheelConf::new(SECOND_TICK_MS, 120).with_prefix());
+        let haw = full(conf);
+        assert_eq!(haw.len(), total_slots(&conf));
+        assert_eq!(haw.size_bytes(), estimated_bytes::<U64SumAggregator>(&conf));
+        assert!(estimated_bytes::<U64SumAggregator>(&conf) > BYTES);
     }
 
-    /// Returns a reference to the weeks wheel
-    pub fn weeks(&self) -> Option<&Wheel<A>> {
-        self.weeks_wheel.as_ref()
+    #[test]
+    fn combine_range_exceeds_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_access_stats());
+        haw.delta_advance(vec![Some(1); 60]);
+        let range = WheelRange::new_unchecked(10000, 60000);
+
+        // the threshold is crossed after scanning 10 of the 50 seconds
+        assert!(haw.combine_range_exceeds(range, 9));
+        assert_eq!(haw.access_stats().seconds.scans, 10);
+        haw.reset_access_stats();
+
+        assert!(!haw.combine_range_exceeds(range, 50));
+        assert_eq!(haw.access_stats().seconds.scans, 50);
+        haw.reset_access_stats();
+
+        assert!(haw.combine_range_exceeds(range, 49));
+
+        // ranges spanning multiple granularities
+        haw.delta_advance(vec![Some(1); 120]);
+        let range = WheelRange::new_unchecked(60000, 150000);
+        assert_eq!(haw.combine_range(range), Some(90));
+        assert!(haw.combine_range_exceeds(range, 89));
+        assert!(!haw.combine_range_exceeds(range, 90));
+
+        // non-monotonic aggregators combine the full range
+        assert!(!I64SumAggregator::monotonic());
+        let mut haw: Haw<I64SumAggregator> = Haw::default();
+        haw.delta_advance([Some(5), Some(-3), Some(1)]);
+        let range = WheelRange::new_unchecked(0, 3000);
+        assert!(haw.combine_range_exceeds(range, 2));
+        assert!(!haw.combine_range_exceeds(range, 3));
     }
 
-    /// Returns an unchecked reference to the weeks wheel
-    pub fn weeks_unchecked(&self) -> &Wheel<A> {
-        self.weeks_wheel.as_ref().unwrap()
-    }
+    #[test]
+    fn seed_granularity_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        haw.delta_advance(vec![Some(1); 30]);
 
-    /// Returns a reference to the years wheel
-    pub fn years(&self) -> Option<&Wheel<A>> {
-        self.years_wheel.as_ref()
-    }
-    /// Returns a reference to the years wheel
-    pub fn years_unchecked(&self) -> &Wheel<A> {
-        self.years_wheel.as_ref().unwrap()
+        // 90 minutes worth of partials ending at 01:30:00
+        let partials: Vec<_> = (1..=90).map(Some).collect();
+        haw.seed_granularity(Granularity::Minute, &partials, 90 * 60000)
+            .unwrap();
+
+        assert_eq!(haw.watermark(), 90 * 60000);
+        assert!(haw.seconds().is_none());
+        assert_eq!(haw.minutes_unchecked().len(), 59);
+        assert_eq!(haw.hours_unchecked().len(), 1);
+
+        // minute-aligned ranges are answered by the seeded wheels
+        let last_minute = WheelRange::new_unchecked(89 * 60000, 90 * 60000);
+        assert_eq!(haw.combine_range(last_minute), Some(90));
+        let last_ten = WheelRange::new_unchecked(80 * 60000, 90 * 60000);
+        assert_eq!(haw.combine_range(last_ten), Some((81..=90).sum()));
+        let first_hour = WheelRange::new_unchecked(0, 60 * 60000);
+        assert_eq!(haw.combine_range(first_hour), Some((1..=60).sum()));
+        assert_eq!(haw.landmark(), Some((1..=90).sum()));
+
+        // seconds were never seeded
+        let second = WheelRange::new_unchecked(89 * 60000, 89 * 60000 + 1000);
+        assert_eq!(haw.combine_range(second), None);
+        assert_eq!(haw.interval(1.seconds()), None);
+
+        // regular ticks continue on top of the seeded wheels
+        haw.delta_advance(vec![Some(1); 60]);
+        let next_minute = WheelRange::new_unchecked(90 * 60000, 91 * 60000);
+        assert_eq!(haw.combine_range(next_minute), Some(60));
+        assert_eq!(haw.landmark(), Some((1..=90).sum::<u64>() + 60));
+
+        // partials that would start before the epoch are rejected without touching the wheel
+        let watermark = haw.watermark();
+        let error = SeedError::StartBeforeEpoch {
+            partials: 2,
+            watermark: 60000,
+        };
+        assert_eq!(
+            haw.seed_granularity(Granularity::Minute, &[Some(1), Some(2)], 60000),
+            Err(error)
+        );
+        assert_eq!(haw.watermark(), watermark);
+        assert_eq!(haw.landmark(), Some((1..=90).sum::<u64>() + 60));
     }
 
-    /// Merges two wheels
-    ///
-    /// Note that the time in `other` may be advanced and thus change state
-    ///
-    /// # Panics
-    ///
-    /// The function currently panics if another data layout than Array is used.
-    pub(crate) fn merge(&mut self, other: &mut Self) {
-        let other_watermark = other.watermark();
+    #[test]
+    fn plan_sub_ranges_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // advance to 02:00:30
+        haw.delta_advance(vec![Some(1); 2 * 3600 + 30]);
+
+        let assert_tiles = |range: WheelRange| {
+            let sub_ranges = haw.plan_sub_ranges(range);
+            assert_eq!(sub_ranges.first().map(|r| r.start), Some(range.start));
+            assert_eq!(sub_ranges.last().map(|r| r.end), Some(range.end));
+            for pair in sub_ranges.windows(2) {
+                assert!(pair[0].start < pair[0].end);
+                assert_eq!(pair[0].end, pair[1].start);
+            }
+            sub_ranges
+        };
 
-        // make sure both wheels are aligned by time
-        if self.watermark() > other_watermark {
-            other.advance_to(self.watermark(), &mut WriterWheel::default());
-        } else {
-            self.advance_to(other_watermark, &mut WriterWheel::default());
-        }
+        // 01:00:00 - 02:00:20 spans hours and seconds
+        let range = WheelRange::new_unchecked(3600000, 7220000);
+        assert_eq!(assert_tiles(range).len(), 2);
 
-        // merge all aggregation wheels
-        self.seconds_wheel.merge(&other.seconds_wheel);
-        self.minutes_wheel.merge(&other.minutes_wheel);
-        self.hours_wheel.merge(&other.hours_wheel);
-        self.days_wheel.merge(&other.days_wheel);
-        self.weeks_wheel.merge(&other.weeks_wheel);
-        self.years_wheel.merge(&other.years_wheel);
-    }
-    #[cfg(feature = "profiler")]
-    /// Returns a reference to the stats of the [Haw]
-    pub fn stats(&self) -> &Stats {
-        &self.stats
+        // 01:30:00 - 02:00:20 spans minutes and seconds
+        let range = WheelRange::new_unchecked(5400000, 7220000);
+        let sub_ranges = assert_tiles(range);
+        assert_eq!(sub_ranges.len(), 2);
+
+        // executing the sub-ranges separately yields the same result
+        let combined = sub_ranges
+            .into_iter()
+            .map(|r| haw.combine_range(r).unwrap())
+            .sum::<u64>();
+        assert_eq!(Some(combined), haw.combine_range(range));
+
+        // ranges the wheel cannot answer are still split along calendar boundaries
+        let range = WheelRange::new_unchecked(10000, 7220000);
+        assert_eq!(assert_tiles(range).len(), 4);
+        assert!(haw
+            .plan_sub_ranges(WheelRange::new_unchecked(0, 0))
+            .is_empty());
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use crate::{
-        aggregator::sum::{U32SumAggregator, U64SumAggregator},
-        duration::NumericalDuration,
-        wheels::read::plan::Aggregation,
-    };
-    use proptest::prelude::*;
-    use time::macros::datetime;
+    #[test]
+    fn peak_slot_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+        // one per second with a burst of 10 per second within minute 42
+        haw.delta_advance((0..3600).map(|sec| Some(if sec / 60 == 42 { 10 } else { 1 })));
 
-    use super::*;
+        assert_eq!(
+            haw.peak_slot(Granularity::Minute, 60),
+            Some((42 * 60000, 600))
+        );
+
+        // the peak outside the lookback is not considered (ties favour the most recent minute)
+        assert_eq!(
+            haw.peak_slot(Granularity::Minute, 10),
+            Some((59 * 60000, 60))
+        );
+
+        // a custom comparator selects the smallest slot instead
+        assert_eq!(
+            haw.peak_slot_by(Granularity::Second, 60, |a, b| b.cmp(a)),
+            Some((3599000, 1))
+        );
+        assert_eq!(haw.peak_slot(Granularity::Day, 7), None);
+    }
 
     #[test]
-    fn delta_advance_test() {
+    fn query_cache_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_query_cache(2));
+        haw.delta_advance((0..120).map(Some));
+
+        let range = WheelRange::new_unchecked(65000, 110000);
+        let (result, ops, _) = haw.analyze_combine_range(range);
+        assert!(ops > 0);
+
+        // served from the cache without any combine operations
+        let (cached, ops, _) = haw.analyze_combine_range(range);
+        assert_eq!((cached, ops), (result, 0));
+        assert_eq!(haw.cache.len(), 1);
+
+        // least recently used entries are evicted
+        let other = WheelRange::new_unchecked(60000, 100000);
+        let another = WheelRange::new_unchecked(70000, 100000);
+        haw.analyze_combine_range(other);
+        haw.analyze_combine_range(another);
+        assert_eq!(haw.cache.len(), 2);
+        assert!(haw.analyze_combine_range(range).1 > 0);
+        assert_eq!(haw.analyze_combine_range(another).1, 0);
+
+        // advancing invalidates the cache
+        haw.delta_advance([Some(1)]);
+        assert_eq!(haw.cache.len(), 0);
+        let (advanced, ops, _) = haw.analyze_combine_range(range);
+        assert_eq!(advanced, result);
+        assert!(ops > 0);
+
+        // clearing the wheels invalidates the cache as well
+        assert_eq!(haw.analyze_combine_range(range).1, 0);
+        haw.clear();
+        assert_eq!(haw.cache.len(), 0);
+        assert_ne!(haw.combine_range(range), result);
+
+        // the cache does not make the wheel !Sync
+        #[cfg(feature = "std")]
+        {
+            fn assert_sync<T: Sync>() {}
+            assert_sync::<QueryCache<u64>>();
+        }
+
+        // caching is disabled by default
         let mut haw: Haw<U64SumAggregator> = Haw::default();
-        // oldest to newest
-        let deltas = vec![Some(10), None, Some(50), None];
+        haw.delta_advance((0..120).map(Some));
+        haw.analyze_combine_range(range);
+        assert!(haw.analyze_combine_range(range).1 > 0);
+        assert_eq!(haw.cache.len(), 0);
+    }
 
-        // Visualization:
-        // 10
-        // 0, 10
-        // 50, 0, 10
-        // 0, 50, 0, 10
-        haw.delta_advance(deltas);
+    #[test]
+    fn unrepresentable_watermark_test() {
+        // beyond the largest date that can be represented
+        let watermark = 300_000_000_000_000;
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_watermark(watermark));
+        haw.delta_advance([Some(1), Some(2)]);
 
-        assert_eq!(haw.interval(1.seconds()), Some(0));
-        assert_eq!(haw.interval(2.seconds()), Some(50));
-        assert_eq!(haw.interval(3.seconds()), Some(50));
-        assert_eq!(haw.interval(4.seconds()), Some(60));
+        assert_eq!(haw.interval(1.seconds()), None);
+        assert_eq!(
+            haw.try_interval(1.seconds()),
+            Err(QueryError::InvalidTimestamp {
+                ts_ms: watermark + 2000
+            })
+        );
+        assert_eq!(haw.interval_multi(&[1.seconds()]), vec![None]);
+        assert_eq!(
+            haw.try_interval_multi(&[1.seconds()]),
+            Err(QueryError::InvalidTimestamp {
+                ts_ms: watermark + 2000
+            })
+        );
+        assert_eq!(haw.combine_range(WheelRange::new_unchecked(0, 1000)), None);
+        assert_eq!(
+            haw.try_combine_range(WheelRange::new_unchecked(0, 1000)),
+            Err(QueryError::InvalidTimestamp {
+                ts_ms: watermark + 2000
+            })
+        );
+
+        // large but representable watermarks are answered correctly
+        let watermark = 250_000_000_000_000;
+        let mut haw: Haw<U64SumAggregator> = Haw::new(HawConf::default().with_watermark(watermark));
+        haw.delta_advance([Some(1), Some(2)]);
+
+        assert_eq!(haw.interval(2.seconds()), Some(3));
+        assert_eq!(haw.try_interval(1.seconds()), Ok(Some(2)));
+        let range = WheelRange::new_unchecked(watermark + 1000, watermark + 2000);
+        assert_eq!(haw.combine_range(range), Some(2));
+        assert_eq!(haw.try_combine_range(range), Ok(Some(2)));
+        assert_eq!(haw.try_interval_multi(&[1.seconds()]), Ok(vec![Some(2)]));
     }
 
     #[test]
@@ -1817,6 +7570,132 @@ mod tests {
         assert_eq!(haw.combine_range(WheelRange { start, end }), None);
     }
 
+    #[test]
+    fn access_stats_outlier_test() {
+        // 2023-11-09 00:00:00
+        let watermark = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(watermark)
+            .with_access_stats();
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        // advance by 1 hour
+        let deltas: Vec<Option<u64>> = (0..3600).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+
+        // queries are not recorded unless enabled
+        let mut untracked: Haw<U64SumAggregator> =
+            Haw::new(HawConf::default().with_watermark(watermark));
+        untracked.delta_advance(vec![Some(1); 3600]);
+        let start = datetime!(2023-11-09 00:10:00 UTC);
+        let end = datetime!(2023-11-09 00:40:00 UTC);
+        assert_eq!(
+            untracked.combine_range(WheelRange { start, end }),
+            Some(1800)
+        );
+        assert_eq!(untracked.access_stats(), AccessStats::default());
+
+        // hammer the minutes wheel
+        let start = datetime!(2023-11-09 00:10:00 UTC);
+        let end = datetime!(2023-11-09 00:40:00 UTC);
+        for _ in 0..50 {
+            assert_eq!(haw.combine_range(WheelRange { start, end }), Some(1800));
+        }
+
+        // single query on the seconds wheel
+        let start = datetime!(2023-11-09 00:59:50 UTC);
+        let end = datetime!(2023-11-09 01:00:00 UTC);
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(10));
+
+        let stats = haw.access_stats();
+        assert_eq!(stats.minutes.queries, 50);
+        assert_eq!(stats.minutes.avg_scans(), 30.0);
+        assert_eq!(stats.seconds.queries, 1);
+        assert!(stats.minutes.outlier);
+        assert!(!stats.seconds.outlier);
+        assert!(!stats.hours.outlier);
+
+        // convert the hot granularity to a prefix-enabled wheel
+        haw.adapt_layout(AdaptiveLayout::Outliers);
+        assert!(haw.minutes_wheel.prefix_support());
+        assert!(!haw.seconds_wheel.prefix_support());
+
+        // prefix-enabled wheels answer the range with a single operation
+        haw.reset_access_stats();
+        let start = datetime!(2023-11-09 00:10:00 UTC);
+        let end = datetime!(2023-11-09 00:40:00 UTC);
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(1800));
+        assert_eq!(haw.access_stats().minutes.avg_scans(), 1.0);
+    }
+
+    #[test]
+    fn adaptive_layout_test() {
+        // 2023-11-09 00:00:00
+        let watermark = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(watermark)
+            .with_adaptive_layout(20.0);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        // advance by 1 hour
+        let deltas: Vec<Option<u64>> = (0..3600).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+        let mut waw = WriterWheel::with_watermark(haw.watermark());
+
+        let start = datetime!(2023-11-09 00:10:00 UTC);
+        let end = datetime!(2023-11-09 00:40:00 UTC);
+        for _ in 0..10 {
+            assert_eq!(haw.combine_range(WheelRange { start, end }), Some(1800));
+        }
+        assert!(!haw.minutes_wheel.prefix_support());
+
+        // the minutes wheel is converted while advancing
+        waw.insert(Entry::new(5, haw.watermark()));
+        haw.advance(1.minutes(), &mut waw);
+        assert!(haw.minutes_wheel.prefix_support());
+        assert!(!haw.seconds_wheel.prefix_support());
+
+        // the wheel keeps producing correct results after the conversion
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(1800));
+        let start = datetime!(2023-11-09 00:30:00 UTC);
+        let end = datetime!(2023-11-09 01:01:00 UTC);
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(1805));
+
+        waw.insert(Entry::new(10, haw.watermark()));
+        haw.advance(1.minutes(), &mut waw);
+        let start = datetime!(2023-11-09 01:00:00 UTC);
+        let end = datetime!(2023-11-09 01:02:00 UTC);
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(15));
+    }
+
+    #[test]
+    fn adaptive_layout_inexact_inverse_test() {
+        // 2023-11-09 00:00:00
+        let watermark = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(watermark)
+            .with_adaptive_layout(20.0);
+        let mut haw: Haw<I64SumAggregator> = Haw::new(conf);
+
+        let deltas: Vec<Option<i64>> = (0..3600).map(|_| Some(-1)).collect();
+        haw.delta_advance(deltas);
+        let mut waw = WriterWheel::with_watermark(haw.watermark());
+
+        let start = datetime!(2023-11-09 00:10:00 UTC);
+        let end = datetime!(2023-11-09 00:40:00 UTC);
+        for _ in 0..10 {
+            assert_eq!(haw.combine_range(WheelRange { start, end }), Some(-1800));
+        }
+
+        // the clamping inverse of signed sums is not exact, so the layout is kept
+        haw.advance(1.minutes(), &mut waw);
+        assert!(!haw.minutes_wheel.prefix_support());
+        assert_eq!(haw.combine_range(WheelRange { start, end }), Some(-1800));
+
+        haw.adapt_layout(AdaptiveLayout::Outliers);
+        assert!(!haw.minutes_wheel.prefix_support());
+    }
+
     #[test]
     fn range_query_sec_test() {
         // 2023-11-09 00:00:00
@@ -2038,10 +7917,215 @@ mod tests {
 
         let result = haw.combine_range(WheelRange { start, end });
         let plan = haw.create_exec_plan(WheelRange { start, end });
-        assert!(matches!(plan, Some(ExecutionPlan::CombinedAggregation(_))));
+        // removing [00:00, 05:00) from the landmark is cheaper than combining the range
+        assert!(matches!(
+            plan,
+            Some(ExecutionPlan::InverseLandmarkAggregation(_))
+        ));
         assert_eq!(result, Some(241200));
     }
 
+    #[test]
+    fn inverse_landmark_multi_year_test() {
+        let mut haw: Haw<U64SumAggregator> = Haw::default();
+
+        let day_ms = time::Duration::DAY.whole_milliseconds() as u64;
+        let week_ms = time::Duration::WEEK.whole_milliseconds() as u64;
+        let year_ms = week_ms * WEEKS as u64;
+        let now = 2 * year_ms + 3 * week_ms + 2 * day_ms + 5 * HOUR_TICK_MS;
+
+        // one entry per day with the day index as value, seeded per hour rather than ticked per second
+        let entries: Vec<(u64, u64)> = (0..now)
+            .step_by(day_ms as usize)
+            .map(|ts| (ts, ts / day_ms + 1))
+            .collect();
+        let hours: Vec<Option<u64>> = (0..now)
+            .step_by(HOUR_TICK_MS as usize)
+            .map(|ts| (ts % day_ms == 0).then_some(ts / day_ms + 1))
+            .collect();
+        haw.seed_granularity(Granularity::Hour, &hours, now)
+            .unwrap();
+
+        let range = WheelRange::new_unchecked(year_ms, 2 * year_ms + week_ms);
+        let expected: u64 = entries
+            .iter()
+            .filter(|(ts, _)| *ts >= year_ms && *ts < 2 * year_ms + week_ms)
+            .map(|(_, value)| value)
+            .sum();
+
+        // complements [wheel_start, start) and [end, now) span years, weeks, days and hours
+        let inverse = haw.inverse_landmark_plan(range, haw.wheel_start()).unwrap();
+        let granularities: Vec<Granularity> = inverse.iter().map(|a| a.granularity).collect();
+        assert_eq!(
+            granularities,
+            vec![
+                Granularity::Year,
+                Granularity::Week,
+                Granularity::Day,
+                Granularity::Hour
+            ]
+        );
+
+        let combined = haw
+            .combined_aggregation_plan(haw.split_aligned_ranges(range, haw.wheel_start()).unwrap())
+            .unwrap();
+
+        let (inverse_result, _) =
+            haw.execute_plan(ExecutionPlan::InverseLandmarkAggregation(inverse));
+        let (combined_result, _) = haw.combined_aggregation(combined);
+
+        assert_eq!(inverse_result, Some(expected));
+        assert_eq!(inverse_result, combined_result);
+        assert_eq!(haw.combine_range(range), Some(expected));
+    }
+
+    #[test]
+    fn inverse_landmark_exact_inverse_test() {
+        // 2 days and 13 hours of hourly partials queried up to 12:00 on the third day
+        let now = 61 * HOUR_TICK_MS;
+        let range = WheelRange::new_unchecked(0, 60 * HOUR_TICK_MS);
+        let hours = (now / HOUR_TICK_MS) as usize;
+
+        let mut unsigned: Haw<U64SumAggregator> = Haw::default();
+        unsigned
+            .seed_granularity(Granularity::Hour, &vec![Some(1); hours], now)
+            .unwrap();
+        assert!(matches!(
+            unsigned.create_exec_plan(range),
+            Some(ExecutionPlan::InverseLandmarkAggregation(_))
+        ));
+        assert_eq!(unsigned.combine_range(range), Some(60));
+
+        // the clamping inverse of signed sums cannot remove negative complements
+        let mut signed: Haw<I64SumAggregator> = Haw::default();
+        signed
+            .seed_granularity(Granularity::Hour, &vec![Some(-1); hours], now)
+            .unwrap();
+        assert!(!matches!(
+            signed.create_exec_plan(range),
+            Some(ExecutionPlan::InverseLandmarkAggregation(_))
+        ));
+        assert_eq!(signed.combine_range(range), Some(-60));
+
+        // a large complement absorbs the small partials of the landmark
+        let mut float: Haw<F64SumAggregator> = Haw::default();
+        let partials: Vec<Option<f64>> = (0..hours)
+            .map(|hour| Some(if hour == 60 { 1e17 } else { 1.0 }))
+            .collect();
+        float
+            .seed_granularity(Granularity::Hour, &partials, now)
+            .unwrap();
+        assert!(!matches!(
+            float.create_exec_plan(range),
+            Some(ExecutionPlan::InverseLandmarkAggregation(_))
+        ));
+        assert_eq!(float.combine_range(range), Some(60.0));
+    }
+
+    #[test]
+    fn combine_range_explained_test() {
+        // 2023-11-09 00:00:00
+        let watermark = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(watermark)
+            .with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        let deltas: Vec<Option<u64>> = (0..3600 * 24 * 3).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+
+        let range = WheelRange {
+            start: datetime!(2023 - 11 - 09 15:50:50 UTC),
+            end: datetime!(2023 - 11 - 11 12:30:45 UTC),
+        };
+
+        let (result, plan) = haw.combine_range_explained(range);
+        let plan = plan.unwrap();
+        let (expected_result, expected_cost, _) = haw.analyze_combine_range(range);
+
+        assert_eq!(result, expected_result);
+        assert_eq!(plan.cost(), expected_cost);
+        assert_eq!(Some(plan.clone()), haw.explain_combine_range(range));
+
+        let combined = match plan {
+            ExecutionPlan::CombinedAggregation(combined) => combined,
+            _ => panic!("expected a combined aggregation"),
+        };
+        let steps_cost: usize = combined.aggregations().iter().map(|a| a.cost()).sum();
+        assert_eq!(steps_cost + combined.aggregations().len(), expected_cost);
+
+        // ranges that cannot be answered return no plan
+        let range = WheelRange {
+            start: datetime!(2023 - 11 - 12 00:00:00 UTC),
+            end: datetime!(2023 - 11 - 11 00:00:00 UTC),
+        };
+        assert_eq!(haw.combine_range_explained(range), (None, None));
+    }
+
+    #[test]
+    fn interval_multi_test() {
+        // 2023-11-09 00:00:00
+        let watermark = 1699488000000;
+        let conf = HawConf::default()
+            .with_watermark(watermark)
+            .with_retention_policy(RetentionPolicy::Keep);
+        let mut haw: Haw<U64SumAggregator> = Haw::new(conf);
+
+        // advance by 2 hours and 50 seconds
+        let deltas: Vec<Option<u64>> = (0..7250).map(|_| Some(1)).collect();
+        haw.delta_advance(deltas);
+
+        let durations = [1.minutes(), 5.minutes(), 1.hours()];
+        let (results, multi_cost) = haw.interval_multi_with_stats(&durations);
+        let mut individual_cost = 0;
+        for (duration, result) in durations.iter().zip(results.iter()) {
+            let (expected, cost) = haw.interval_with_stats(*duration);
+            assert_eq!(*result, expected);
+            individual_cost += cost;
+        }
+        assert_eq!(results, vec![Some(60), Some(300), Some(3600)]);
+        assert!(multi_cost <= individual_cost);
+
+        // shorter intervals within the seconds wheel reuse the shared suffix
+        let durations = [40.seconds(), 10.seconds(), 20.seconds(), 20.seconds()];
+        let (results, multi_cost) = haw.interval_multi_with_stats(&durations);
+        let individual_cost: usize = durations
+            .iter()
+            .map(|d| haw.interval_with_stats(*d).1)
+            .sum();
+        assert_eq!(results, vec![Some(40), Some(10), Some(20), Some(20)]);
+        assert!(multi_cost < individual_cost);
+
+        assert!(haw.interval_multi(&[]).is_empty());
+    }
+
+    #[test]
+    fn interval_multi_non_commutative_test() {
+        let mut haw: Haw<DigitConcatAggregator> = Haw::default();
+        let digits = [(25, 1), (35, 2), (45, 3), (55, 4)];
+        haw.delta_advance((0..60).map(|second| {
+            digits
+                .iter()
+                .find(|(at, _)| *at == second)
+                .map(|(_, digit)| DigitConcatAggregator::lift(*digit))
+        }));
+
+        // longer intervals extend the shorter ones backwards in time
+        let durations = [10.seconds(), 20.seconds(), 40.seconds()];
+        let (results, multi_cost) = haw.interval_multi_with_stats(&durations);
+        let (expected, individual_cost): (Vec<_>, Vec<_>) = durations
+            .iter()
+            .map(|d| haw.interval_with_stats(*d))
+            .unzip();
+        assert!(multi_cost < individual_cost.iter().sum());
+        assert_eq!(results, expected);
+        let lowered: Vec<_> = results
+            .into_iter()
+            .map(|result| result.map(DigitConcatAggregator::lower))
+            .collect();
+        assert_eq!(lowered, vec![Some(4), Some(34), Some(1234)]);
+    }
+
     #[test]
     fn month_cross_test() {
         // 2018-08-31 00:00:00
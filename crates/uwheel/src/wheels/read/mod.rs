@@ -5,7 +5,12 @@ pub mod aggregation;
 /// This module contains the Hierarchical Aggregate Wheel (HAW).
 pub mod hierarchical;
 
+mod access;
+mod cache;
+
 mod plan;
+#[cfg(feature = "test-util")]
+mod synthetic;
 
 #[cfg(feature = "profiler")]
 pub(crate) mod stats;
@@ -20,8 +25,38 @@ use crate::{
     window::WindowAggregate,
     WheelRange,
 };
-pub use hierarchical::{Haw, DAYS, HOURS, MINUTES, SECONDS, WEEKS, YEARS};
-pub use plan::ExecutionPlan;
+pub use access::{AccessStats, WheelAccess};
+pub use hierarchical::{
+    combine_range_across,
+    estimated_bytes,
+    total_slots,
+    AdvanceIter,
+    ConcatError,
+    Coverage,
+    CycleExceeded,
+    DecimatedPoint,
+    DiffError,
+    EvictionFn,
+    Granularity,
+    Haw,
+    HistoryView,
+    LevelSize,
+    MergeError,
+    QueryError,
+    QueryResult,
+    SeedError,
+    SizeBreakdown,
+    WheelDiff,
+    DAYS,
+    HOURS,
+    MINUTES,
+    SECONDS,
+    WEEKS,
+    YEARS,
+};
+pub use plan::{ComplexityClass, ExecutionPlan, PlanKind};
+#[cfg(feature = "test-util")]
+pub use synthetic::{SyntheticSpec, ValueDistribution};
 
 use crate::aggregator::Aggregator;
 
@@ -98,6 +133,13 @@ where
         self.inner.write().set_optimizer_hints(hints);
     }
 
+    /// Returns per-granularity access statistics
+    ///
+    /// See [Haw::access_stats] for more information
+    pub fn access_stats(&self) -> AccessStats {
+        self.inner.read().access_stats()
+    }
+
     /// Converts all wheels to be prefix-enabled
     ///
     /// See [Haw::to_prefix_wheels] for more information
@@ -134,6 +176,14 @@ where
         self.inner.write().schedule_once(at, f)
     }
 
+    /// Returns the times of all pending one-shot timers in ascending order
+    ///
+    /// See [`Haw::pending_timers`] for more information.
+    #[cfg(feature = "timer")]
+    pub fn pending_timers(&self) -> Vec<u64> {
+        self.inner.read().pending_timers()
+    }
+
     /// Schedules a timer to fire repeatedly
     ///
     /// See [`Haw::schedule_repeat`] for more information.
@@ -147,11 +197,23 @@ where
         self.inner.write().schedule_repeat(at, interval, f)
     }
 
+    /// Registers a hook that is invoked with every slot evicted by the retention policy of a wheel
+    ///
+    /// See [`Haw::on_evict`] for more information.
+    pub fn on_evict(&self, hook: impl EvictionFn<A::PartialAggregate>) {
+        self.inner.write().on_evict(hook);
+    }
+
     #[doc(hidden)]
     pub fn window(&mut self, window: Window) {
         self.inner.write().window(window);
     }
 
+    #[doc(hidden)]
+    pub fn window_with_offset(&mut self, window: Window, offset: time::UtcOffset) {
+        self.inner.write().window_with_offset(window, offset);
+    }
+
     /// Advance the watermark of the wheel by the given [Duration]
     #[inline]
     #[doc(hidden)]
@@ -163,6 +225,46 @@ where
         self.inner.write().advance(duration, waw)
     }
 
+    /// Advances the time of the wheel aligned by the lowest unit (Second)
+    ///
+    /// See [`Haw::advance_checked`] for more information.
+    #[inline]
+    #[doc(hidden)]
+    pub fn advance_checked(
+        &self,
+        duration: Duration,
+        waw: &mut WriterWheel<A>,
+    ) -> Result<Vec<WindowAggregate<A::PartialAggregate>>, CycleExceeded> {
+        self.inner.write().advance_checked(duration, waw)
+    }
+
+    /// Advances the time of the wheel by at most [`Haw::CYCLE_LENGTH`]
+    ///
+    /// See [`Haw::advance_saturating`] for more information.
+    #[inline]
+    #[doc(hidden)]
+    pub fn advance_saturating(
+        &self,
+        duration: Duration,
+        waw: &mut WriterWheel<A>,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        self.inner.write().advance_saturating(duration, waw)
+    }
+
+    /// Advances the time of the wheel towards the given watermark by performing at most `steps` ticks
+    ///
+    /// See [`Haw::advance_steps`] for more information.
+    #[inline]
+    #[doc(hidden)]
+    pub fn advance_steps(
+        &self,
+        watermark: u64,
+        steps: usize,
+        waw: &mut WriterWheel<A>,
+    ) -> (Vec<WindowAggregate<A::PartialAggregate>>, usize) {
+        self.inner.write().advance_steps(watermark, steps, waw)
+    }
+
     /// Advances the time of the wheel aligned by the lowest unit (Second)
     #[inline]
     pub(crate) fn advance_to(
@@ -173,6 +275,20 @@ where
         self.inner.write().advance_to(watermark, waw)
     }
 
+    /// Advances the time of the wheel to the given watermark while reporting every sealed second
+    ///
+    /// See [`Haw::advance_to_with`] for more information.
+    #[inline]
+    #[doc(hidden)]
+    pub fn advance_to_with(
+        &self,
+        watermark: u64,
+        waw: &mut WriterWheel<A>,
+        on_tick: impl FnMut(u64, A::PartialAggregate),
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        self.inner.write().advance_to_with(watermark, waw, on_tick)
+    }
+
     /// Advances the wheel by applying a set of deltas, each representing the lowest unit.
     ///
     /// See [`Haw::delta_advance`] for more information.
@@ -191,6 +307,13 @@ where
     pub fn watermark(&self) -> u64 {
         self.inner.read().watermark()
     }
+    /// Returns the timestamp of the oldest data point the wheel can still answer queries for
+    ///
+    /// See [`Haw::oldest_timestamp`] for more information.
+    #[inline]
+    pub fn oldest_timestamp(&self) -> Option<u64> {
+        self.inner.read().oldest_timestamp()
+    }
     /// Returns the aggregate in the given time interval
     pub fn interval_and_lower(&self, dur: Duration) -> Option<A::Aggregate> {
         self.interval(dur).map(|partial| A::lower(partial))
@@ -204,6 +327,14 @@ where
         self.inner.read().interval(dur)
     }
 
+    /// Returns the partial aggregate in the given time interval or an error if the watermark cannot be represented as a date
+    ///
+    /// See [`Haw::try_interval`] for more information.
+    #[inline]
+    pub fn try_interval(&self, dur: Duration) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().try_interval(dur)
+    }
+
     /// Returns the partial aggregate in the given time interval and the number of combine operations
     #[inline]
     pub fn interval_with_ops(&self, dur: Duration) -> (Option<A::PartialAggregate>, usize) {
@@ -219,6 +350,157 @@ where
         self.inner.read().combine_range(range)
     }
 
+    /// Combines partial aggregates within the given date range [start, end) or returns an error if the query cannot be answered
+    ///
+    /// See [`Haw::try_combine_range`] for more information.
+    #[inline]
+    pub fn try_combine_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().try_combine_range(range)
+    }
+
+    /// Combines the partial aggregates of the milliseconds within [start, end) into a final partial aggregate
+    ///
+    /// See [`Haw::combine_millis`] for more information.
+    #[inline]
+    pub fn combine_millis(&self, start_ms: u64, end_ms: u64) -> Option<A::PartialAggregate> {
+        self.inner.read().combine_millis(start_ms, end_ms)
+    }
+
+    /// Combines partial aggregates within the given date range [start, end) into a [QueryResult]
+    ///
+    /// See [`Haw::combine_range_result`] for more information.
+    #[inline]
+    pub fn combine_range_result(&self, range: impl Into<WheelRange>) -> QueryResult<A> {
+        self.inner.read().combine_range_result(range)
+    }
+
+    /// Combines partial aggregates within the given date range [start, end) and reports its coverage
+    ///
+    /// See [`Haw::combine_range_checked`] for more information.
+    #[inline]
+    pub fn combine_range_checked(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> (Option<A::PartialAggregate>, Coverage) {
+        self.inner.read().combine_range_checked(range)
+    }
+
+    /// Combines partial aggregates within the given date range [start, end) using only the wheel of the given granularity
+    ///
+    /// See [`Haw::combine_range_at`] for more information.
+    #[inline]
+    pub fn combine_range_at(
+        &self,
+        range: impl Into<WheelRange>,
+        granularity: Granularity,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().combine_range_at(range, granularity)
+    }
+
+    /// Combines partial aggregates within the given date range using only the seconds wheel
+    ///
+    /// See [`Haw::seconds_range`] for more information.
+    #[inline]
+    pub fn seconds_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().seconds_range(range)
+    }
+
+    /// Combines partial aggregates within the given date range using only the minutes wheel
+    ///
+    /// See [`Haw::minutes_range`] for more information.
+    #[inline]
+    pub fn minutes_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().minutes_range(range)
+    }
+
+    /// Combines partial aggregates within the given date range using only the hours wheel
+    ///
+    /// See [`Haw::hours_range`] for more information.
+    #[inline]
+    pub fn hours_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().hours_range(range)
+    }
+
+    /// Combines partial aggregates within the given date range using only the days wheel
+    ///
+    /// See [`Haw::days_range`] for more information.
+    #[inline]
+    pub fn days_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().days_range(range)
+    }
+
+    /// Combines partial aggregates within the given date range using only the weeks wheel
+    ///
+    /// See [`Haw::weeks_range`] for more information.
+    #[inline]
+    pub fn weeks_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().weeks_range(range)
+    }
+
+    /// Combines partial aggregates within the given date range using only the years wheel
+    ///
+    /// See [`Haw::years_range`] for more information.
+    #[inline]
+    pub fn years_range(
+        &self,
+        range: impl Into<WheelRange>,
+    ) -> Result<Option<A::PartialAggregate>, QueryError> {
+        self.inner.read().years_range(range)
+    }
+
+    /// Replays the windows of length `window_range` and slide `window_slide` over the retained data in `range`
+    ///
+    /// See [`Haw::replay_windows`] for more information.
+    #[inline]
+    pub fn replay_windows(
+        &self,
+        range: impl Into<WheelRange>,
+        window_range: Duration,
+        window_slide: Duration,
+    ) -> Vec<WindowAggregate<A::PartialAggregate>> {
+        self.inner
+            .read()
+            .replay_windows(range, window_range, window_slide)
+    }
+
+    /// Returns `true` if the range can be answered in O(1) through a prefix-sum or landmark aggregation
+    ///
+    /// See [`Haw::is_fast_query`] for more information.
+    #[inline]
+    pub fn is_fast_query(&self, range: impl Into<WheelRange>) -> bool {
+        self.inner.read().is_fast_query(range)
+    }
+
+    /// Returns the difference between the partial aggregates of range `a` and range `b`
+    ///
+    /// See [`Haw::range_delta`] for more information.
+    #[inline]
+    pub fn range_delta(
+        &self,
+        a: impl Into<WheelRange>,
+        b: impl Into<WheelRange>,
+    ) -> Option<A::PartialAggregate> {
+        self.inner.read().range_delta(a, b)
+    }
+
     /// Combines aggregates within the given date range [start, end) into a final partial aggregate
     ///
     /// Returns `None` if the range cannot be answered by the wheel
@@ -279,9 +561,11 @@ where
         self.inner.read().landmark()
     }
     /// Merges another [ReaderWheel] into this one
+    ///
+    /// Returns an error if the wheels have incompatible configurations (see [MergeError]).
     #[inline]
-    pub fn merge(&self, other: &Self) {
-        self.inner.write().merge(&mut other.inner.write());
+    pub fn merge(&self, other: &Self) -> Result<(), MergeError> {
+        self.inner.write().merge(&mut other.inner.write())
     }
     /// Returns a reference to the internal [Haw] data structure
     pub fn as_ref(&self) -> HawRef<'_, A> {
@@ -28,7 +28,39 @@ pub enum ExecutionPlan {
     InverseLandmarkAggregation(WheelAggregations),
 }
 
+/// The kind of an [ExecutionPlan] without its wheel aggregations
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+pub enum PlanKind {
+    /// A single Wheel Aggregation
+    WheelAggregation,
+    /// Multiple Wheel Aggregations
+    CombinedAggregation,
+    /// A landmark window
+    LandmarkAggregation,
+    /// A landmark window + inverse combine
+    InverseLandmarkAggregation,
+}
+
+/// A coarse classification of how the execution time of an [ExecutionPlan] grows with the queried range
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum ComplexityClass {
+    /// O(1): answered through landmark totals and prefix-sums regardless of the range length
+    Constant,
+    /// O(n): at least one wheel aggregation scans slots proportional to the range length
+    Linear,
+}
+
 impl ExecutionPlan {
+    /// Returns the kind of the plan
+    pub fn kind(&self) -> PlanKind {
+        match self {
+            ExecutionPlan::WheelAggregation(_) => PlanKind::WheelAggregation,
+            ExecutionPlan::CombinedAggregation(_) => PlanKind::CombinedAggregation,
+            ExecutionPlan::LandmarkAggregation => PlanKind::LandmarkAggregation,
+            ExecutionPlan::InverseLandmarkAggregation(_) => PlanKind::InverseLandmarkAggregation,
+        }
+    }
     /// Returns `true``if execution plan is a single-wheel prefix sum or landmark`
     #[inline]
     pub fn is_prefix_or_landmark(&self) -> bool {
@@ -39,6 +71,13 @@ impl ExecutionPlan {
         }
     }
     /// Returns the expected aggregate cost |⊕| of the plan
+    ///
+    /// The cost is the number of combine operations needed to execute the plan:
+    ///
+    /// - A [WheelAggregation] costs the number of scanned slots, or `1` if answered through a prefix-sum.
+    /// - A [CombinedAggregation] costs the sum of its wheel aggregations plus one combine per aggregation.
+    /// - A landmark aggregation combines the rotation totals of all six wheels.
+    /// - An inverse landmark aggregation costs the landmark, its complement aggregations and two inverse combines.
     pub fn cost(&self) -> usize {
         match self {
             ExecutionPlan::WheelAggregation(w) => w.cost(),
@@ -49,11 +88,98 @@ impl ExecutionPlan {
             }
         }
     }
+
+    /// Returns the [ComplexityClass] of the plan
+    ///
+    /// Landmark aggregations and prefix-sum wheel aggregations are constant, as are inverse landmark aggregations
+    /// whose complement is answered through prefix-sums. Any plan that scans slots is linear in the scanned slots.
+    /// Complements the numeric [cost](Self::cost) with a label for planner diagnostics.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator, wheels::read::ComplexityClass};
+    ///
+    /// let mut haw: Haw<U32SumAggregator> = Haw::default();
+    /// haw.delta_advance(vec![Some(1); 30]);
+    ///
+    /// let plan = haw.explain_combine_range(WheelRange::new_unchecked(10000, 20000)).unwrap();
+    /// assert_eq!(plan.complexity_class(), ComplexityClass::Linear);
+    /// ```
+    pub fn complexity_class(&self) -> ComplexityClass {
+        let prefix = |aggs: &[WheelAggregation]| aggs.iter().all(WheelAggregation::is_prefix);
+        let constant = match self {
+            ExecutionPlan::WheelAggregation(w) => w.is_prefix(),
+            ExecutionPlan::CombinedAggregation(c) => prefix(c.aggregations()),
+            ExecutionPlan::LandmarkAggregation => true,
+            ExecutionPlan::InverseLandmarkAggregation(w) => prefix(w),
+        };
+        if constant {
+            ComplexityClass::Constant
+        } else {
+            ComplexityClass::Linear
+        }
+    }
+
+    // Rank used to break ties between plans of equal cost and fastness
+    fn kind_rank(&self) -> u8 {
+        match self.kind() {
+            PlanKind::LandmarkAggregation => 0,
+            PlanKind::WheelAggregation => 1,
+            PlanKind::InverseLandmarkAggregation => 2,
+            PlanKind::CombinedAggregation => 3,
+        }
+    }
+
+    // Wheel aggregations of the plan used to break ties between distinct plans of the same kind and cost
+    fn wheel_aggregations(&self) -> &[WheelAggregation] {
+        match self {
+            ExecutionPlan::WheelAggregation(w) => core::slice::from_ref(w),
+            ExecutionPlan::CombinedAggregation(c) => c.aggregations(),
+            ExecutionPlan::LandmarkAggregation => &[],
+            ExecutionPlan::InverseLandmarkAggregation(w) => w,
+        }
+    }
 }
 
+/// Orders execution plans by their [cost](ExecutionPlan::cost) so that the cheapest plan is the minimum
+///
+/// Plans of equal cost prefer a single-wheel prefix-sum or landmark aggregation (see [ExecutionPlan::is_prefix_or_landmark]),
+/// followed by landmark, wheel, inverse landmark and combined aggregations. Remaining ties between distinct plans of
+/// the same kind are broken by their wheel aggregations so that only equal plans compare as [Ordering::Equal].
+///
+/// # Example
+///
+/// ```
+/// use uwheel::{Haw, WheelRange, aggregator::sum::U32SumAggregator, wheels::read::ExecutionPlan};
+///
+/// let mut haw: Haw<U32SumAggregator> = Haw::default();
+/// haw.delta_advance(vec![Some(1); 120]);
+///
+/// let plan = haw.explain_combine_range(WheelRange::new_unchecked(61000, 119000)).unwrap();
+/// assert!(ExecutionPlan::LandmarkAggregation < plan);
+/// ```
 impl Ord for ExecutionPlan {
     fn cmp(&self, other: &Self) -> Ordering {
-        self.cost().cmp(&other.cost())
+        self.cost()
+            .cmp(&other.cost())
+            .then_with(|| {
+                other
+                    .is_prefix_or_landmark()
+                    .cmp(&self.is_prefix_or_landmark())
+            })
+            .then_with(|| self.kind_rank().cmp(&other.kind_rank()))
+            .then_with(|| {
+                self.wheel_aggregations()
+                    .iter()
+                    .map(WheelAggregation::order_key)
+                    .cmp(
+                        other
+                            .wheel_aggregations()
+                            .iter()
+                            .map(WheelAggregation::order_key),
+                    )
+            })
     }
 }
 
@@ -100,6 +226,22 @@ impl WheelAggregation {
     pub fn range(&self) -> &WheelRange {
         &self.range
     }
+
+    // Key covering every field so that keys are equal only for equal wheel aggregations
+    #[inline]
+    fn order_key(&self) -> impl Ord {
+        let plan = match self.plan {
+            Aggregation::Prefix => (0, 0),
+            Aggregation::Scan(slots) => (1, slots),
+        };
+        (
+            self.range.start,
+            self.range.end,
+            self.granularity as usize,
+            self.slots,
+            plan,
+        )
+    }
 }
 
 /// Aggregation method
@@ -134,6 +276,10 @@ impl From<WheelAggregations> for CombinedAggregation {
 }
 
 impl CombinedAggregation {
+    /// Returns the wheel aggregations that make up the Combined Aggregation
+    pub fn aggregations(&self) -> &[WheelAggregation] {
+        &self.aggregations
+    }
     /// Returns the cost of executing the Combined Aggregation
     pub fn cost(&self) -> usize {
         let cost = self.aggregations.iter().fold(0, |mut acc, w_agg| {
@@ -0,0 +1,189 @@
+use super::hierarchical::Haw;
+use crate::aggregator::Aggregator;
+
+#[cfg(not(feature = "std"))]
+use alloc::vec::Vec;
+
+/// Distribution of the values generated by [Haw::fill_synthetic]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ValueDistribution {
+    /// Every value is the same
+    Constant(u32),
+    /// Values are drawn uniformly from the range [low, high]
+    Uniform {
+        /// Lowest possible value
+        low: u32,
+        /// Highest possible value
+        high: u32,
+    },
+}
+
+/// Specification of a deterministic synthetic workload used by [Haw::fill_synthetic]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SyntheticSpec {
+    /// Number of seconds to advance the wheel by
+    pub seconds: usize,
+    /// Fraction of seconds that contain data, from `0.0` (none) to `1.0` (every second)
+    pub density: f64,
+    /// Number of values aggregated into each second that contains data
+    pub values_per_second: u32,
+    /// Distribution of the generated values
+    pub values: ValueDistribution,
+}
+
+impl SyntheticSpec {
+    /// Creates a spec that fills every one of the given seconds with a single value of `1`
+    pub const fn new(seconds: usize) -> Self {
+        Self {
+            seconds,
+            density: 1.0,
+            values_per_second: 1,
+            values: ValueDistribution::Constant(1),
+        }
+    }
+    /// Configures the fraction of seconds that contain data
+    pub const fn with_density(mut self, density: f64) -> Self {
+        self.density = density;
+        self
+    }
+    /// Configures the number of values aggregated into each second that contains data
+    pub const fn with_values_per_second(mut self, values: u32) -> Self {
+        self.values_per_second = values;
+        self
+    }
+    /// Configures the distribution of the generated values
+    pub const fn with_values(mut self, values: ValueDistribution) -> Self {
+        self.values = values;
+        self
+    }
+}
+
+impl<A: Aggregator> Haw<A>
+where
+    A::Input: From<u32>,
+{
+    /// Advances the wheel with a deterministic synthetic workload
+    ///
+    /// The same `seed` and `spec` always produce the same data, regardless of platform,
+    /// which makes the workload suitable for reproducible benchmarks and regression tests.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Haw, SyntheticSpec, ValueDistribution, aggregator::sum::U64SumAggregator};
+    ///
+    /// let spec = SyntheticSpec::new(3600)
+    ///     .with_density(0.5)
+    ///     .with_values(ValueDistribution::Uniform { low: 1, high: 100 });
+    ///
+    /// let mut a: Haw<U64SumAggregator> = Haw::default();
+    /// let mut b: Haw<U64SumAggregator> = Haw::default();
+    /// a.fill_synthetic(42, spec);
+    /// b.fill_synthetic(42, spec);
+    ///
+    /// assert_eq!(a.watermark(), 3600 * 1000);
+    /// assert_eq!(a.landmark(), b.landmark());
+    /// ```
+    pub fn fill_synthetic(&mut self, seed: u64, spec: SyntheticSpec) {
+        let mut rng = SplitMix64(seed);
+        let deltas: Vec<_> = (0..spec.seconds)
+            .map(|_| {
+                if rng.next_f64() >= spec.density || spec.values_per_second == 0 {
+                    return None;
+                }
+                let mut partial = A::lift(rng.next_value(spec.values).into());
+                for _ in 1..spec.values_per_second {
+                    A::combine_mutable(&mut partial, rng.next_value(spec.values).into());
+                }
+                Some(A::freeze(partial))
+            })
+            .collect();
+        self.delta_advance(deltas);
+    }
+}
+
+// Small deterministic pseudo-random generator (SplitMix64) so the workload does not depend on external crates
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    #[inline]
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+    // Returns a value in the range [0.0, 1.0)
+    #[inline]
+    fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64
+    }
+    #[inline]
+    fn next_value(&mut self, values: ValueDistribution) -> u32 {
+        match values {
+            ValueDistribution::Constant(value) => value,
+            ValueDistribution::Uniform { low, high } => {
+                let span = u64::from(high.saturating_sub(low)) + 1;
+                low + (self.next_u64() % span) as u32
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{aggregator::sum::U64SumAggregator, Granularity, WheelRange};
+
+    #[test]
+    fn fill_synthetic_test() {
+        let spec = SyntheticSpec::new(2 * 3600 + 90)
+            .with_density(0.3)
+            .with_values_per_second(4)
+            .with_values(ValueDistribution::Uniform { low: 10, high: 20 });
+
+        let mut a: Haw<U64SumAggregator> = Haw::default();
+        let mut b: Haw<U64SumAggregator> = Haw::default();
+        a.fill_synthetic(7, spec);
+        b.fill_synthetic(7, spec);
+
+        #[cfg(feature = "serde")]
+        assert_eq!(
+            bincode::serialize(&a).unwrap(),
+            bincode::serialize(&b).unwrap()
+        );
+
+        let ranges = [
+            WheelRange::new_unchecked(0, 2 * 3600 * 1000),
+            WheelRange::new_unchecked(3600 * 1000, 2 * 3600 * 1000 + 60000),
+            WheelRange::new_unchecked(2 * 3600 * 1000 + 60000, 2 * 3600 * 1000 + 90000),
+        ];
+        for range in ranges {
+            assert!(a.combine_range(range).is_some());
+            assert_eq!(a.combine_range(range), b.combine_range(range));
+        }
+        assert_eq!(a.landmark(), b.landmark());
+
+        // values stay within the distribution and roughly 30% of the seconds contain data
+        let occupied = a
+            .occupancy(Granularity::Second, 30)
+            .into_iter()
+            .filter(|&occupied| occupied)
+            .count();
+        assert!((3..=18).contains(&occupied));
+        let total = a.combine_range(ranges[2]).unwrap();
+        assert!(total >= occupied as u64 * 4 * 10 && total <= occupied as u64 * 4 * 20);
+
+        // different seeds produce a different workload
+        let mut c: Haw<U64SumAggregator> = Haw::default();
+        c.fill_synthetic(8, spec);
+        assert_ne!(a.landmark(), c.landmark());
+
+        // an empty density only advances time
+        let mut empty: Haw<U64SumAggregator> = Haw::default();
+        empty.fill_synthetic(7, SyntheticSpec::new(120).with_density(0.0));
+        assert_eq!(empty.watermark(), 120000);
+        assert_eq!(empty.landmark(), Some(0));
+    }
+}
@@ -103,6 +103,11 @@ impl<EntryType, RestType> ByteWheel<EntryType, RestType> {
         }
     }
 
+    /// Returns an iterator over all entries in the wheel
+    pub fn entries(&self) -> impl Iterator<Item = &EntryType> {
+        self.slots.iter().flatten().flatten().map(|we| &we.entry)
+    }
+
     /// True if the number of entries is 0
     pub fn is_empty(&self) -> bool {
         self.count == 0
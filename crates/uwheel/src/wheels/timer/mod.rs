@@ -114,10 +114,20 @@ use alloc::boxed::Box;
 pub type WheelFn<A> = Box<dyn Fn(&Haw<A>)>;
 
 pub enum TimerAction<A: Aggregator> {
-    Oneshot(WheelFn<A>),
+    Oneshot((u64, WheelFn<A>)),
     Repeat((u64, duration::Duration, WheelFn<A>)),
 }
 
+impl<A: Aggregator> TimerAction<A> {
+    /// Returns the time at which the action is scheduled to fire
+    pub fn at(&self) -> u64 {
+        match self {
+            TimerAction::Oneshot((at, _)) => *at,
+            TimerAction::Repeat((at, _, _)) => *at,
+        }
+    }
+}
+
 // Two Timer Wheel implementations
 
 cfg_not_sync! {
@@ -318,6 +318,16 @@ impl<EntryType> QuadWheelWithOverflow<EntryType> {
         self.quarternary.advance(new_time_bytes[0]);
     }
 
+    /// Returns an iterator over all pending entries in no particular order
+    pub fn entries(&self) -> impl Iterator<Item = &EntryType> {
+        self.primary
+            .entries()
+            .chain(self.secondary.entries())
+            .chain(self.tertiary.entries())
+            .chain(self.quarternary.entries())
+            .chain(self.overflow.iter().map(|e| &e.entry))
+    }
+
     /// Determine if and how many ticks can be skipped
     pub fn can_skip(&self) -> Skip {
         if self.primary.is_empty() {
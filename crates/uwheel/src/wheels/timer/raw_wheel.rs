@@ -37,6 +37,11 @@ impl<A> RawTimerWheel<A> {
             .insert_with_delay(entry, Duration::from_millis(delay))
     }
 
+    /// Returns an iterator over all pending entries in no particular order
+    pub fn entries(&self) -> impl Iterator<Item = &A> {
+        self.timer.entries()
+    }
+
     #[inline]
     pub(crate) fn time(&self) -> u64 {
         self.time
@@ -1,8 +1,11 @@
-use core::{mem, time::Duration as CoreDuration};
+use core::{
+    fmt::{self, Display},
+    mem,
+};
 
-use crate::{aggregator::Aggregator, duration::Duration, Entry};
+use crate::{aggregator::Aggregator, Entry};
 
-use super::{timer::RawTimerWheel, wheel_ext::WheelExt};
+use super::{read::hierarchical::SECOND_TICK_MS, timer::RawTimerWheel, wheel_ext::WheelExt};
 
 /// Number of write ahead slots
 pub const DEFAULT_WRITE_AHEAD_SLOTS: usize = 64;
@@ -10,6 +13,52 @@ pub const DEFAULT_WRITE_AHEAD_SLOTS: usize = 64;
 #[cfg(not(feature = "std"))]
 use alloc::{boxed::Box, vec::Vec};
 
+/// Errors that may occur when writing into the write-ahead section of a [WriterWheel]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum WriteAheadError {
+    /// The slot is beyond the available write-ahead slots
+    Overflow {
+        /// Number of slots ahead of the watermark that was written to
+        addend: u64,
+        /// Number of available write-ahead slots
+        available: usize,
+    },
+}
+
+impl Display for WriteAheadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WriteAheadError::Overflow { addend, available } => write!(
+                f,
+                "cannot write {addend} slots ahead with {available} write-ahead slots available"
+            ),
+        }
+    }
+}
+
+/// Errors that may occur when inserting into a wheel
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum InsertError {
+    /// The entry is below the watermark and would be dropped
+    Late {
+        /// Timestamp of the entry in milliseconds
+        timestamp: u64,
+        /// Current watermark of the wheel
+        watermark: u64,
+    },
+}
+
+impl Display for InsertError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertError::Late {
+                timestamp,
+                watermark,
+            } => write!(f, "entry at {timestamp} is below the watermark {watermark}"),
+        }
+    }
+}
+
 /// A writer wheel optimized for single-threaded ingestion of aggregates.
 ///
 /// Note that you do not have to interact manually with this wheel if you are using the
@@ -27,15 +76,31 @@ pub struct WriterWheel<A: Aggregator> {
     num_slots: usize,
     /// Defines the capacity of the write-ahead wheel
     capacity: usize,
+    /// Upper bound the write-ahead capacity may grow to or `None` if the capacity is fixed
+    #[cfg_attr(feature = "serde", serde(default))]
+    max_capacity: Option<usize>,
     /// A Hierarchical Timing Wheel for managing future entries that do not fit within the write-ahead wheel
     overflow: RawTimerWheel<Entry<A::Input>>,
+    /// A Hierarchical Timing Wheel for managing future partial aggregates that do not fit within the write-ahead wheel
+    overflow_partials: RawTimerWheel<Entry<A::PartialAggregate>>,
     /// Pre-allocated memory for mutable write-ahead aggregation
     slots: Box<[Option<A::MutablePartialAggregate>]>,
+    /// Pre-allocated memory for pre-aggregated partials inserted into the write-ahead section
+    partials: Box<[Option<A::PartialAggregate>]>,
     /// The current tail of the write-ahead section
     tail: usize,
     /// The current head of the write-ahead section
     head: usize,
+    /// Time in milliseconds covered by a write-ahead slot
+    #[cfg_attr(feature = "serde", serde(default = "default_tick_size_ms"))]
+    tick_size_ms: u64,
+}
+
+#[cfg(feature = "serde")]
+const fn default_tick_size_ms() -> u64 {
+    SECOND_TICK_MS
 }
+
 impl<A: Aggregator> Default for WriterWheel<A> {
     fn default() -> Self {
         Self::with_watermark(0)
@@ -53,23 +118,89 @@ impl<A: Aggregator> WriterWheel<A> {
         Self {
             num_slots,
             capacity,
+            max_capacity: None,
             watermark,
             overflow: RawTimerWheel::new(watermark),
+            overflow_partials: RawTimerWheel::new(watermark),
             slots: (0..capacity)
                 .map(|_| None)
                 .collect::<Vec<_>>()
                 .into_boxed_slice(),
+            partials: (0..capacity)
+                .map(|_| None)
+                .collect::<Vec<_>>()
+                .into_boxed_slice(),
             head: 0,
             tail: 0,
+            tick_size_ms: SECOND_TICK_MS,
         }
     }
+    /// Allows the write-ahead section to grow up to `max_capacity` slots
+    ///
+    /// By default writes beyond the write-ahead capacity are scheduled in an overflow timer wheel.
+    /// In growable mode the capacity is instead doubled until the write fits or `max_capacity` is reached,
+    /// which suits producers that occasionally burst ahead of the watermark. Writes beyond `max_capacity`
+    /// are still scheduled in the overflow timer wheel.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Entry, aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut wheel: WriterWheel<U32SumAggregator> =
+    ///     WriterWheel::with_capacity_and_watermark(4, 0).growable(16);
+    /// wheel.insert(Entry::new(10, 9000));
+    /// assert_eq!(wheel.write_ahead_len(), 16);
+    /// ```
+    pub fn growable(mut self, max_capacity: usize) -> Self {
+        self.max_capacity = Some(max_capacity);
+        self
+    }
+
+    /// Configures the time in milliseconds covered by each write-ahead slot
+    ///
+    /// Defaults to [SECOND_TICK_MS] so that each tick seals one second. A tick size of
+    /// [MILLISECOND_TICK_MS](crate::wheels::read::hierarchical::MILLISECOND_TICK_MS) is required to feed a
+    /// [Haw](crate::Haw) configured with a milliseconds wheel (see [HawConf::with_milliseconds](crate::HawConf::with_milliseconds)).
+    /// Note that the write-ahead capacity is counted in slots and thus covers less time with smaller ticks.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the tick size does not evenly divide a second.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{Entry, aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut wheel: WriterWheel<U32SumAggregator> = WriterWheel::default().with_tick_size(1);
+    /// wheel.insert(Entry::new(10, 1));
+    /// assert_eq!(wheel.tick(), None);
+    /// assert_eq!(wheel.tick(), Some(10));
+    /// assert_eq!(wheel.watermark(), 2);
+    /// ```
+    pub fn with_tick_size(mut self, tick_size_ms: u64) -> Self {
+        assert!(
+            tick_size_ms > 0 && SECOND_TICK_MS.is_multiple_of(tick_size_ms),
+            "tick size must evenly divide a second"
+        );
+        self.tick_size_ms = tick_size_ms;
+        self
+    }
+
+    /// Returns the time in milliseconds covered by each tick
+    pub fn tick_size_ms(&self) -> u64 {
+        self.tick_size_ms
+    }
+
     /// Returns the current low watermark
     pub fn watermark(&self) -> u64 {
         self.watermark
     }
 
-    /// Ticks the `WriterWheel` and returns a possible mutable partial aggregate
+    /// Ticks the `WriterWheel` and returns a possible frozen partial aggregate
     ///
+    /// The result includes both the aggregated entries and the partial aggregates inserted through [Self::insert_partial].
     /// Note that you don't need to use this function directly if you are using the `Reader-Writer Wheel`.
     ///
     /// # Example
@@ -79,17 +210,31 @@ impl<A: Aggregator> WriterWheel<A> {
     ///
     /// // Creates a wheel with time 0 and default write-ahead capacity
     /// let mut wheel: WriterWheel<U32SumAggregator> = WriterWheel::default();
-    /// // Insert two entries at time 0
+    /// // Insert two entries and a partial aggregate at time 0
     /// wheel.insert(Entry::new(10, 0));
     /// wheel.insert(Entry::new(20, 0));
-    /// // verify that the ticked result returns 20 + 10 and that time has advanced
-    /// assert_eq!(wheel.tick(), Some(30));
+    /// wheel.insert_partial(5, 0);
+    /// // verify that the ticked result returns 20 + 10 + 5 and that time has advanced
+    /// assert_eq!(wheel.tick(), Some(35));
     /// assert_eq!(wheel.watermark(), 1000);
     /// ```
     #[inline]
-    pub fn tick(&mut self) -> Option<A::MutablePartialAggregate> {
-        // bump the watermark by 1 second as millis
-        self.watermark += Duration::SECOND.whole_milliseconds() as u64;
+    pub fn tick(&mut self) -> Option<A::PartialAggregate> {
+        match self.tick_with_partial() {
+            (Some(mutable), Some(partial)) => Some(A::combine(A::freeze(mutable), partial)),
+            (mutable, partial) => mutable.map(A::freeze).or(partial),
+        }
+    }
+
+    #[inline]
+    fn tick_with_partial(
+        &mut self,
+    ) -> (
+        Option<A::MutablePartialAggregate>,
+        Option<A::PartialAggregate>,
+    ) {
+        // bump the watermark by the tick size
+        self.watermark += self.tick_size_ms;
 
         // bump head + tail
         self.head = self.wrap_add(self.head, 1);
@@ -100,8 +245,11 @@ impl<A: Aggregator> WriterWheel<A> {
         for entry in self.overflow.advance_to(self.watermark) {
             self.insert(entry); // this is assumed to be safe if it was scheduled correctly
         }
+        for entry in self.overflow_partials.advance_to(self.watermark) {
+            self.insert_partial(entry.data, entry.timestamp);
+        }
 
-        self.slot(tail).take()
+        (self.slot(tail).take(), self.partials[tail].take())
     }
 
     /// Check whether this wheel can write ahead by ´addend` slots
@@ -122,11 +270,87 @@ impl<A: Aggregator> WriterWheel<A> {
         self.slots[idx].as_ref()
     }
 
-    /// Attempts to write `entry` into the Wheel
+    /// Attempts to write `data` into the slot `addend` ticks ahead of the watermark
+    ///
+    /// Returns [WriteAheadError::Overflow] if the slot is beyond the available write-ahead slots.
     #[inline(always)]
-    fn write_ahead(&mut self, addend: u64, data: A::Input) {
-        let slot_idx = self.slot_idx_forward_from_head(addend as usize);
+    pub fn write_ahead(&mut self, addend: u64, data: A::Input) -> Result<(), WriteAheadError> {
+        self.maybe_grow(addend);
+        let slot_idx = self.checked_slot_idx(addend)?;
         self.combine_or_lift(slot_idx, data);
+        Ok(())
+    }
+
+    // Attempts to write a partial aggregate into the slot `addend` ticks ahead of the watermark
+    #[inline]
+    fn write_ahead_partial(
+        &mut self,
+        addend: u64,
+        partial: A::PartialAggregate,
+    ) -> Result<(), WriteAheadError> {
+        self.maybe_grow(addend);
+        let slot_idx = self.checked_slot_idx(addend)?;
+        let slot = &mut self.partials[slot_idx];
+        *slot = Some(match slot.take() {
+            Some(current) => A::combine(current, partial),
+            None => partial,
+        });
+        Ok(())
+    }
+
+    // Grows the write-ahead section if configured to and `addend` does not fit within the current capacity
+    #[inline]
+    fn maybe_grow(&mut self, addend: u64) {
+        let Some(max_capacity) = self.max_capacity else {
+            return;
+        };
+        let addend = addend as usize;
+        if self.can_write_ahead(addend as u64) || addend >= max_capacity {
+            return;
+        }
+        let mut capacity = self.capacity;
+        while capacity <= addend {
+            capacity = capacity.saturating_mul(2);
+        }
+        self.resize(capacity.min(max_capacity));
+    }
+
+    // Re-lays out the write-ahead section into `capacity` slots with the tail at index 0
+    fn resize(&mut self, capacity: usize) {
+        let num_slots = crate::capacity_to_slots!(capacity);
+        let len = self.len();
+        let mut slots: Vec<Option<A::MutablePartialAggregate>> =
+            (0..num_slots).map(|_| None).collect();
+        let mut partials: Vec<Option<A::PartialAggregate>> = (0..num_slots).map(|_| None).collect();
+
+        for i in 0..self.capacity {
+            let idx = self.wrap_add(self.tail, i);
+            if let Some(slot) = self.slots.get_mut(idx) {
+                slots[i] = slot.take();
+            }
+            if let Some(partial) = self.partials.get_mut(idx) {
+                partials[i] = partial.take();
+            }
+        }
+
+        self.slots = slots.into_boxed_slice();
+        self.partials = partials.into_boxed_slice();
+        self.head = len;
+        self.tail = 0;
+        self.capacity = capacity;
+        self.num_slots = num_slots;
+    }
+
+    #[inline(always)]
+    fn checked_slot_idx(&self, addend: u64) -> Result<usize, WriteAheadError> {
+        if self.can_write_ahead(addend) {
+            Ok(self.slot_idx_forward_from_head(addend as usize))
+        } else {
+            Err(WriteAheadError::Overflow {
+                addend,
+                available: self.write_ahead_len(),
+            })
+        }
     }
 
     #[inline]
@@ -164,20 +388,93 @@ impl<A: Aggregator> WriterWheel<A> {
         let watermark = self.watermark;
 
         if entry.timestamp >= watermark {
-            let diff = entry.timestamp - self.watermark;
-            let seconds = CoreDuration::from_millis(diff).as_secs();
-            if self.can_write_ahead(seconds) {
-                self.write_ahead(seconds, entry.data);
-            } else {
+            let ticks = (entry.timestamp - watermark) / self.tick_size_ms;
+            if self.write_ahead(ticks, entry.data).is_err() {
                 // Overflows: schedule it to be aggregated later on
                 // TODO: batch as many entries at possible into the same overflow slot
-                let schedule_ts = watermark + seconds * 1000; // convert back to milliseconds
+                let schedule_ts = watermark + ticks * self.tick_size_ms; // convert back to milliseconds
                 self.overflow.schedule_at(schedule_ts, entry).unwrap();
             }
         }
     }
 }
 
+impl<A: Aggregator> WriterWheel<A> {
+    /// Inserts a batch of inputs sharing the same timestamp into the wheel
+    ///
+    /// Inputs are combined into the slot through [Aggregator::combine_mutable_bulk] which is faster than
+    /// inserting them one at a time for aggregators that specialize it.
+    ///
+    /// # Safety
+    /// - The inputs will be dropped if the timestamp is below the current watermark.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use uwheel::{aggregator::sum::U32SumAggregator, wheels::WriterWheel};
+    ///
+    /// let mut wheel: WriterWheel<U32SumAggregator> = WriterWheel::default();
+    /// wheel.insert_bulk(0, [1, 2, 3, 4]);
+    /// assert_eq!(wheel.tick(), Some(10));
+    /// ```
+    #[inline]
+    pub fn insert_bulk(&mut self, timestamp: u64, inputs: impl IntoIterator<Item = A::Input>) {
+        let watermark = self.watermark;
+
+        if timestamp >= watermark {
+            let ticks = (timestamp - watermark) / self.tick_size_ms;
+            let mut inputs = inputs.into_iter();
+            self.maybe_grow(ticks);
+            match self.checked_slot_idx(ticks) {
+                Ok(slot_idx) => {
+                    let slot = self.slot(slot_idx);
+                    match slot {
+                        Some(dst) => A::combine_mutable_bulk(dst, inputs),
+                        None => {
+                            if let Some(first) = inputs.next() {
+                                let mut partial = A::lift(first);
+                                A::combine_mutable_bulk(&mut partial, inputs);
+                                *slot = Some(partial);
+                            }
+                        }
+                    }
+                }
+                Err(_) => {
+                    // Overflows: schedule them to be aggregated later on
+                    let schedule_ts = watermark + ticks * self.tick_size_ms; // convert back to milliseconds
+                    for data in inputs {
+                        self.overflow
+                            .schedule_at(schedule_ts, Entry::new(data, timestamp))
+                            .unwrap();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Inserts a pre-aggregated partial aggregate at the given timestamp into the wheel
+    ///
+    /// Unlike [Self::insert], the partial is not lifted but combined with other partials of the same slot.
+    ///
+    /// # Safety
+    /// - The partial will be dropped if its timestamp is below the current watermark.
+    #[inline]
+    pub fn insert_partial(&mut self, partial: A::PartialAggregate, timestamp: u64) {
+        let watermark = self.watermark;
+
+        if timestamp >= watermark {
+            let ticks = (timestamp - watermark) / self.tick_size_ms;
+            if self.write_ahead_partial(ticks, partial).is_err() {
+                // Overflows: schedule it to be inserted later on
+                let schedule_ts = watermark + ticks * self.tick_size_ms; // convert back to milliseconds
+                self.overflow_partials
+                    .schedule_at(schedule_ts, Entry::new(partial, timestamp))
+                    .unwrap();
+            }
+        }
+    }
+}
+
 impl<A: Aggregator> WheelExt for WriterWheel<A> {
     fn num_slots(&self) -> usize {
         self.num_slots
@@ -192,7 +489,9 @@ impl<A: Aggregator> WheelExt for WriterWheel<A> {
         self.tail
     }
     fn size_bytes(&self) -> Option<usize> {
-        let inner_slots = mem::size_of::<Option<A::MutablePartialAggregate>>() * self.num_slots;
+        let inner_slots = (mem::size_of::<Option<A::MutablePartialAggregate>>()
+            + mem::size_of::<Option<A::PartialAggregate>>())
+            * self.num_slots;
         Some(mem::size_of::<Self>() + inner_slots)
     }
 }
@@ -252,6 +551,84 @@ mod tests {
         assert_eq!(wheel.tail, 0);
     }
 
+    #[test]
+    fn tick_partial_test() {
+        let mut wheel: WriterWheel<U64SumAggregator> =
+            WriterWheel::with_capacity_and_watermark(4, 0);
+
+        wheel.insert(Entry::new(1, 0));
+        wheel.insert_partial(10, 0);
+        wheel.insert_partial(20, 1000);
+        // beyond the write-ahead slots
+        wheel.insert_partial(30, 6000);
+
+        assert_eq!(wheel.tick(), Some(11));
+        assert_eq!(wheel.tick(), Some(20));
+        for _ in 0..4 {
+            assert_eq!(wheel.tick(), None);
+        }
+        assert_eq!(wheel.tick(), Some(30));
+    }
+
+    #[test]
+    fn write_ahead_overflow_test() {
+        let mut wheel: WriterWheel<U64SumAggregator> =
+            WriterWheel::with_capacity_and_watermark(4, 0);
+
+        assert_eq!(wheel.write_ahead(3, 1), Ok(()));
+        assert_eq!(
+            wheel.write_ahead(4, 10),
+            Err(WriteAheadError::Overflow {
+                addend: 4,
+                available: 4
+            })
+        );
+        assert_eq!(
+            wheel.write_ahead(100, 10),
+            Err(WriteAheadError::Overflow {
+                addend: 100,
+                available: 4
+            })
+        );
+
+        // no other slot has been written to
+        for _ in 0..3 {
+            assert_eq!(wheel.tick(), None);
+        }
+        assert_eq!(wheel.tick(), Some(1));
+        assert_eq!(wheel.tick(), None);
+    }
+
+    #[test]
+    fn growable_write_ahead_test() {
+        let mut wheel: WriterWheel<U64SumAggregator> =
+            WriterWheel::with_capacity_and_watermark(4, 0).growable(16);
+
+        // move the head so that the ring wraps around before growing
+        wheel.insert(Entry::new(1, 0));
+        assert_eq!(wheel.tick(), Some(1));
+        assert_eq!(wheel.tick(), None);
+        for second in 2..5 {
+            wheel.insert(Entry::new(second, second * 1000));
+        }
+        assert_eq!(wheel.head, 2);
+
+        // grows by doubling rather than scheduling into the overflow wheel
+        wheel.insert(Entry::new(7, 7000));
+        assert_eq!(wheel.write_ahead_len(), 8);
+        wheel.insert(Entry::new(12, 12000));
+        assert_eq!(wheel.write_ahead_len(), 16);
+
+        // bounded by the max capacity
+        wheel.insert(Entry::new(20, 20000));
+        assert_eq!(wheel.write_ahead_len(), 16);
+
+        for second in 2..=20 {
+            let expected = [2, 3, 4, 7, 12, 20].contains(&second).then_some(second);
+            assert_eq!(wheel.tick(), expected);
+        }
+    }
+
     #[test]
     fn wrap_around_test() {
         let mut wheel: WriterWheel<U64SumAggregator> =
@@ -270,6 +647,28 @@ mod tests {
         assert_eq!(wheel.tick(), Some(5));
     }
 
+    #[test]
+    fn insert_bulk_test() {
+        let mut wheel: WriterWheel<U64SumAggregator> =
+            WriterWheel::with_capacity_and_watermark(4, 0);
+        wheel.insert_bulk(0, [1, 2, 3]);
+        wheel.insert_bulk(0, [4]);
+        wheel.insert_bulk(1000, []);
+        // beyond the write-ahead capacity
+        wheel.insert_bulk(6000, [5, 5]);
+
+        assert_eq!(wheel.tick(), Some(10));
+        assert_eq!(wheel.tick(), None);
+        for _ in 0..4 {
+            assert_eq!(wheel.tick(), None);
+        }
+        assert_eq!(wheel.tick(), Some(10));
+
+        // late batches are dropped
+        wheel.insert_bulk(0, [100]);
+        assert_eq!(wheel.tick(), None);
+    }
+
     #[test]
     fn late_event_handling_test() {
         let mut wheel: WriterWheel<U64SumAggregator> =
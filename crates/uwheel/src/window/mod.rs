@@ -12,8 +12,6 @@ use alloc::vec::Vec;
 #[cfg(feature = "std")]
 use std::collections::VecDeque;
 
-use self::util::pairs_space;
-
 /// Window Aggregation Result
 #[derive(PartialEq, Debug, Clone, Copy)]
 pub struct WindowAggregate<T> {
@@ -81,6 +79,12 @@ impl Window {
     /// let window = Window::sliding(10.seconds(), 3.seconds());
     ///
     /// ```
+    ///
+    /// # Memory
+    ///
+    /// The window buffers at most `range / slide` partial aggregates (pairs), or `ceil(2 * range / slide)`
+    /// if the range is not a multiple of the slide. Once the buffer is full, the oldest pair is evicted as
+    /// a new one arrives, so memory stays bounded regardless of how long the wheel is advanced.
     pub fn sliding(range: Duration, slide: Duration) -> Self {
         assert!(
             range >= slide,
@@ -117,17 +121,17 @@ impl<A: Aggregator> WindowManager<A> {
         // Create window aggregator and state based on the window type
         let aggregator = match window {
             Window::Tumbling { range } => {
-                let pairs = pairs_space(to_ms(range), to_ms(range));
+                let state = SlicingState::new(watermark, to_ms(range), to_ms(range));
                 WindowAggregator::Slicing {
-                    state: SlicingState::new(watermark, to_ms(range), to_ms(range)),
-                    aggregator: SlicingAggregator::with_capacity(pairs),
+                    aggregator: SlicingAggregator::with_capacity(state.max_pairs),
+                    state,
                 }
             }
             Window::Sliding { range, slide } => {
-                let pairs = pairs_space(to_ms(range), to_ms(slide));
+                let state = SlicingState::new(watermark, to_ms(range), to_ms(slide));
                 WindowAggregator::Slicing {
-                    state: SlicingState::new(watermark, to_ms(range), to_ms(slide)),
-                    aggregator: SlicingAggregator::with_capacity(pairs),
+                    aggregator: SlicingAggregator::with_capacity(state.max_pairs),
+                    state,
                 }
             }
             Window::Session { timeout } => WindowAggregator::Session {
@@ -137,6 +141,27 @@ impl<A: Aggregator> WindowManager<A> {
         };
         Self { aggregator, window }
     }
+
+    /// Creates a new window manager whose windows are aligned to the local calendar time of the given UTC offset
+    ///
+    /// The first window starts at the next local boundary of the slide (the range for tumbling windows),
+    /// for instance local midnight for daily windows. Session windows are not affected by the offset.
+    pub fn with_offset(watermark: u64, window: Window, offset_secs: i64) -> Self {
+        let slide = match window {
+            Window::Tumbling { range } => range,
+            Window::Sliding { slide, .. } => slide,
+            Window::Session { .. } => return Self::new(watermark, window),
+        };
+        let slide_ms = slide.whole_milliseconds() as i64;
+        let local_ms = watermark as i64 + offset_secs * 1000;
+        let lead_ms = (slide_ms - local_ms.rem_euclid(slide_ms)) % slide_ms;
+
+        let mut manager = Self::new(watermark + lead_ms as u64, window);
+        let (state, _) = manager.aggregator.slicing_as_mut();
+        // skip the ticks until the first aligned window starts
+        state.pair_ticks_remaining += lead_ms as usize / 1000;
+        manager
+    }
 }
 #[cfg_attr(feature = "serde", derive(serde::Deserialize, serde::Serialize))]
 #[cfg_attr(feature = "serde", serde(bound = "A: Default"))]
@@ -235,6 +260,14 @@ impl<A: Aggregator> SlicingAggregator<A> {
             Self::TwoStacks(stacks) => stacks.pop(),
         }
     }
+
+    /// Returns the number of pairs currently buffered by the aggregator
+    pub fn len(&self) -> usize {
+        match self {
+            Self::Soe(soe) => soe.stack.len(),
+            Self::TwoStacks(stacks) => stacks.front.len() + stacks.back.len(),
+        }
+    }
 }
 
 impl<A: Aggregator> Default for SubtractOnEvict<A> {
@@ -353,11 +386,17 @@ mod tests {
     use super::Window;
     use crate::{
         aggregator::sum::U64SumAggregator,
+        wheels::read::hierarchical::HOUR_TICK_MS,
         window::WindowAggregate,
+        Conf,
         Duration,
         Entry,
+        HawConf,
         NumericalDuration,
+        RetentionPolicy,
         RwWheel,
+        WheelConf,
+        HOURS,
     };
 
     #[test]
@@ -463,6 +502,57 @@ mod tests {
         );
     }
 
+    #[test]
+    fn window_local_midnight_test() {
+        // 2023-11-09 00:00:00 UTC which is 02:00:00 at UTC+2
+        let start = 1699488000000;
+        let day = Duration::DAY.whole_milliseconds() as u64;
+        let hour = Duration::HOUR.whole_milliseconds() as u64;
+        // daily windows not aligned to UTC midnight are answered by the hours wheel
+        let haw_conf = HawConf::default()
+            .with_hours(
+                WheelConf::new(HOUR_TICK_MS, HOURS)
+                    .with_retention_policy(RetentionPolicy::KeepWithLimit(HOURS)),
+            )
+            .with_watermark(start);
+        let mut wheel: RwWheel<U64SumAggregator> =
+            RwWheel::with_conf(Conf::default().with_haw_conf(haw_conf));
+        wheel.window_with_offset(
+            Window::tumbling(Duration::days(1)),
+            time::UtcOffset::from_hms(2, 0, 0).unwrap(),
+        );
+
+        let mut results = Vec::new();
+        for second in 0..(3 * day / 1000) {
+            wheel.insert(Entry::new(1, start + second * 1000));
+            if second % 60 == 59 {
+                results.extend(wheel.advance(1.minutes()));
+            }
+        }
+        assert_eq!(wheel.watermark(), start + 3 * day);
+
+        // windows end at local midnight (22:00:00 UTC) instead of UTC midnight
+        let first_end = start + day - 2 * hour + day;
+        assert_eq!(
+            results,
+            [
+                WindowAggregate {
+                    window_start_ms: first_end - day,
+                    window_end_ms: first_end,
+                    aggregate: day / 1000,
+                },
+                WindowAggregate {
+                    window_start_ms: first_end,
+                    window_end_ms: first_end + day,
+                    aggregate: day / 1000,
+                },
+            ]
+        );
+        for window in results {
+            assert_eq!((window.window_end_ms + 2 * hour) % day, 0);
+        }
+    }
+
     #[test]
     fn window_30_sec_range_10_sec_slide_test() {
         let mut wheel: RwWheel<U64SumAggregator> = RwWheel::new(1533081600000);
@@ -1,4 +1,4 @@
-use super::util::{create_pair_type, PairType};
+use super::util::{create_pair_type, pairs_space, PairType};
 use crate::duration::Duration;
 
 /// Stream Slicing State using the pairs technique
@@ -20,6 +20,8 @@ pub struct SlicingState {
     pub next_pair_end: u64,
     /// A flag indicating whether we are in pair p1.
     pub in_p1: bool,
+    /// Maximum number of pairs buffered for a single window
+    pub max_pairs: usize,
 }
 impl SlicingState {
     pub fn new(time: u64, range: usize, slide: usize) -> Self {
@@ -38,6 +40,7 @@ impl SlicingState {
             next_window_end: time + range as u64,
             next_pair_end,
             in_p1: true,
+            max_pairs: pairs_space(range, slide),
         }
     }
     pub fn current_pair_duration(&self) -> Duration {
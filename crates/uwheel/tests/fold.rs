@@ -0,0 +1,46 @@
+//! Verifies that folding a range avoids the intermediate allocations of combining it.
+
+mod common;
+
+use common::allocations_while;
+use uwheel::{aggregator::collect::CollectAggregator, Aggregator, Haw, WheelRange};
+
+// non-commutative with partial aggregates too large to reuse the allocation of the plan
+type Collect = CollectAggregator<u64, 16>;
+
+#[test]
+fn fold_avoids_combine_allocations() {
+    let mut haw: Haw<Collect> = Haw::default();
+    // advance to 02:30:00 where each second collects its own timestamp
+    haw.delta_advance((0..2 * 3600 + 1800).map(|i| Some(Collect::freeze(Collect::lift(i)))));
+
+    // spans the hours and minutes wheels
+    let range = WheelRange::new_unchecked(3600000, 2 * 3600000 + 10 * 60000);
+    let expected = haw.combine_range(range).unwrap();
+    assert_eq!(expected.to_vec(), (3600..3616).collect::<Vec<_>>());
+
+    let mut parts = 0;
+    let folded = haw
+        .combine_range_fold(range, Collect::IDENTITY, |acc, partial| {
+            parts += 1;
+            Collect::combine(acc, partial)
+        })
+        .unwrap();
+    assert_eq!(folded.to_vec(), expected.to_vec());
+    assert!(folded.is_truncated());
+    assert!(parts > 1);
+
+    let combine_allocations = allocations_while(|| {
+        haw.combine_range(range);
+    });
+    let fold_allocations = allocations_while(|| {
+        haw.combine_range_fold(range, Collect::IDENTITY, Collect::combine);
+    });
+    assert!(fold_allocations < combine_allocations);
+
+    // only planning the range allocates while visiting the partial aggregates does not
+    let plan_allocations = allocations_while(|| {
+        haw.combine_range_fold(range, 0, |parts, _| parts + 1);
+    });
+    assert_eq!(fold_allocations, plan_allocations);
+}
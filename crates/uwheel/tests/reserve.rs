@@ -0,0 +1,27 @@
+//! Verifies that a reserved [Haw] does not allocate while ticking.
+
+mod common;
+
+use common::allocations_while;
+use uwheel::{aggregator::sum::U64SumAggregator, wheels::WriterWheel, Haw, NumericalDuration};
+
+#[test]
+fn reserve_avoids_tick_allocations() {
+    // lazily created wheels allocate as data reaches each granularity
+    let mut haw: Haw<U64SumAggregator> = Haw::default();
+    let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::default();
+    let allocations = allocations_while(|| {
+        haw.advance(8.days(), &mut waw);
+    });
+    assert!(allocations > 0);
+
+    // rotates the seconds, minutes, hours and days wheels
+    let mut haw: Haw<U64SumAggregator> = Haw::default();
+    let mut waw: WriterWheel<U64SumAggregator> = WriterWheel::default();
+    haw.reserve();
+    let allocations = allocations_while(|| {
+        haw.advance(8.days(), &mut waw);
+    });
+    assert_eq!(allocations, 0);
+    assert_eq!(haw.watermark(), 8.days().whole_milliseconds() as u64);
+}
@@ -119,6 +119,7 @@ impl Aggregate {
 // NOTE: in this case both the mutable and immutable aggregate types are the same
 impl Aggregator for CustomAggregator {
     const IDENTITY: Self::PartialAggregate = PartialAggregate::identity();
+    const TAG: &'static str = "CustomAggregator";
 
     type Input = RawData;
     type PartialAggregate = PartialAggregate;
@@ -18,6 +18,7 @@ pub struct PcoSumAggregator;
 
 impl Aggregator for PcoSumAggregator {
     const IDENTITY: Self::PartialAggregate = 0;
+    const TAG: &'static str = "PcoSumAggregator";
 
     type Input = u32;
     type PartialAggregate = u32;
@@ -55,6 +56,7 @@ pub struct BitPackingSumAggregator;
 
 impl Aggregator for BitPackingSumAggregator {
     const IDENTITY: Self::PartialAggregate = 0;
+    const TAG: &'static str = "BitPackingSumAggregator";
 
     type Input = u32;
     type PartialAggregate = u32;